@@ -7,6 +7,13 @@ fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
         let path = PathBuf::from(arg);
         println!("loading {}", path.display());
         let db = Database::from_file(&path)?;
+        for (first, second) in db.conflicting_species() {
+            println!(
+                "species {} and {} share a name, merge them",
+                first.latin, second.latin
+            );
+        }
+
         for post in db.posts.iter() {
             for file in &post.files {
                 if !file.full_path.exists() {
@@ -14,9 +21,19 @@ fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
                 }
             }
 
-            if let Some(name) = &post.species {
-                if db.species_by_latin(name).is_none() {
-                    println!("species {name} not found");
+            for name in post.all_species() {
+                if db.species_by_latin(name).is_some() {
+                    continue;
+                }
+
+                match db.species_by_synonym(name) {
+                    Some(species) => {
+                        println!(
+                            "species {name} not found, it was renamed to {}",
+                            species.latin
+                        )
+                    }
+                    None => println!("species {name} not found"),
                 }
             }
         }
@@ -0,0 +1,157 @@
+use crate::Database;
+use crate::Post;
+use crate::Species;
+
+/// Alt text proposed for photos of a post.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AltText {
+    pub pl: String,
+    pub en: String,
+}
+
+impl Database {
+    /// Generates alt text from the species and tags of the post, like
+    /// "Red fox (Vulpes vulpes): forest, snow".
+    pub fn default_alt_text(&self, post: &Post) -> AltText {
+        let species: Vec<_> = post
+            .all_species()
+            .filter_map(|latin| self.species_by_latin(latin))
+            .collect();
+
+        let mut tags_pl = Vec::new();
+        let mut tags_en = Vec::new();
+        for tag in post.tags.iter() {
+            if self.ignored_tags.contains(tag) {
+                continue;
+            }
+
+            match self.tag_translations.translate(tag) {
+                Some(other) if self.tag_translations.0.iter().any(|t| t.en == *tag) => {
+                    tags_en.push(tag.as_str());
+                    tags_pl.push(other.as_str());
+                }
+                Some(other) => {
+                    tags_en.push(other.as_str());
+                    tags_pl.push(tag.as_str());
+                }
+                None => {
+                    tags_en.push(tag.as_str());
+                    tags_pl.push(tag.as_str());
+                }
+            }
+        }
+
+        let compose = |name: fn(&Species) -> &str, tags: &[&str]| {
+            let subject = species
+                .iter()
+                .map(|species| match name(species) {
+                    "" => species.latin.to_string(),
+                    name => format!("{name} ({})", species.latin),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let tags = tags.join(", ");
+            let text = match (subject.is_empty(), tags.is_empty()) {
+                (false, false) => format!("{subject}: {tags}"),
+                (false, true) => subject,
+                _ => tags,
+            };
+
+            capitalize(&text)
+        };
+
+        AltText {
+            pl: compose(|species| &species.pl, &tags_pl),
+            en: compose(|species| &species.en, &tags_en),
+        }
+    }
+
+    /// Alt text of the n-th photo sent to platforms: English if given,
+    /// otherwise Polish, otherwise the generated one.
+    pub fn alt_text(&self, post: &Post, index: usize) -> String {
+        if let Some(file) = post.files.get(index) {
+            if !file.alt_en.is_empty() {
+                return file.alt_en.clone();
+            }
+
+            if !file.alt_pl.is_empty() {
+                return file.alt_pl.clone();
+            }
+        }
+
+        let default = self.default_alt_text(post);
+        if default.en.is_empty() {
+            default.pl
+        } else {
+            default.en
+        }
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FileMetadata;
+    use crate::TagList;
+    use crate::test_util::DatabaseBuilder;
+    use crate::test_util::latin;
+    use crate::test_util::red_fox;
+    use crate::test_util::species;
+
+    #[test]
+    fn test_alt_text() {
+        let mut db = DatabaseBuilder::new()
+            .species(red_fox())
+            .translation("forest", "las")
+            .ignored_tag("private")
+            .build();
+
+        let mut post = Post {
+            species: Some(latin("Vulpes vulpes")),
+            tags: TagList(vec![
+                "las".to_owned(),
+                "private".to_owned(),
+                "snow".to_owned(),
+            ]),
+            files: vec![FileMetadata::default(), FileMetadata::default()],
+            ..Post::default()
+        };
+
+        let default = db.default_alt_text(&post);
+        assert_eq!(default.pl, "Lis (Vulpes vulpes): las, snow");
+        assert_eq!(default.en, "Red fox (Vulpes vulpes): forest, snow");
+
+        post.files[1].alt_pl = "Lis przy norze".to_owned();
+        assert_eq!(db.alt_text(&post, 0), default.en);
+        assert_eq!(db.alt_text(&post, 1), "Lis przy norze");
+
+        db.add_species(&Species {
+            en: "hare".to_owned(),
+            ..species("Lepus europaeus")
+        });
+        db.refresh_caches();
+        post.other_species = vec![latin("Lepus europaeus")];
+        let default = db.default_alt_text(&post);
+        assert_eq!(
+            default.pl,
+            "Lis (Vulpes vulpes), Lepus europaeus: las, snow"
+        );
+        assert_eq!(
+            default.en,
+            "Red fox (Vulpes vulpes), hare (Lepus europaeus): forest, snow"
+        );
+
+        post.set_species_list(Vec::new());
+        post.tags = TagList::default();
+        assert_eq!(db.default_alt_text(&post), AltText::default());
+    }
+}
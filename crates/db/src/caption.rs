@@ -0,0 +1,698 @@
+//! Captions of posts, rendered from user-editable templates.
+//!
+//! A template is a text with placeholders:
+//!
+//! * `{{name}}` is replaced with the value of a variable, see [`Variable`];
+//! * `{{#if cond}}...{{else}}...{{/if}}` keeps the first part if the
+//!   condition holds, the second one otherwise; `{{else}}` is optional.
+//!   A condition is a variable, which holds when not empty, possibly
+//!   negated with `not` and combined with `and` and `or`, for instance
+//!   `{{#if pl or not latin}}`.
+//!
+//! A line containing nothing but `{{#if}}`, `{{else}}` or `{{/if}}` is
+//! dropped entirely, so blocks can be written one tag per line.
+
+use crate::Database;
+use crate::Latin;
+use crate::Post;
+use crate::Rank;
+use crate::edit_details::SocialMediaLink;
+use serde::Deserialize;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+pub const DEFAULT_NAME: &str = "default";
+
+/// Layout used before templates were introduced.
+pub const DEFAULT_TEMPLATE: &str = "\
+{{#if pl}}
+🇵🇱 {{pl}}
+{{/if}}
+{{#if en}}
+🇬🇧 {{en}}
+{{/if}}
+{{#if pl or en}}
+{{#if latin}}
+
+{{latin}}{{#if species_pl or species_en}} ({{#if species_pl}}🇵🇱 {{species_pl}}{{/if}}{{#if species_pl and species_en}} {{/if}}{{#if species_en}}🇬🇧 {{species_en}}{{/if}}){{/if}}
+{{/if}}
+{{else}}
+{{#if species_pl}}
+🇵🇱 {{species_pl}} ({{latin}})
+{{#if species_en}}
+🇬🇧 {{species_en}}
+{{/if}}
+{{else}}
+{{#if species_en}}
+🇬🇧 {{species_en}} ({{latin}})
+{{else}}
+{{#if latin}}
+{{latin}}
+{{/if}}
+{{/if}}
+{{/if}}
+{{/if}}
+{{#if pl or en or latin}}
+
+{{/if}}
+{{tags}}
+";
+
+/// Named caption template; platforms might use their own variant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptionTemplate {
+    pub name: String,
+    pub text: String,
+    #[serde(default)]
+    pub overrides: BTreeMap<SocialMediaLink, String>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct CaptionTemplateList {
+    /// Name of the template used for new captions
+    #[serde(default)]
+    selected: String,
+    #[serde(default)]
+    templates: Vec<CaptionTemplate>,
+}
+
+impl Default for CaptionTemplate {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_NAME.to_owned(),
+            text: DEFAULT_TEMPLATE.to_owned(),
+            overrides: BTreeMap::new(),
+        }
+    }
+}
+
+impl CaptionTemplate {
+    /// Source of the template used for the platform.
+    pub fn text_for(&self, platform: Option<SocialMediaLink>) -> &str {
+        platform
+            .and_then(|link| self.overrides.get(&link))
+            .unwrap_or(&self.text)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("name of a template cannot be empty".to_owned());
+        }
+
+        Template::parse(&self.text)?;
+        for (link, text) in &self.overrides {
+            Template::parse(text).map_err(|err| format!("{}: {err}", link.name()))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn render(
+        &self,
+        post: &Post,
+        db: &Database,
+        platform: Option<SocialMediaLink>,
+    ) -> Result<String, String> {
+        let template = Template::parse(self.text_for(platform))?;
+
+        Ok(template.render(&Values::new(post, db, platform)))
+    }
+}
+
+impl CaptionTemplateList {
+    /// The selected template; the built-in one if nothing was selected.
+    pub fn current(&self) -> Cow<'_, CaptionTemplate> {
+        match self.get(&self.selected) {
+            Some(template) => Cow::Borrowed(template),
+            None => self.get_or_default(DEFAULT_NAME),
+        }
+    }
+
+    pub fn selected(&self) -> &str {
+        if self.get(&self.selected).is_some() {
+            &self.selected
+        } else {
+            DEFAULT_NAME
+        }
+    }
+
+    pub fn select(&mut self, name: &str) -> bool {
+        if self.selected() == name {
+            return false;
+        }
+
+        self.selected = name.to_owned();
+
+        true
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CaptionTemplate> {
+        self.templates.iter().find(|item| item.name == name)
+    }
+
+    /// Finds the template, the built-in one can be modified by the user.
+    pub fn get_or_default(&self, name: &str) -> Cow<'_, CaptionTemplate> {
+        match self.get(name) {
+            Some(template) => Cow::Borrowed(template),
+            None => Cow::Owned(CaptionTemplate::default()),
+        }
+    }
+
+    /// Names of all templates, including the built-in one.
+    pub fn names(&self) -> Vec<&str> {
+        let mut result: Vec<&str> = self
+            .templates
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect();
+        if !result.contains(&DEFAULT_NAME) {
+            result.insert(0, DEFAULT_NAME);
+        }
+
+        result
+    }
+
+    /// Adds a new template or replaces the existing one.
+    pub fn save(&mut self, template: CaptionTemplate) -> Result<bool, String> {
+        template.validate()?;
+        match self
+            .templates
+            .iter_mut()
+            .find(|item| item.name == template.name)
+        {
+            Some(existing) if *existing == template => return Ok(false),
+            Some(existing) => *existing = template,
+            None => self.templates.push(template),
+        }
+
+        Ok(true)
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        let Some(index) = self.templates.iter().position(|item| item.name == name) else {
+            return false;
+        };
+
+        self.templates.remove(index);
+
+        true
+    }
+}
+
+/// Renders caption of the post using the selected template. A broken
+/// template falls back to the built-in one.
+pub fn render_caption(post: &Post, db: &Database, platform: Option<SocialMediaLink>) -> String {
+    let values = Values::new(post, db, platform);
+    let current = db.caption_templates.current();
+
+    match Template::parse(current.text_for(platform)) {
+        Ok(template) => template.render(&values),
+        Err(_) => Template::parse(DEFAULT_TEMPLATE).unwrap().render(&values),
+    }
+}
+
+// --------------------------------------------------
+
+/// Variables available in templates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variable {
+    Pl,
+    En,
+    Latin,
+    SpeciesPl,
+    SpeciesEn,
+    WikipediaPl,
+    WikipediaEn,
+    InsektariumPl,
+    Order,
+    Family,
+    OtherSpecies,
+    Tags,
+    Date,
+    FileCount,
+    Platform,
+}
+
+impl Variable {
+    pub const ALL: [Self; 15] = [
+        Self::Pl,
+        Self::En,
+        Self::Latin,
+        Self::SpeciesPl,
+        Self::SpeciesEn,
+        Self::WikipediaPl,
+        Self::WikipediaEn,
+        Self::InsektariumPl,
+        Self::Order,
+        Self::Family,
+        Self::OtherSpecies,
+        Self::Tags,
+        Self::Date,
+        Self::FileCount,
+        Self::Platform,
+    ];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Pl => "pl",
+            Self::En => "en",
+            Self::Latin => "latin",
+            Self::SpeciesPl => "species_pl",
+            Self::SpeciesEn => "species_en",
+            Self::WikipediaPl => "wikipedia_pl",
+            Self::WikipediaEn => "wikipedia_en",
+            Self::InsektariumPl => "insektarium_pl",
+            Self::Order => "order",
+            Self::Family => "family",
+            Self::OtherSpecies => "other_species",
+            Self::Tags => "tags",
+            Self::Date => "date",
+            Self::FileCount => "file_count",
+            Self::Platform => "platform",
+        }
+    }
+
+    pub const fn description(&self) -> &'static str {
+        match self {
+            Self::Pl => "Polish description",
+            Self::En => "English description",
+            Self::Latin => "Latin name of species",
+            Self::SpeciesPl => "Polish name of species",
+            Self::SpeciesEn => "English name of species",
+            Self::WikipediaPl => "link to Polish Wikipedia",
+            Self::WikipediaEn => "link to English Wikipedia",
+            Self::InsektariumPl => "link to insektarium.net",
+            Self::Order => "order of species",
+            Self::Family => "family of species",
+            Self::OtherSpecies => "Latin names of further species, comma separated",
+            Self::Tags => "hashtags, following the policy of the platform",
+            Self::Date => "date of the post",
+            Self::FileCount => "number of photos",
+            Self::Platform => "name of the platform, empty when copying",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|var| var.name() == name)
+    }
+}
+
+struct Values([String; Variable::ALL.len()]);
+
+impl Values {
+    fn new(post: &Post, db: &Database, platform: Option<SocialMediaLink>) -> Self {
+        let species = post
+            .species
+            .as_ref()
+            .and_then(|latin| db.species_by_latin(latin));
+
+        let tags: Vec<String> = db
+            .hashtags(post, platform)
+            .into_iter()
+            .map(|tag| format!("#{tag}"))
+            .collect();
+
+        let species_field = |f: fn(&crate::Species) -> &str| -> String {
+            species
+                .map(|species| f(species).to_owned())
+                .unwrap_or_default()
+        };
+
+        Self(Variable::ALL.map(|var| {
+            match var {
+                Variable::Pl => post.pl.clone(),
+                Variable::En => post.en.clone(),
+                Variable::Latin => species_field(|s| s.latin.as_str()),
+                Variable::SpeciesPl => species_field(|s| &s.pl),
+                Variable::SpeciesEn => species_field(|s| &s.en),
+                Variable::WikipediaPl => species_field(|s| &s.wikipedia_pl),
+                Variable::WikipediaEn => species_field(|s| &s.wikipedia_en),
+                Variable::InsektariumPl => species_field(|s| &s.insektarium_pl),
+                Variable::Order => species_field(|s| s.rank(Rank::Order).unwrap_or_default()),
+                Variable::Family => species_field(|s| s.rank(Rank::Family).unwrap_or_default()),
+                Variable::OtherSpecies => post
+                    .other_species
+                    .iter()
+                    .map(Latin::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                Variable::Tags => tags.join(" "),
+                Variable::Date => post.date.to_string(),
+                Variable::FileCount => post.files.len().to_string(),
+                Variable::Platform => platform
+                    .map(|link| link.name().to_owned())
+                    .unwrap_or_default(),
+            }
+        }))
+    }
+
+    fn get(&self, var: Variable) -> &str {
+        &self.0[var as usize]
+    }
+}
+
+/// Alternative of conjunctions of possibly negated variables.
+#[derive(Debug)]
+struct Condition(Vec<Vec<(bool, Variable)>>);
+
+impl Condition {
+    fn parse(s: &str) -> Result<Self, String> {
+        let mut alternatives = Vec::new();
+        for alternative in s.split(" or ") {
+            let mut conjunction = Vec::new();
+            for term in alternative.split(" and ") {
+                let term = term.trim();
+                let (negated, name) = match term.strip_prefix("not ") {
+                    Some(name) => (true, name.trim()),
+                    None => (false, term),
+                };
+
+                let var = Variable::from_name(name)
+                    .ok_or_else(|| format!("unknown variable '{name}' in condition"))?;
+                conjunction.push((negated, var));
+            }
+
+            alternatives.push(conjunction);
+        }
+
+        Ok(Self(alternatives))
+    }
+
+    fn eval(&self, values: &Values) -> bool {
+        self.0.iter().any(|conjunction| {
+            conjunction
+                .iter()
+                .all(|(negated, var)| values.get(*var).is_empty() == *negated)
+        })
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Variable(Variable),
+    If {
+        condition: Condition,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+}
+
+enum Tag {
+    Variable(Variable),
+    If(Condition),
+    Else,
+    EndIf,
+}
+
+#[derive(Debug)]
+struct Template(Vec<Node>);
+
+impl Template {
+    fn parse(s: &str) -> Result<Self, String> {
+        // stack of open blocks: nodes before the block, condition,
+        // the `then` part when `else` was already seen
+        let mut stack: Vec<(Vec<Node>, Condition, Option<Vec<Node>>)> = Vec::new();
+        let mut nodes = Vec::new();
+
+        for (lineno, line) in s.split_inclusive('\n').enumerate() {
+            let trimmed = line.trim();
+            let standalone = trimmed.starts_with("{{")
+                && trimmed.ends_with("}}")
+                && trimmed.matches("{{").count() == 1
+                && (trimmed.starts_with("{{#")
+                    || trimmed.starts_with("{{/")
+                    || trimmed == "{{else}}");
+            let line = if standalone { trimmed } else { line };
+
+            let mut rest = line;
+            while !rest.is_empty() {
+                let Some(start) = rest.find("{{") else {
+                    nodes.push(Node::Text(rest.to_owned()));
+                    break;
+                };
+
+                if start > 0 {
+                    nodes.push(Node::Text(rest[..start].to_owned()));
+                }
+
+                let Some(len) = rest[start..].find("}}") else {
+                    return Err(format!("line {}: unclosed {{{{", lineno + 1));
+                };
+
+                let tag = parse_tag(&rest[start + 2..start + len])
+                    .map_err(|err| format!("line {}: {err}", lineno + 1))?;
+                rest = &rest[start + len + 2..];
+
+                match tag {
+                    Tag::Variable(var) => nodes.push(Node::Variable(var)),
+                    Tag::If(condition) => {
+                        stack.push((std::mem::take(&mut nodes), condition, None));
+                    }
+                    Tag::Else => match stack.last_mut() {
+                        Some((_, _, then @ None)) => *then = Some(std::mem::take(&mut nodes)),
+                        Some(_) => {
+                            return Err(format!("line {}: duplicated {{{{else}}}}", lineno + 1));
+                        }
+                        None => {
+                            return Err(format!(
+                                "line {}: {{{{else}}}} outside of {{{{#if}}}}",
+                                lineno + 1
+                            ));
+                        }
+                    },
+                    Tag::EndIf => {
+                        let Some((before, condition, then)) = stack.pop() else {
+                            return Err(format!(
+                                "line {}: {{{{/if}}}} without {{{{#if}}}}",
+                                lineno + 1
+                            ));
+                        };
+
+                        let block = std::mem::replace(&mut nodes, before);
+                        let (then, otherwise) = match then {
+                            Some(then) => (then, block),
+                            None => (block, Vec::new()),
+                        };
+
+                        nodes.push(Node::If {
+                            condition,
+                            then,
+                            otherwise,
+                        });
+                    }
+                }
+            }
+        }
+
+        if !stack.is_empty() {
+            return Err("missing {{/if}}".to_owned());
+        }
+
+        Ok(Self(nodes))
+    }
+
+    fn render(&self, values: &Values) -> String {
+        let mut result = String::new();
+        render_nodes(&self.0, values, &mut result);
+
+        result.trim_end().to_owned()
+    }
+}
+
+fn parse_tag(tag: &str) -> Result<Tag, String> {
+    let tag = tag.trim();
+    if let Some(condition) = tag.strip_prefix("#if ") {
+        return Ok(Tag::If(Condition::parse(condition)?));
+    }
+
+    match tag {
+        "else" => Ok(Tag::Else),
+        "/if" => Ok(Tag::EndIf),
+        _ => Variable::from_name(tag)
+            .map(Tag::Variable)
+            .ok_or_else(|| format!("unknown variable '{tag}'")),
+    }
+}
+
+fn render_nodes(nodes: &[Node], values: &Values, result: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => result.push_str(text),
+            Node::Variable(var) => result.push_str(values.get(*var)),
+            Node::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                if condition.eval(values) {
+                    render_nodes(then, values, result);
+                } else {
+                    render_nodes(otherwise, values, result);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::TagList;
+    use crate::test_util::DatabaseBuilder;
+    use crate::test_util::red_fox;
+    use crate::test_util::species;
+
+    fn post(pl: &str, en: &str, species: Option<&str>, tags: &[&str]) -> Post {
+        let mut list = TagList::default();
+        for tag in tags {
+            list.add((*tag).to_owned());
+        }
+
+        Post {
+            pl: pl.to_owned(),
+            en: en.to_owned(),
+            species: species.map(|latin| Latin::from(latin.to_owned())),
+            tags: list,
+            ..Post::default()
+        }
+    }
+
+    #[test]
+    fn test_default_template() {
+        let db = DatabaseBuilder::new()
+            .species(red_fox())
+            .species(species("Bombus"))
+            .ignored_tag("private")
+            .build();
+
+        let caption = render_caption(
+            &post(
+                "Lis",
+                "Fox",
+                Some("Vulpes vulpes"),
+                &["fox", "private", "nature"],
+            ),
+            &db,
+            None,
+        );
+        assert_eq!(
+            caption,
+            "🇵🇱 Lis\n🇬🇧 Fox\n\nVulpes vulpes (🇵🇱 lis 🇬🇧 red fox)\n\n#fox #nature"
+        );
+
+        let caption = render_caption(&post("", "", Some("Vulpes vulpes"), &["fox"]), &db, None);
+        assert_eq!(caption, "🇵🇱 lis (Vulpes vulpes)\n🇬🇧 red fox\n\n#fox");
+
+        let caption = render_caption(&post("", "Bee", Some("Bombus"), &[]), &db, None);
+        assert_eq!(caption, "🇬🇧 Bee\n\nBombus");
+
+        let caption = render_caption(&post("", "", None, &["bee"]), &db, None);
+        assert_eq!(caption, "#bee");
+    }
+
+    #[test]
+    fn test_overrides() {
+        let mut db = DatabaseBuilder::new().build();
+        let mut template = CaptionTemplate {
+            name: "short".to_owned(),
+            text: "{{en}}{{#if not en}}{{pl}}{{/if}} [{{file_count}}]".to_owned(),
+            overrides: BTreeMap::new(),
+        };
+        template.overrides.insert(
+            SocialMediaLink::Instagram,
+            "{{platform}}: {{en}}\n{{tags}}".to_owned(),
+        );
+
+        assert!(db.caption_templates.save(template).unwrap());
+        assert!(db.caption_templates.select("short"));
+        assert_eq!(db.caption_templates.names(), vec![DEFAULT_NAME, "short"]);
+
+        let post = post("Lis", "", None, &["fox"]);
+        assert_eq!(render_caption(&post, &db, None), "Lis [0]");
+        assert_eq!(
+            render_caption(&post, &db, Some(SocialMediaLink::Instagram)),
+            "Instagram: \n#fox"
+        );
+
+        assert!(db.caption_templates.remove("short"));
+        assert_eq!(db.caption_templates.selected(), DEFAULT_NAME);
+    }
+
+    #[test]
+    fn test_taxonomy() {
+        let mut fox = red_fox();
+        fox.taxonomy.set(Rank::Order, "Carnivora");
+        fox.taxonomy.set(Rank::Family, "Canidae");
+        let mut db = DatabaseBuilder::new()
+            .species(fox)
+            .species(species("Bombus"))
+            .build();
+        let template = CaptionTemplate {
+            name: "taxa".to_owned(),
+            text: "{{latin}}{{#if family}} ({{order}}: {{family}}){{/if}}".to_owned(),
+            overrides: BTreeMap::new(),
+        };
+        assert!(db.caption_templates.save(template).unwrap());
+        assert!(db.caption_templates.select("taxa"));
+
+        let fox = post("", "", Some("Vulpes vulpes"), &[]);
+        assert_eq!(
+            render_caption(&fox, &db, None),
+            "Vulpes vulpes (Carnivora: Canidae)"
+        );
+        let bee = post("", "", Some("Bombus"), &[]);
+        assert_eq!(render_caption(&bee, &db, None), "Bombus");
+    }
+
+    #[test]
+    fn test_other_species() {
+        let mut db = DatabaseBuilder::new().species(red_fox()).build();
+        let template = CaptionTemplate {
+            name: "many".to_owned(),
+            text: "{{species_en}}{{#if other_species}} with {{other_species}}{{/if}}".to_owned(),
+            overrides: BTreeMap::new(),
+        };
+        assert!(db.caption_templates.save(template).unwrap());
+        assert!(db.caption_templates.select("many"));
+
+        let mut post = post("", "", Some("Vulpes vulpes"), &[]);
+        assert_eq!(render_caption(&post, &db, None), "red fox");
+
+        post.other_species = vec![
+            Latin::from("Bombus".to_owned()),
+            Latin::from("Apis mellifera".to_owned()),
+        ];
+        assert_eq!(
+            render_caption(&post, &db, None),
+            "red fox with Bombus, Apis mellifera"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Template::parse("{{#if pl}}\nx\n{{else}}\ny\n{{/if}}\n").is_ok());
+        assert_eq!(
+            Template::parse("{{name}}").unwrap_err(),
+            "line 1: unknown variable 'name'"
+        );
+        assert_eq!(
+            Template::parse("{{#if pl or foo}}").unwrap_err(),
+            "line 1: unknown variable 'foo' in condition"
+        );
+        assert_eq!(
+            Template::parse("{{#if pl}}\n{{pl}}").unwrap_err(),
+            "missing {{/if}}"
+        );
+        assert_eq!(
+            Template::parse("x\n{{/if}}").unwrap_err(),
+            "line 2: {{/if}} without {{#if}}"
+        );
+        assert_eq!(
+            Template::parse("{{#if pl}}{{else}}{{else}}{{/if}}").unwrap_err(),
+            "line 1: duplicated {{else}}"
+        );
+        assert_eq!(Template::parse("{{pl").unwrap_err(), "line 1: unclosed {{");
+    }
+}
@@ -1,3 +1,7 @@
+use chrono::Datelike;
+use chrono::Local;
+use chrono::NaiveDate;
+use chrono::TimeDelta;
 use serde::Deserialize;
 use serde::Serialize;
 use std::fmt::Display;
@@ -58,6 +62,44 @@ impl FromStr for Date {
     }
 }
 
+impl Date {
+    pub fn today() -> Self {
+        Self::from_naive(Local::now().date_naive())
+    }
+
+    pub fn from_naive(date: NaiveDate) -> Self {
+        Self {
+            year: date.year() as Year,
+            month: Month(date.month() as u8),
+            day: Day(date.day() as u8),
+        }
+    }
+
+    /// Converts to the chrono date; fails for non-existing days, like 02-30.
+    pub fn to_naive(&self) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(
+            self.year as i32,
+            self.month.as_u8() as u32,
+            self.day.as_u8() as u32,
+        )
+    }
+
+    /// Returns the ISO 8601 year and week number.
+    pub fn iso_week(&self) -> Option<(Year, u8)> {
+        let week = self.to_naive()?.iso_week();
+
+        Some((week.year() as Year, week.week() as u8))
+    }
+
+    /// Moves the date by the given number of days.
+    pub fn add_days(&self, days: i64) -> Option<Self> {
+        let date = self.to_naive()?;
+        let date = date.checked_add_signed(TimeDelta::days(days))?;
+
+        Some(Self::from_naive(date))
+    }
+}
+
 impl Ord for Date {
     fn cmp(&self, other: &Date) -> std::cmp::Ordering {
         fn as_tuple(d: &Date) -> (u16, u8, u8) {
@@ -1,26 +1,62 @@
 use crate::Database;
 use crate::Latin;
+use crate::Location;
 use crate::Post;
 use crate::PostId;
+use crate::Schedule;
 use crate::TagList;
 use crate::post::PublishedState;
+use crate::publication::PlatformStatus;
+use serde::Deserialize;
+use serde::Serialize;
 
 #[derive(Clone)]
 pub enum EditDetails {
     SetPublished(PostId, PublishedState),
     Example(PostId, bool),
+    /// Marks the n-th photo as an example
+    FileExample(PostId, usize, bool),
     SetPolish(PostId, String),
     SetEnglish(PostId, String),
     SetTags(PostId, TagList),
+    /// Replaces all species with the one given
     SetSpecies(PostId, Option<Latin>),
+    /// Sets all species, the first one is the primary
+    SetSpeciesList(PostId, Vec<Latin>),
     SetSocialMediaLink(PostId, String, SocialMediaLink),
+    SetPlatformStatus(PostId, SocialMediaLink, PlatformStatus),
+    SetSchedule(PostId, Option<Schedule>),
+    /// Polish and English alt text of the n-th photo
+    SetAltText(PostId, usize, String, String),
+    SetLocation(PostId, Option<Location>),
     Undo(PostId),
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SocialMediaLink {
     Facebook,
     Instagram,
+    Mastodon,
+    Bluesky,
+}
+
+impl SocialMediaLink {
+    pub const ALL: [SocialMediaLink; 4] = [
+        Self::Facebook,
+        Self::Instagram,
+        Self::Mastodon,
+        Self::Bluesky,
+    ];
+
+    pub const fn name(&self) -> &str {
+        match self {
+            Self::Facebook => "Facebook",
+            Self::Instagram => "Instagram",
+            Self::Mastodon => "Mastodon",
+            Self::Bluesky => "Bluesky",
+        }
+    }
 }
 
 impl EditDetails {
@@ -28,11 +64,17 @@ impl EditDetails {
         match self {
             Self::SetPublished(id, _)
             | Self::Example(id, _)
+            | Self::FileExample(id, _, _)
             | Self::SetPolish(id, _)
             | Self::SetEnglish(id, _)
             | Self::SetTags(id, _)
             | Self::SetSpecies(id, _)
+            | Self::SetSpeciesList(id, _)
             | Self::SetSocialMediaLink(id, _, _)
+            | Self::SetPlatformStatus(id, _, _)
+            | Self::SetSchedule(id, _)
+            | Self::SetAltText(id, _, _, _)
+            | Self::SetLocation(id, _)
             | Self::Undo(id) => *id,
         }
     }
@@ -87,12 +129,25 @@ pub fn apply_aux(action: EditDetails, post: &mut Post) -> Option<EditDetails> {
                 None
             }
         }
-        EditDetails::SetSpecies(id, maybe_species) => {
-            if post.species != maybe_species {
-                let prev = post.species.clone();
-                post.species = maybe_species;
+        EditDetails::FileExample(id, index, flag) => {
+            let file = post.files.get_mut(index)?;
+            if file.is_example != flag {
+                file.is_example = flag;
 
-                Some(EditDetails::SetSpecies(id, prev))
+                Some(EditDetails::FileExample(id, index, !flag))
+            } else {
+                None
+            }
+        }
+        EditDetails::SetSpecies(id, maybe_species) => apply_aux(
+            EditDetails::SetSpeciesList(id, maybe_species.into_iter().collect()),
+            post,
+        ),
+        EditDetails::SetSpeciesList(id, list) => {
+            let prev = post.species_list();
+            post.set_species_list(list);
+            if post.species_list() != prev {
+                Some(EditDetails::SetSpeciesList(id, prev))
             } else {
                 None
             }
@@ -128,19 +183,52 @@ pub fn apply_aux(action: EditDetails, post: &mut Post) -> Option<EditDetails> {
             }
         }
         EditDetails::SetSocialMediaLink(id, url, sml) => {
-            let prev = match sml {
-                SocialMediaLink::Facebook => &post.social_media.facebook_url,
-                SocialMediaLink::Instagram => &post.social_media.instagram_url,
-            };
-
-            if url != *prev {
-                let prev = prev.clone();
-                match sml {
-                    SocialMediaLink::Facebook => post.social_media.facebook_url = url,
-                    SocialMediaLink::Instagram => post.social_media.instagram_url = url,
-                };
-
-                Some(EditDetails::SetSocialMediaLink(id, prev, sml))
+            if url != post.platforms.url(sml) {
+                let prev = post.platforms.get(sml).cloned().unwrap_or_default();
+                post.platforms.set_url(sml, url);
+
+                Some(EditDetails::SetPlatformStatus(id, sml, prev))
+            } else {
+                None
+            }
+        }
+        EditDetails::SetSchedule(id, schedule) => {
+            if post.schedule != schedule {
+                let prev = post.schedule.clone();
+                post.schedule = schedule;
+
+                Some(EditDetails::SetSchedule(id, prev))
+            } else {
+                None
+            }
+        }
+        EditDetails::SetAltText(id, index, pl, en) => {
+            let file = post.files.get_mut(index)?;
+            if file.alt_pl != pl || file.alt_en != en {
+                let prev_pl = std::mem::replace(&mut file.alt_pl, pl);
+                let prev_en = std::mem::replace(&mut file.alt_en, en);
+
+                Some(EditDetails::SetAltText(id, index, prev_pl, prev_en))
+            } else {
+                None
+            }
+        }
+        EditDetails::SetLocation(id, location) => {
+            if post.location != location {
+                let prev = post.location;
+                post.location = location;
+
+                Some(EditDetails::SetLocation(id, prev))
+            } else {
+                None
+            }
+        }
+        EditDetails::SetPlatformStatus(id, sml, status) => {
+            let prev = post.platforms.get(sml).cloned().unwrap_or_default();
+            if status != prev {
+                post.platforms.set(sml, status);
+
+                Some(EditDetails::SetPlatformStatus(id, sml, prev))
             } else {
                 None
             }
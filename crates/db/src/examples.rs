@@ -0,0 +1,207 @@
+//! Example photos of species: all files of posts marked as examples and
+//! single files marked on their own.
+//!
+//! The pinned cover and the files arranged by hand are stored with the
+//! species; the remaining files follow in the order of posts. The selected
+//! example is not part of the database, the GUI keeps it between sessions.
+
+use crate::Database;
+use crate::FileMetadata;
+use crate::Latin;
+use crate::Species;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExampleOrder {
+    /// File shown first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cover: Option<PathBuf>,
+    /// Files arranged by hand, shown after the cover
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub order: Vec<PathBuf>,
+}
+
+impl ExampleOrder {
+    pub fn is_empty(&self) -> bool {
+        self.cover.is_none() && self.order.is_empty()
+    }
+
+    pub fn is_cover(&self, path: &Path) -> bool {
+        self.cover.as_deref() == Some(path)
+    }
+
+    /// Sorts the files: the cover, the arranged ones and then the rest.
+    pub fn arrange(&self, files: &mut [FileMetadata]) {
+        files.sort_by_key(|file| self.position(&file.rel_path));
+    }
+
+    fn position(&self, path: &Path) -> (u8, usize) {
+        if self.is_cover(path) {
+            return (0, 0);
+        }
+
+        match self.order.iter().position(|entry| entry == path) {
+            Some(index) => (1, index),
+            None => (2, 0),
+        }
+    }
+
+    /// Moves the file by the offset within already arranged files; all of
+    /// them get a fixed position, files which are gone are forgotten.
+    pub fn move_file(&mut self, files: &[FileMetadata], index: usize, offset: isize) {
+        let Some(target) = index
+            .checked_add_signed(offset)
+            .filter(|target| *target < files.len())
+        else {
+            return;
+        };
+
+        let mut paths: Vec<PathBuf> = files.iter().map(|file| file.rel_path.clone()).collect();
+        paths.swap(index, target);
+        paths.retain(|path| !self.is_cover(path));
+        self.order = paths;
+    }
+}
+
+impl Species {
+    /// Arranges the examples and selects the previously selected one.
+    pub(crate) fn set_examples(&mut self, mut examples: Vec<FileMetadata>) {
+        self.example_order.arrange(&mut examples);
+        self.examples = examples;
+        self.current_example = self
+            .selected_example
+            .as_ref()
+            .and_then(|path| self.examples.iter().position(|file| file.rel_path == *path))
+            .unwrap_or_default();
+    }
+
+    pub fn select_example(&mut self, index: usize) {
+        if let Some(file) = self.examples.get(index) {
+            self.current_example = index;
+            self.selected_example = Some(file.rel_path.clone());
+        }
+    }
+}
+
+impl Database {
+    /// Selected example of each species where it is not the first one.
+    pub fn example_selection(&self) -> BTreeMap<Latin, PathBuf> {
+        self.species
+            .iter()
+            .filter(|species| species.current_example > 0)
+            .filter_map(|species| {
+                let file = species.examples.get(species.current_example)?;
+                Some((species.latin.clone(), file.rel_path.clone()))
+            })
+            .collect()
+    }
+
+    pub fn restore_example_selection(&mut self, mut selection: BTreeMap<Latin, PathBuf>) {
+        for species in self.species.iter_mut() {
+            let index = selection.remove(&species.latin).and_then(|path| {
+                species
+                    .examples
+                    .iter()
+                    .position(|file| file.rel_path == path)
+            });
+            if let Some(index) = index {
+                species.select_example(index);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Post;
+    use crate::PostId;
+    use crate::SpeciesId;
+    use crate::edit_details::EditDetails;
+    use crate::edit_details::apply;
+
+    fn file(path: &str) -> FileMetadata {
+        FileMetadata {
+            rel_path: PathBuf::from(path),
+            ..FileMetadata::default()
+        }
+    }
+
+    fn paths(species: &Species) -> Vec<&str> {
+        species
+            .examples
+            .iter()
+            .map(|file| file.rel_path.to_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_examples() {
+        let mut db = Database::default();
+        let fox = Latin::from("Vulpes vulpes".to_owned());
+        db.add_species(&Species {
+            latin: fox.clone(),
+            ..Species::default()
+        });
+        db.posts.push(Post {
+            id: PostId(0),
+            species: Some(fox.clone()),
+            files: vec![file("a.jpg"), file("b.jpg")],
+            is_example: true,
+            ..Post::default()
+        });
+        db.posts.push(Post {
+            id: PostId(1),
+            species: Some(fox.clone()),
+            files: vec![file("c.jpg"), file("d.jpg")],
+            ..Post::default()
+        });
+        db.refresh_caches();
+        assert_eq!(paths(&db.species[0]), vec!["a.jpg", "b.jpg"]);
+
+        // a single file of the other post
+        apply(EditDetails::FileExample(PostId(1), 1, true), &mut db);
+        db.refresh_caches();
+        assert_eq!(paths(&db.species[0]), vec!["a.jpg", "b.jpg", "d.jpg"]);
+
+        let mut species = db.species[0].clone();
+        species.example_order.cover = Some(PathBuf::from("d.jpg"));
+        db.update_species(&species);
+        db.refresh_caches();
+        assert_eq!(paths(&db.species[0]), vec!["d.jpg", "a.jpg", "b.jpg"]);
+
+        let mut species = db.species[0].clone();
+        let examples = species.examples.clone();
+        species.example_order.move_file(&examples, 2, -1);
+        assert_eq!(
+            species.example_order.order,
+            vec![PathBuf::from("b.jpg"), PathBuf::from("a.jpg")]
+        );
+        db.update_species(&species);
+        db.refresh_caches();
+        assert_eq!(paths(&db.species[0]), vec!["d.jpg", "b.jpg", "a.jpg"]);
+
+        // the selection follows the file, not the position
+        db.species_mut_by_id(&SpeciesId(0))
+            .unwrap()
+            .select_example(2);
+        let selection = db.example_selection();
+        assert_eq!(selection.get(&fox), Some(&PathBuf::from("a.jpg")));
+
+        let mut species = db.species[0].clone();
+        species.example_order.order.clear();
+        db.update_species(&species);
+        db.refresh_caches();
+        assert_eq!(db.species[0].current_example, 1);
+
+        db.species_mut_by_id(&SpeciesId(0))
+            .unwrap()
+            .select_example(0);
+        db.restore_example_selection(selection);
+        assert_eq!(db.species[0].current_example, 1);
+    }
+}
@@ -0,0 +1,287 @@
+use crate::Database;
+use crate::Post;
+use crate::edit_details::SocialMediaLink;
+use crate::text::fold;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Instagram silently drops posts exceeding this limit.
+pub const INSTAGRAM_MAX_HASHTAGS: usize = 30;
+
+/// Which tags and in what order end up in a caption.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashtagPolicy {
+    #[serde(default)]
+    pub max_count: Option<usize>,
+    #[serde(default)]
+    pub order: HashtagOrder,
+    #[serde(default)]
+    pub language: HashtagLanguage,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashtagOrder {
+    /// As entered by the user
+    #[default]
+    Entered,
+    /// Species names first, then tags from tag groups, then the rest
+    Priority,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashtagLanguage {
+    /// Tags as entered
+    #[default]
+    Any,
+    /// Translated tags in English, untranslated ones as entered
+    En,
+    /// Translated tags in Polish, untranslated ones as entered
+    Pl,
+    /// Translated tags in both languages
+    Both,
+}
+
+impl HashtagOrder {
+    pub const ALL: [Self; 2] = [Self::Entered, Self::Priority];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Entered => "as entered",
+            Self::Priority => "species and groups first",
+        }
+    }
+}
+
+impl HashtagLanguage {
+    pub const ALL: [Self; 4] = [Self::Any, Self::En, Self::Pl, Self::Both];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Any => "any language",
+            Self::En => "English",
+            Self::Pl => "Polish",
+            Self::Both => "both languages",
+        }
+    }
+}
+
+impl HashtagPolicy {
+    /// Policy used when the user has not set one for the platform.
+    pub fn default_for(platform: Option<SocialMediaLink>) -> Self {
+        match platform {
+            Some(SocialMediaLink::Instagram) => Self {
+                max_count: Some(INSTAGRAM_MAX_HASHTAGS),
+                ..Self::default()
+            },
+            _ => Self::default(),
+        }
+    }
+}
+
+impl Database {
+    pub fn hashtag_policy(&self, platform: Option<SocialMediaLink>) -> HashtagPolicy {
+        platform
+            .and_then(|link| self.hashtag_policies.get(&link))
+            .cloned()
+            .unwrap_or_else(|| HashtagPolicy::default_for(platform))
+    }
+
+    pub fn set_hashtag_policy(&mut self, link: SocialMediaLink, policy: HashtagPolicy) {
+        if self.hashtag_policy(Some(link)) == policy {
+            return;
+        }
+
+        if policy == HashtagPolicy::default_for(Some(link)) {
+            self.hashtag_policies.remove(&link);
+        } else {
+            self.hashtag_policies.insert(link, policy);
+        }
+        self.current_version.hashtag_policies += 1;
+    }
+
+    /// Hashtags of the post, without `#`, following the policy of the
+    /// platform; ignored tags are skipped.
+    pub fn hashtags(&self, post: &Post, platform: Option<SocialMediaLink>) -> Vec<String> {
+        let policy = self.hashtag_policy(platform);
+
+        let mut tags: Vec<&String> = post
+            .tags
+            .iter()
+            .filter(|tag| !self.ignored_tags.contains(*tag))
+            .collect();
+
+        if policy.order == HashtagOrder::Priority {
+            let species: Vec<String> = post
+                .all_species()
+                .filter_map(|latin| self.species_by_latin(latin))
+                .flat_map(|species| {
+                    [species.latin.as_str(), &species.pl, &species.en]
+                        .into_iter()
+                        .filter_map(normalize)
+                })
+                .collect();
+
+            // groups might list the tag in the other language
+            let group = |tag: &String| {
+                let translation = self.tag_translations.translate(tag);
+                self.tag_groups
+                    .iter()
+                    .filter(|group| group.enabled)
+                    .position(|group| {
+                        group
+                            .tags
+                            .iter()
+                            .any(|item| item == tag || Some(item) == translation)
+                    })
+            };
+
+            tags.sort_by_cached_key(|tag| {
+                let is_species = normalize(tag).is_some_and(|tag| species.contains(&tag));
+                (!is_species, group(tag).unwrap_or(usize::MAX))
+            });
+        }
+
+        let mut result: Vec<String> = Vec::new();
+        for tag in tags {
+            let translation = self.tag_translations.translate(tag);
+            let is_en = self.tag_translations.0.iter().any(|trans| trans.en == *tag);
+            let (en, pl) = match translation {
+                Some(other) if is_en => (Some(tag), Some(other)),
+                Some(other) => (Some(other), Some(tag)),
+                None => (None, None),
+            };
+
+            let variants = match (policy.language, en, pl) {
+                (HashtagLanguage::En, Some(en), _) => vec![en],
+                (HashtagLanguage::Pl, _, Some(pl)) => vec![pl],
+                (HashtagLanguage::Both, Some(en), Some(pl)) => vec![en, pl],
+                _ => vec![tag],
+            };
+
+            for variant in variants {
+                if let Some(hashtag) = normalize(variant)
+                    && !result.contains(&hashtag)
+                {
+                    result.push(hashtag);
+                }
+            }
+        }
+
+        if let Some(max_count) = policy.max_count {
+            result.truncate(max_count);
+        }
+
+        result
+    }
+}
+
+/// Converts a tag into a valid hashtag: lower case, without diacritics,
+/// spaces and punctuation. Returns `None` if nothing valid is left.
+pub fn normalize(tag: &str) -> Option<String> {
+    let result: String = fold(tag.trim_start_matches('#'))
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '_')
+        .collect();
+
+    if result.is_empty() {
+        return None;
+    }
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::TagGroup;
+    use crate::TagList;
+    use crate::test_util::DatabaseBuilder;
+    use crate::test_util::latin;
+    use crate::test_util::red_fox;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("Żółw błotny").as_deref(), Some("zolwblotny"));
+        assert_eq!(normalize("#red-fox").as_deref(), Some("redfox"));
+        assert_eq!(normalize("macro_photo").as_deref(), Some("macro_photo"));
+        assert_eq!(normalize("2025").as_deref(), Some("2025"));
+        assert_eq!(normalize("--"), None);
+    }
+
+    #[test]
+    fn test_hashtags() {
+        let mut db = DatabaseBuilder::new()
+            .species(red_fox())
+            .translation("forest", "las")
+            .translation("red fox", "lis")
+            .ignored_tag("private")
+            .build();
+        db.tag_groups
+            .add(TagGroup {
+                name: "places".to_owned(),
+                enabled: true,
+                tags: TagList(vec!["forest".to_owned()]),
+                ..TagGroup::default()
+            })
+            .unwrap();
+        db.refresh_caches();
+
+        let tags = ["nature", "private", "las", "2025", "red fox", "Łąka"];
+        let post = Post {
+            species: Some(latin("Vulpes vulpes")),
+            tags: TagList(tags.iter().map(|tag| (*tag).to_owned()).collect()),
+            ..Post::default()
+        };
+
+        assert_eq!(
+            db.hashtags(&post, None),
+            vec!["nature", "las", "2025", "redfox", "laka"]
+        );
+
+        db.set_hashtag_policy(
+            SocialMediaLink::Mastodon,
+            HashtagPolicy {
+                max_count: Some(3),
+                order: HashtagOrder::Priority,
+                language: HashtagLanguage::En,
+            },
+        );
+        assert_eq!(
+            db.hashtags(&post, Some(SocialMediaLink::Mastodon)),
+            vec!["redfox", "forest", "nature"]
+        );
+
+        db.set_hashtag_policy(
+            SocialMediaLink::Mastodon,
+            HashtagPolicy {
+                language: HashtagLanguage::Both,
+                ..HashtagPolicy::default()
+            },
+        );
+        assert_eq!(
+            db.hashtags(&post, Some(SocialMediaLink::Mastodon)),
+            vec!["nature", "forest", "las", "2025", "redfox", "lis", "laka"]
+        );
+
+        db.set_hashtag_policy(SocialMediaLink::Mastodon, HashtagPolicy::default());
+        assert!(db.hashtag_policies.is_empty());
+    }
+
+    #[test]
+    fn test_instagram_limit() {
+        let db = DatabaseBuilder::new().build();
+
+        let post = Post {
+            tags: TagList((0..40).map(|i| format!("tag{i}")).collect()),
+            ..Post::default()
+        };
+
+        assert_eq!(db.hashtags(&post, None).len(), 40);
+        assert_eq!(
+            db.hashtags(&post, Some(SocialMediaLink::Instagram)).len(),
+            INSTAGRAM_MAX_HASHTAGS
+        );
+    }
+}
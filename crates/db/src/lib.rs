@@ -1,23 +1,55 @@
+mod alt_text;
+pub mod caption;
 mod date;
 pub mod edit_details;
 pub mod edit_tags;
+mod examples;
+pub mod hashtags;
+mod life_list;
+mod merge;
+mod observation;
 mod post;
+mod publication;
+pub mod query;
+mod rename;
+mod saved_search;
+pub mod schedule;
 mod search_parts;
 mod species;
 mod tag_group;
 mod tag_hints;
 mod tag_list;
 mod tag_translations;
-
+mod taxonomy;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+pub mod text;
+
+pub use alt_text::AltText;
+pub use caption::CaptionTemplate;
+pub use caption::CaptionTemplateList;
+pub use caption::render_caption;
 pub use date::Date;
 pub use date::Day;
 pub use date::Month;
 pub use date::Year;
+pub use examples::ExampleOrder;
+pub use hashtags::HashtagPolicy;
+pub use life_list::LifeRecord;
+pub use observation::Location;
+pub use observation::ObservationSettings;
 pub use post::FileMetadata;
 pub use post::Post;
 pub use post::PublishedState;
-pub use post::SocialMediaState;
-pub use post::render_text;
+pub use publication::PlatformState;
+pub use publication::PlatformStatus;
+pub use publication::Platforms;
+pub use rename::Rename;
+pub use saved_search::SavedSearch;
+pub use saved_search::SavedSearchList;
+pub use saved_search::Source;
+pub use schedule::Schedule;
+pub use search_parts::PartKind;
 pub use search_parts::SearchParts;
 pub use species::Latin;
 pub use species::Species;
@@ -28,7 +60,12 @@ pub use tag_list::TagList;
 pub use tag_translations::TagTranslations;
 pub use tag_translations::TranslatedTag;
 pub use tag_translations::Translation;
+pub use taxonomy::Rank;
+pub use taxonomy::TaxonNode;
+pub use taxonomy::Taxonomy;
 
+use crate::edit_details::SocialMediaLink;
+use crate::query::Query;
 use crate::tag_hints::Builder;
 use crate::tag_hints::TagHints;
 use chrono::DateTime;
@@ -41,6 +78,7 @@ use serde::Serialize;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
@@ -51,6 +89,7 @@ pub use picture_views::PictureViews;
 use picture_views::PictureViewsBuilder;
 
 mod selector;
+pub use selector::Season;
 pub use selector::Selector;
 
 // --------------------------------------------------
@@ -66,6 +105,18 @@ pub struct Database {
     pub tag_groups: TagGroupList,
     pub ignored_tags: BTreeSet<String>,
 
+    #[serde(default)]
+    pub saved_searches: SavedSearchList,
+
+    #[serde(default)]
+    pub caption_templates: CaptionTemplateList,
+
+    #[serde(default)]
+    pub hashtag_policies: BTreeMap<SocialMediaLink, HashtagPolicy>,
+
+    #[serde(default)]
+    pub observation_settings: ObservationSettings,
+
     #[serde(skip)]
     pub rootpath: PathBuf,
 
@@ -78,12 +129,21 @@ pub struct Database {
     #[serde(skip)]
     tags_views: BTreeMap<Selector, TranslatedTagsView>,
 
+    #[serde(skip)]
+    saved_search_views: HashMap<String, Vec<PostId>>,
+
     #[serde(skip)]
     latin2id: BTreeMap<Latin, SpeciesId>,
 
+    #[serde(skip)]
+    first_records: HashSet<PostId>,
+
     #[serde(skip)]
     pub tag_hints: TagHints,
 
+    #[serde(skip)]
+    renames: Vec<rename::Applied>,
+
     #[serde(skip)]
     saved_version: Version,
 
@@ -102,28 +162,42 @@ pub struct Version {
     pub tag_groups: u64,
     pub tag_translations: u64,
     pub ignored_tags: u64,
+    pub saved_searches: u64,
+    pub caption_templates: u64,
+    pub hashtag_policies: u64,
+    pub observation_settings: u64,
 }
 
 struct CacheVersion {
     picture_views: u64,
-    species_examples: u64,
+    species_examples_posts: u64,
+    species_examples_species: u64,
     tags_views_posts: u64,
     tags_views_tag_translations: u64,
     tags_views_tag_groups: u64,
     tag_hints: u64,
     latin2id: u64,
+    first_records: u64,
+    saved_search_views_posts: u64,
+    saved_search_views_species: u64,
+    saved_search_views_searches: u64,
 }
 
 impl Default for CacheVersion {
     fn default() -> Self {
         Self {
             picture_views: u64::MAX,
-            species_examples: u64::MAX,
+            species_examples_posts: u64::MAX,
+            species_examples_species: u64::MAX,
             tags_views_posts: u64::MAX,
             tags_views_tag_translations: u64::MAX,
             tags_views_tag_groups: u64::MAX,
             tag_hints: u64::MAX,
             latin2id: u64::MAX,
+            first_records: u64::MAX,
+            saved_search_views_posts: u64::MAX,
+            saved_search_views_species: u64::MAX,
+            saved_search_views_searches: u64::MAX,
         }
     }
 }
@@ -211,6 +285,7 @@ impl Database {
 
         let mut entry = Species {
             id: SpeciesId(id),
+            latin: data.latin.clone(),
             ..Species::default()
         };
         entry.update(data);
@@ -248,13 +323,17 @@ impl Database {
     }
 
     pub fn is_dirty(&self) -> bool {
-        const fn mktuple(v: &Version) -> (u64, u64, u64, u64, u64) {
+        const fn mktuple(v: &Version) -> (u64, u64, u64, u64, u64, u64, u64, u64, u64) {
             (
                 v.photos,
                 v.species,
                 v.tag_groups,
                 v.tag_translations,
                 v.ignored_tags,
+                v.saved_searches,
+                v.caption_templates,
+                v.hashtag_policies,
+                v.observation_settings,
             )
         }
 
@@ -323,6 +402,59 @@ impl Database {
         }
     }
 
+    pub fn add_saved_search(&mut self, search: SavedSearch) -> Result<(), String> {
+        self.saved_searches.add(search)?;
+        self.current_version.saved_searches += 1;
+
+        Ok(())
+    }
+
+    pub fn update_saved_search(&mut self, name: &str, search: SavedSearch) -> Result<(), String> {
+        if self.saved_searches.update(name, search)? {
+            self.current_version.saved_searches += 1;
+        }
+
+        Ok(())
+    }
+
+    pub fn remove_saved_search(&mut self, name: &str) {
+        if self.saved_searches.remove(name) {
+            self.current_version.saved_searches += 1;
+        }
+    }
+
+    pub fn save_caption_template(&mut self, template: CaptionTemplate) -> Result<(), String> {
+        if self.caption_templates.save(template)? {
+            self.current_version.caption_templates += 1;
+        }
+
+        Ok(())
+    }
+
+    pub fn remove_caption_template(&mut self, name: &str) {
+        if self.caption_templates.remove(name) {
+            self.current_version.caption_templates += 1;
+        }
+    }
+
+    pub fn select_caption_template(&mut self, name: &str) {
+        if self.caption_templates.select(name) {
+            self.current_version.caption_templates += 1;
+        }
+    }
+
+    pub fn set_observation_settings(&mut self, settings: ObservationSettings) {
+        if self.observation_settings != settings {
+            self.observation_settings = settings;
+            self.current_version.observation_settings += 1;
+        }
+    }
+
+    /// Returns posts matching the saved search, ordered by date.
+    pub fn saved_search_view(&self, name: &str) -> Option<&Vec<PostId>> {
+        self.saved_search_views.get(name)
+    }
+
     pub fn move_group_up(&mut self, id: &TagGroupId) {
         if self.tag_groups.move_up(id) {
             self.current_version.tag_groups += 1;
@@ -359,35 +491,32 @@ impl Database {
     }
 
     fn refresh_species_examples(&mut self) {
-        if self.cache_versions.species_examples == self.current_version.posts {
+        let cv = &mut self.cache_versions;
+        if cv.species_examples_posts == self.current_version.posts
+            && cv.species_examples_species == self.current_version.species
+        {
             return;
         }
 
-        self.cache_versions.species_examples = self.current_version.posts;
+        cv.species_examples_posts = self.current_version.posts;
+        cv.species_examples_species = self.current_version.species;
 
         let mut tmp = HashMap::<Latin, Vec<FileMetadata>>::new();
-        for post in self
-            .posts
-            .iter()
-            .filter(|post| post.is_example)
-            .filter(|post| post.species.is_some())
-        {
-            let species = post.species.as_ref().unwrap();
-            tmp.entry(species.clone())
-                .and_modify(|list| {
-                    for file in &post.files {
-                        list.push(file.clone());
-                    }
-                })
-                .or_insert_with(|| post.files.clone());
+        for post in self.posts.iter() {
+            let files = post
+                .files
+                .iter()
+                .filter(|file| post.is_example || file.is_example);
+            for file in files {
+                for species in post.all_species() {
+                    tmp.entry(species.clone()).or_default().push(file.clone());
+                }
+            }
         }
 
         for species in self.species.iter_mut() {
-            if let Some(examples) = tmp.remove(&species.latin) {
-                species.examples = examples;
-            } else {
-                species.examples.clear();
-            }
+            let examples = tmp.remove(&species.latin).unwrap_or_default();
+            species.set_examples(examples);
         }
     }
 
@@ -405,6 +534,42 @@ impl Database {
         self.refresh_tag_hints();
         self.refresh_latin2id();
         self.refresh_species_examples();
+        self.refresh_first_records();
+        self.refresh_saved_search_views();
+    }
+
+    fn refresh_saved_search_views(&mut self) {
+        let cv = &mut self.cache_versions;
+        if cv.saved_search_views_posts == self.current_version.posts
+            && cv.saved_search_views_species == self.current_version.species
+            && cv.saved_search_views_searches == self.current_version.saved_searches
+        {
+            return;
+        }
+
+        cv.saved_search_views_posts = self.current_version.posts;
+        cv.saved_search_views_species = self.current_version.species;
+        cv.saved_search_views_searches = self.current_version.saved_searches;
+
+        let mut posts: Vec<&Post> = self.posts.iter().collect();
+        posts.sort_by_key(|post| post.date);
+
+        let mut views = HashMap::new();
+        for search in self.saved_searches.iter() {
+            let Ok(query) = Query::parse(&search.query) else {
+                continue;
+            };
+
+            let view = posts
+                .iter()
+                .filter(|post| query.matches(post, self))
+                .map(|post| post.id)
+                .collect();
+
+            views.insert(search.name.clone(), view);
+        }
+
+        self.saved_search_views = views;
     }
 
     fn refresh_latin2id(&mut self) {
@@ -415,13 +580,9 @@ impl Database {
         self.cache_versions.latin2id = self.current_version.species;
         self.latin2id.clear();
 
-        for species in &self.species {
-            let inserted = self.latin2id.insert(species.latin.clone(), species.id);
-            assert!(
-                inserted.is_none(),
-                "species with latin name '{}' repeated",
-                species.latin.as_str()
-            );
+        // a double entry resolves to the first record until it gets merged
+        for species in self.species.iter().rev() {
+            self.latin2id.insert(species.latin.clone(), species.id);
         }
     }
 
@@ -495,6 +656,7 @@ impl Database {
 
         for (id, entry) in self.posts.0.iter_mut().enumerate() {
             entry.id = PostId(id);
+            std::mem::take(&mut entry.social_media).migrate(&entry.published, &mut entry.platforms);
             for entry in &mut entry.files {
                 entry.full_path = self.rootdir.join(&entry.rel_path);
                 entry.uri = format!("file://{}", entry.full_path.display());
@@ -0,0 +1,173 @@
+//! Life list: when each species was photographed for the first and the
+//! last time, how often and in which months.
+//!
+//! A post is a first record when it is the earliest one of any of its
+//! species; posts from the same day are ordered by their id.
+
+use crate::Database;
+use crate::Date;
+use crate::Latin;
+use crate::Post;
+use crate::PostId;
+use crate::Year;
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifeRecord {
+    pub latin: Latin,
+    pub first_seen: Date,
+    pub last_seen: Date,
+    pub first_post: PostId,
+    pub posts: usize,
+    pub photos: usize,
+    /// Number of posts in each month, January first
+    pub months: [usize; 12],
+}
+
+impl LifeRecord {
+    /// Numbers of months with any post, like `[5, 6, 9]`.
+    pub fn months_observed(&self) -> Vec<usize> {
+        (1..=12)
+            .filter(|month| self.months[month - 1] > 0)
+            .collect()
+    }
+}
+
+impl Database {
+    /// All species seen in posts, ordered by the first record.
+    pub fn life_list(&self) -> Vec<LifeRecord> {
+        self.life_list_of(self.posts.iter())
+    }
+
+    /// Species seen in the given posts, ordered by the first record.
+    pub fn life_list_of<'a>(
+        &'a self,
+        posts: impl IntoIterator<Item = &'a Post>,
+    ) -> Vec<LifeRecord> {
+        let mut records = BTreeMap::<&Latin, LifeRecord>::new();
+        for post in posts {
+            for latin in post.all_species() {
+                let record = records.entry(latin).or_insert_with(|| LifeRecord {
+                    latin: latin.clone(),
+                    first_seen: post.date,
+                    last_seen: post.date,
+                    first_post: post.id,
+                    posts: 0,
+                    photos: 0,
+                    months: [0; 12],
+                });
+
+                if (post.date, post.id) < (record.first_seen, record.first_post) {
+                    record.first_seen = post.date;
+                    record.first_post = post.id;
+                }
+                record.last_seen = record.last_seen.max(post.date);
+                record.posts += 1;
+                record.photos += post.files.len();
+                if let Some(count) = record.months.get_mut(post.date.month.as_u8() as usize - 1) {
+                    *count += 1;
+                }
+            }
+        }
+
+        let mut result: Vec<LifeRecord> = records.into_values().collect();
+        result.sort_by_key(|record| (record.first_seen, record.first_post));
+
+        result
+    }
+
+    /// Species seen for the first time, grouped by year.
+    pub fn new_species_by_year(&self) -> BTreeMap<Year, Vec<Latin>> {
+        let mut result = BTreeMap::<Year, Vec<Latin>>::new();
+        for record in self.life_list() {
+            result
+                .entry(record.first_seen.year)
+                .or_default()
+                .push(record.latin);
+        }
+
+        result
+    }
+
+    /// Whether the post is the first record of any of its species.
+    pub fn is_first_record(&self, id: &PostId) -> bool {
+        self.first_records.contains(id)
+    }
+
+    pub(crate) fn refresh_first_records(&mut self) {
+        if self.cache_versions.first_records == self.current_version.posts {
+            return;
+        }
+
+        self.cache_versions.first_records = self.current_version.posts;
+        self.first_records = self
+            .life_list()
+            .into_iter()
+            .map(|record| record.first_post)
+            .collect::<HashSet<_>>();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FileMetadata;
+    use crate::Post;
+
+    fn latin(name: &str) -> Latin {
+        Latin::from(name.to_owned())
+    }
+
+    #[test]
+    fn test_life_list() {
+        let mut db = Database::default();
+        for (i, (date, species, files)) in [
+            ("2024-06-10", vec!["Vulpes vulpes"], 2),
+            ("2023-05-01", vec!["Vulpes vulpes"], 1),
+            ("2024-06-10", vec!["Apis mellifera", "Vulpes vulpes"], 1),
+            ("2024-07-02", vec![], 1),
+            ("2024-06-10", vec!["Apis mellifera"], 3),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let mut post = Post {
+                id: PostId(i),
+                date: date.parse().unwrap(),
+                files: vec![FileMetadata::default(); files],
+                ..Post::default()
+            };
+            post.set_species_list(species.into_iter().map(latin).collect());
+            db.posts.push(post);
+        }
+        db.refresh_caches();
+
+        let list = db.life_list();
+        assert_eq!(list.len(), 2);
+
+        let fox = &list[0];
+        assert_eq!(fox.latin, latin("Vulpes vulpes"));
+        assert_eq!(fox.first_seen.to_string(), "2023-05-01");
+        assert_eq!(fox.last_seen.to_string(), "2024-06-10");
+        assert_eq!((fox.posts, fox.photos), (3, 4));
+        assert_eq!(fox.months_observed(), vec![5, 6]);
+        assert_eq!(fox.months[5], 2);
+
+        // the same day, the lower id wins
+        let bee = &list[1];
+        assert_eq!(bee.first_post, PostId(2));
+        assert_eq!((bee.posts, bee.photos), (2, 4));
+
+        assert_eq!(
+            db.new_species_by_year(),
+            BTreeMap::from([
+                (2023, vec![latin("Vulpes vulpes")]),
+                (2024, vec![latin("Apis mellifera")]),
+            ])
+        );
+
+        let first: Vec<bool> = (0..5).map(|i| db.is_first_record(&PostId(i))).collect();
+        assert_eq!(first, vec![false, true, true, false, false]);
+    }
+}
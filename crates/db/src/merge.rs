@@ -0,0 +1,261 @@
+//! Removing species: deleting a record or merging two records after a
+//! taxonomic revision or a double entry.
+//!
+//! Species are identified by their index, thus removing one renumbers
+//! the following ones and forgets the undo history of renames.
+
+use crate::Database;
+use crate::Latin;
+use crate::Species;
+use crate::SpeciesId;
+
+impl Database {
+    /// Removes the species. When posts refer to it, they are moved to
+    /// `reassign`; without it the species must not be used. Returns the
+    /// number of moved posts.
+    pub fn delete_species(
+        &mut self,
+        id: SpeciesId,
+        reassign: Option<SpeciesId>,
+    ) -> Result<usize, String> {
+        let Some(species) = self.species_by_id(&id) else {
+            return Err(format!("unknown species #{}", id.0));
+        };
+
+        let used = self.posts_of_species(&species.latin).len();
+        let target = match reassign {
+            Some(target) => Some(self.merge_target(id, target)?),
+            None if used > 0 => {
+                return Err(format!(
+                    "'{}' is used by {used} post(s), choose a species for them",
+                    species.latin
+                ));
+            }
+            None => None,
+        };
+
+        let from = species.latin.clone();
+        if let Some(target) = &target {
+            self.move_posts(&from, target);
+        }
+        self.remove_species(id);
+
+        Ok(used)
+    }
+
+    /// Moves posts of `from` to `into`, fills empty fields of `into`
+    /// and keeps both names of `from` as synonyms, then removes `from`.
+    /// Returns the number of moved posts.
+    pub fn merge_species(&mut self, from: SpeciesId, into: SpeciesId) -> Result<usize, String> {
+        let Some(source) = self.species_by_id(&from).cloned() else {
+            return Err(format!("unknown species #{}", from.0));
+        };
+        let target = self.merge_target(from, into)?;
+
+        let moved = if source.latin == target {
+            // double entry, the posts refer to both
+            0
+        } else {
+            self.move_posts(&source.latin, &target)
+        };
+
+        let entry = &mut self.species[into.0];
+        for (field, value) in [
+            (&mut entry.pl, &source.pl),
+            (&mut entry.wikipedia_pl, &source.wikipedia_pl),
+            (&mut entry.insektarium_pl, &source.insektarium_pl),
+            (&mut entry.en, &source.en),
+            (&mut entry.wikipedia_en, &source.wikipedia_en),
+        ] {
+            if field.is_empty() {
+                field.clone_from(value);
+            }
+        }
+        if entry.category.is_none() {
+            entry.category.clone_from(&source.category);
+        }
+        if entry.example_order.cover.is_none() {
+            entry
+                .example_order
+                .cover
+                .clone_from(&source.example_order.cover);
+        }
+        for path in &source.example_order.order {
+            if !entry.example_order.order.contains(path) {
+                entry.example_order.order.push(path.clone());
+            }
+        }
+        for (rank, name) in source.taxonomy.iter() {
+            if entry.taxonomy.get(rank).is_none() {
+                entry.taxonomy.set(rank, name);
+            }
+        }
+        for name in std::iter::once(&source.latin).chain(&source.synonyms) {
+            if *name != entry.latin && !entry.synonyms.contains(name) {
+                entry.synonyms.push(name.clone());
+            }
+        }
+        entry.refresh();
+
+        self.remove_species(from);
+
+        Ok(moved)
+    }
+
+    fn merge_target(&self, from: SpeciesId, into: SpeciesId) -> Result<Latin, String> {
+        if from == into {
+            return Err("cannot merge a species with itself".to_owned());
+        }
+
+        self.species_by_id(&into)
+            .map(|species| species.latin.clone())
+            .ok_or_else(|| format!("unknown species #{}", into.0))
+    }
+
+    /// Moves all posts, also in their undo history; returns their number.
+    fn move_posts(&mut self, from: &Latin, to: &Latin) -> usize {
+        let posts = self.posts_of_species(from);
+        for id in &posts {
+            self.post_mut(id).replace_species(from, to);
+        }
+        self.replace_in_undo(from, to);
+        self.current_version.posts += 1;
+
+        posts.len()
+    }
+
+    fn remove_species(&mut self, id: SpeciesId) {
+        self.species.remove(id.0);
+        for (index, species) in self.species.iter_mut().enumerate() {
+            species.id = SpeciesId(index);
+        }
+
+        self.renames.clear();
+        self.current_version.species += 1;
+        // examples are assigned by name, a double entry could hold them
+        self.current_version.posts += 1;
+    }
+
+    /// Pairs of species with the same Latin name or with a name which is
+    /// a synonym of another species.
+    pub fn conflicting_species(&self) -> Vec<(&Species, &Species)> {
+        let mut result = Vec::new();
+        for (i, first) in self.species.iter().enumerate() {
+            for second in &self.species[i + 1..] {
+                let conflict = first.latin == second.latin
+                    || first.synonyms.contains(&second.latin)
+                    || second.synonyms.contains(&first.latin)
+                    || first
+                        .synonyms
+                        .iter()
+                        .any(|name| second.synonyms.contains(name));
+                if conflict {
+                    result.push((first, second));
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::PostId;
+    use crate::edit_details;
+    use crate::edit_details::EditDetails;
+    use crate::test_util::DatabaseBuilder;
+    use crate::test_util::latin;
+    use crate::test_util::post_of;
+    use crate::test_util::species;
+
+    fn database() -> Database {
+        DatabaseBuilder::new()
+            .species(Species {
+                pl: "wydra".to_owned(),
+                ..species("Lutra lutra")
+            })
+            .species(Species {
+                en: "otter".to_owned(),
+                wikipedia_en: "https://en.wikipedia.org/wiki/Eurasian_otter".to_owned(),
+                synonyms: vec![latin("Mustela lutra")],
+                ..species("Lutra vulgaris")
+            })
+            .species(species("Vulpes vulpes"))
+            .post(post_of("Lutra vulgaris"))
+            .post(post_of("Vulpes vulpes"))
+            .post(post_of("Lutra vulgaris"))
+            .build()
+    }
+
+    #[test]
+    fn test_merge_species() {
+        let mut db = database();
+        edit_details::apply(
+            EditDetails::SetSpecies(PostId(1), Some(latin("Lutra vulgaris"))),
+            &mut db,
+        );
+        edit_details::apply(
+            EditDetails::SetSpecies(PostId(1), Some(latin("Vulpes vulpes"))),
+            &mut db,
+        );
+
+        assert!(db.merge_species(SpeciesId(1), SpeciesId(1)).is_err());
+        assert_eq!(db.merge_species(SpeciesId(1), SpeciesId(0)), Ok(2));
+        db.refresh_caches();
+
+        assert_eq!(db.species.len(), 2);
+        assert_eq!(db.species[1].id, SpeciesId(1));
+        assert_eq!(db.species[1].latin.as_str(), "Vulpes vulpes");
+
+        let otter = db.species_by_latin(&latin("Lutra lutra")).unwrap();
+        assert_eq!(otter.pl, "wydra");
+        assert_eq!(otter.en, "otter");
+        assert_eq!(
+            otter.wikipedia_en,
+            "https://en.wikipedia.org/wiki/Eurasian_otter"
+        );
+        assert_eq!(
+            otter.synonyms,
+            vec![latin("Lutra vulgaris"), latin("Mustela lutra")]
+        );
+        assert_eq!(db.posts_of_species(&otter.latin).len(), 2);
+
+        edit_details::apply(EditDetails::Undo(PostId(1)), &mut db);
+        assert_eq!(db.post(&PostId(1)).species, Some(latin("Lutra lutra")));
+    }
+
+    #[test]
+    fn test_merge_double_entry() {
+        let mut db = database();
+        db.species[2].latin = latin("Lutra lutra");
+        db.species[2].insektarium_pl = "https://example.com".to_owned();
+        db.current_version.species += 1;
+        db.refresh_caches();
+        assert_eq!(db.conflicting_species().len(), 1);
+
+        assert_eq!(db.merge_species(SpeciesId(2), SpeciesId(0)), Ok(0));
+        assert!(db.conflicting_species().is_empty());
+        assert_eq!(db.species[0].insektarium_pl, "https://example.com");
+        assert!(db.species[0].synonyms.is_empty());
+    }
+
+    #[test]
+    fn test_delete_species() {
+        let mut db = database();
+
+        assert_eq!(
+            db.delete_species(SpeciesId(1), None),
+            Err("'Lutra vulgaris' is used by 2 post(s), choose a species for them".to_owned())
+        );
+        assert_eq!(db.delete_species(SpeciesId(1), Some(SpeciesId(0))), Ok(2));
+        assert_eq!(db.post(&PostId(2)).species, Some(latin("Lutra lutra")));
+        assert!(db.species[0].synonyms.is_empty());
+
+        db.post_mut(&PostId(1)).species = None;
+        assert_eq!(db.delete_species(SpeciesId(1), None), Ok(0));
+        assert_eq!(db.species.len(), 1);
+        assert!(db.delete_species(SpeciesId(1), None).is_err());
+    }
+}
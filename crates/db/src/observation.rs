@@ -0,0 +1,102 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+/// Place where photos were taken, in WGS84 degrees.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl FromStr for Location {
+    type Err = &'static str;
+
+    /// Parses "latitude, longitude", like "52.2297, 21.0122".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((latitude, longitude)) = s.split_once(',') else {
+            return Err("expected latitude and longitude separated with a comma");
+        };
+
+        let Ok(latitude) = latitude.trim().parse::<f64>() else {
+            return Err("the latitude is not a number");
+        };
+
+        let Ok(longitude) = longitude.trim().parse::<f64>() else {
+            return Err("the longitude is not a number");
+        };
+
+        if !(-90.0..=90.0).contains(&latitude) {
+            return Err("the latitude must be in range -90..90");
+        }
+
+        if !(-180.0..=180.0).contains(&longitude) {
+            return Err("the longitude must be in range -180..180");
+        }
+
+        Ok(Self {
+            latitude,
+            longitude,
+        })
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "{}, {}", self.latitude, self.longitude)
+    }
+}
+
+/// Data attached to exported biodiversity records.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObservationSettings {
+    /// Person who took photos
+    #[serde(default)]
+    pub observer: String,
+    /// License of photos, like "CC-BY-4.0"
+    #[serde(default)]
+    pub license: String,
+    /// Public address of photos; without it media are referenced by
+    /// paths relative to the database
+    #[serde(default)]
+    pub media_url: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_location() {
+        let cases = [
+            (
+                "52.2297, 21.0122",
+                Ok(Location {
+                    latitude: 52.2297,
+                    longitude: 21.0122,
+                }),
+            ),
+            (
+                " -33.9,18.4 ",
+                Ok(Location {
+                    latitude: -33.9,
+                    longitude: 18.4,
+                }),
+            ),
+            (
+                "52.2297 21.0122",
+                Err("expected latitude and longitude separated with a comma"),
+            ),
+            ("north, 21", Err("the latitude is not a number")),
+            ("52, east", Err("the longitude is not a number")),
+            ("95, 21", Err("the latitude must be in range -90..90")),
+            ("52, 210", Err("the longitude must be in range -180..180")),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(Location::from_str(input), expected, "input = '{input}'");
+        }
+    }
+}
@@ -1,84 +1,202 @@
-use crate::Month;
+use crate::Date;
 use crate::Post;
 use crate::PostId;
 use crate::Selector;
-use crate::Year;
-use std::collections::BTreeSet;
-use std::collections::HashMap;
-use std::collections::HashSet;
+use std::borrow::Cow;
 
+/// Posts indexed by date. Views for selectors covering contiguous dates
+/// are just slices of the index.
 #[derive(Default)]
 pub struct PictureViews {
+    /// Years, months and days having any posts
     pub selectors: Vec<Selector>,
-    pub views: HashMap<Selector, Vec<PostId>>,
+    dates: Vec<Date>,
+    ids: Vec<PostId>,
 }
 
 impl PictureViews {
     pub fn is_empty(&self) -> bool {
-        self.selectors.is_empty() || self.views.is_empty()
+        self.selectors.is_empty() || self.ids.is_empty()
     }
 
-    pub fn get(&self, selector: Selector) -> Option<&Vec<PostId>> {
-        self.views.get(&selector)
+    /// Returns posts ordered by date. For years, months and days without
+    /// any posts yields `None`.
+    pub fn get(&self, selector: Selector) -> Option<Cow<'_, [PostId]>> {
+        self.get_at(selector, &Date::today())
+    }
+
+    pub fn get_at(&self, selector: Selector, today: &Date) -> Option<Cow<'_, [PostId]>> {
+        let view = match selector.range(today) {
+            Some((from, to)) => Cow::Borrowed(self.slice(&from, &to)),
+            None => self.on_this_day(selector),
+        };
+
+        let standard = matches!(
+            selector,
+            Selector::ByDate(_) | Selector::ByMonth(..) | Selector::ByYear(_)
+        );
+
+        if standard && view.is_empty() {
+            None
+        } else {
+            Some(view)
+        }
+    }
+
+    /// Iterates over days having any posts, in chronological order.
+    pub fn days(&self) -> impl Iterator<Item = (Date, &[PostId])> {
+        let mut start = 0;
+        std::iter::from_fn(move || {
+            let date = *self.dates.get(start)?;
+            let len = self.dates[start..].partition_point(|d| *d == date);
+            let ids = &self.ids[start..start + len];
+            start += len;
+
+            Some((date, ids))
+        })
+    }
+
+    pub fn first_date(&self) -> Option<Date> {
+        self.dates.first().copied()
+    }
+
+    pub fn last_date(&self) -> Option<Date> {
+        self.dates.last().copied()
+    }
+
+    fn slice(&self, from: &Date, to: &Date) -> &[PostId] {
+        let start = self.dates.partition_point(|date| date < from);
+        let end = self.dates.partition_point(|date| date <= to);
+
+        &self.ids[start..end.max(start)]
+    }
+
+    fn on_this_day(&self, selector: Selector) -> Cow<'_, [PostId]> {
+        let Selector::OnThisDay(day) = selector else {
+            return Cow::Borrowed(&[]);
+        };
+
+        let Some(first) = self.dates.first() else {
+            return Cow::Borrowed(&[]);
+        };
+
+        let mut result = Vec::new();
+        for year in first.year..day.year {
+            let date = Date { year, ..day };
+            result.extend_from_slice(self.slice(&date, &date));
+        }
+
+        Cow::Owned(result)
     }
 }
 
 #[derive(Default)]
 pub struct PictureViewsBuilder {
-    lookup: HashMap<Selector, Vec<PostId>>,
-    years: BTreeSet<Year>,
-    months: HashSet<(Year, Month)>,
+    index: Vec<(Date, PostId)>,
 }
 
 impl PictureViewsBuilder {
     pub fn add(&mut self, post: &Post) {
-        let selector = Selector::ByDate(post.date);
-        let from_date = self.lookup.entry(selector).or_default();
-        from_date.push(post.id);
-
-        self.months.insert((post.date.year, post.date.month));
-        self.years.insert(post.date.year);
+        self.index.push((post.date, post.id));
     }
 
     pub fn capture(mut self) -> PictureViews {
-        for (year, month) in &self.months {
-            let mut all = Vec::<PostId>::new();
-
-            for (selector, list) in &self.lookup {
-                if let Selector::ByDate(d) = selector
-                    && d.year == *year
-                    && d.month == *month
-                {
-                    all.extend(list);
-                }
+        self.index.sort();
+
+        let mut selectors = Vec::<Selector>::new();
+        let mut prev: Option<Date> = None;
+        for (date, _) in &self.index {
+            if prev.is_some_and(|prev| prev == *date) {
+                continue;
+            }
+
+            if prev.is_none_or(|prev| prev.year != date.year) {
+                selectors.push(Selector::ByYear(date.year));
+            }
+
+            if prev.is_none_or(|prev| prev.year != date.year || prev.month != date.month) {
+                selectors.push(Selector::ByMonth(date.year, date.month));
             }
 
-            let selector = Selector::ByMonth(*year, *month);
-            self.lookup.insert(selector, all);
+            selectors.push(Selector::ByDate(*date));
+            prev = Some(*date);
         }
 
-        for year in &self.years {
-            let mut all = Vec::<PostId>::new();
+        selectors.sort();
 
-            for (selector, list) in &self.lookup {
-                if let Selector::ByMonth(y, _) = selector
-                    && y == year
-                {
-                    all.extend(list);
-                }
-            }
+        let (dates, ids) = self.index.into_iter().unzip();
+
+        PictureViews {
+            selectors,
+            dates,
+            ids,
+        }
+    }
+}
 
-            let selector = Selector::ByYear(*year);
-            self.lookup.insert(selector, all);
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Season;
+    use std::str::FromStr;
+
+    fn date(s: &str) -> Date {
+        Date::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_views() {
+        let dates = [
+            "2024-12-24",
+            "2023-03-02",
+            "2025-01-15",
+            "2024-03-02",
+            "2024-12-24",
+            "2025-03-01",
+            "2025-03-02",
+        ];
+
+        let mut builder = PictureViewsBuilder::default();
+        for (id, s) in dates.iter().enumerate() {
+            let post = Post {
+                id: PostId(id),
+                date: date(s),
+                ..Post::default()
+            };
+            builder.add(&post);
         }
 
-        let mut res = PictureViews {
-            selectors: self.lookup.keys().cloned().collect(),
-            views: self.lookup,
+        let views = builder.capture();
+        assert_eq!(views.selectors.len(), 3 + 5 + 6);
+        assert_eq!(views.selectors[0], Selector::ByYear(2025));
+
+        let today = date("2025-03-02");
+        let get = |selector| -> Option<Vec<usize>> {
+            let view = views.get_at(selector, &today)?;
+            Some(view.iter().map(|id| id.0).collect())
         };
 
-        res.selectors.sort();
+        assert_eq!(get(Selector::ByYear(2024)), Some(vec![3, 0, 4]));
+        assert_eq!(get(Selector::ByDate(date("2024-12-24"))), Some(vec![0, 4]));
+        assert_eq!(get(Selector::ByYear(2022)), None);
+        assert_eq!(
+            get(Selector::BySeason(2024, Season::Winter)),
+            Some(vec![0, 4, 2])
+        );
+        assert_eq!(get(Selector::LastDays(2)), Some(vec![5, 6]));
+        assert_eq!(get(Selector::OnThisDay(today)), Some(vec![1, 3]));
+        assert_eq!(
+            get(Selector::ByRange(date("2023-01-01"), date("2024-03-02"))),
+            Some(vec![1, 3])
+        );
+        assert_eq!(get(Selector::ByWeek(2025, 3)), Some(vec![2]));
+        assert_eq!(get(Selector::ByWeek(2025, 4)), Some(vec![]));
 
-        res
+        let days: Vec<(String, usize)> = views
+            .days()
+            .map(|(date, ids)| (date.to_string(), ids.len()))
+            .collect();
+        assert_eq!(days.len(), 6);
+        assert_eq!(days[2], ("2024-12-24".to_owned(), 2));
     }
 }
@@ -1,11 +1,15 @@
-use crate::Database;
 use crate::Date;
 use crate::Latin;
 use crate::LocalDateTime;
+use crate::Location;
+use crate::PartKind;
 use crate::PostId;
+use crate::Schedule;
 use crate::SearchParts;
 use crate::TagList;
 use crate::edit_details::EditDetails;
+use crate::publication::LegacySocialMedia;
+use crate::publication::Platforms;
 use chrono::Local;
 use jpeg::ImageSize;
 use serde::Deserialize;
@@ -22,11 +26,22 @@ pub struct Post {
     #[serde(default)]
     pub en: String,
     pub tags: TagList,
+    /// The primary species, used in captions
     pub species: Option<Latin>,
+    /// Further species in the photos, in order; empty without the primary
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub other_species: Vec<Latin>,
     #[serde(default)]
     pub is_example: bool,
     #[serde(default)]
-    pub social_media: SocialMediaState,
+    pub platforms: Platforms,
+    #[serde(default)]
+    pub schedule: Option<Schedule>,
+    #[serde(default)]
+    pub location: Option<Location>,
+    /// Read only to migrate older databases into `platforms`
+    #[serde(default, skip_serializing)]
+    pub social_media: LegacySocialMedia,
 
     // runtime parameters
     #[serde(skip)]
@@ -51,142 +66,88 @@ impl Post {
         self.tags_string = self.tags.as_str();
 
         self.search_parts.clear();
-        self.search_parts.add(&self.pl);
-        self.search_parts.add(&self.en);
+        self.search_parts.add(PartKind::Description, &self.pl);
+        self.search_parts.add(PartKind::Description, &self.en);
         for tag in self.tags.iter() {
-            self.search_parts.add(&format!("#{tag}"));
+            self.search_parts.add(PartKind::Tag, &format!("#{tag}"));
         }
     }
 
-    pub fn is_unpublished(&self) -> bool {
-        !self.published.as_bool()
+    /// Whether the post or any of its photos is an example.
+    pub fn has_examples(&self) -> bool {
+        self.is_example || self.files.iter().any(|file| file.is_example)
     }
 
-    pub fn is_published(&self) -> bool {
-        self.published.as_bool()
+    /// All species, the primary one first.
+    pub fn all_species(&self) -> impl Iterator<Item = &Latin> {
+        self.species.iter().chain(&self.other_species)
     }
-}
 
-#[derive(Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
-pub struct FileMetadata {
-    pub rel_path: PathBuf,
-    pub image_size: Option<ImageSize>,
-
-    #[serde(skip)]
-    pub uri: String,
-
-    #[serde(skip)]
-    pub full_path: PathBuf,
-}
-
-#[derive(Default, Debug, Clone, Serialize, Deserialize)]
-pub struct SocialMediaState {
-    pub facebook_url: String,
-    pub instagram_url: String,
-}
-
-// --------------------------------------------------
-
-const PL_EMOJI: &str = "🇵🇱";
-const EN_EMOJI: &str = "🇬🇧";
-
-pub fn render_text(post: &Post, db: &Database) -> String {
-    let mut f = Builder::default();
-
-    if !post.pl.is_empty() {
-        f.writeln(format!("{PL_EMOJI} {}", post.pl));
+    pub fn has_species(&self, latin: &Latin) -> bool {
+        self.all_species().any(|item| item == latin)
     }
 
-    if !post.en.is_empty() {
-        f.writeln(format!("{EN_EMOJI} {}", post.en));
+    pub fn species_list(&self) -> Vec<Latin> {
+        self.all_species().cloned().collect()
     }
 
-    if post.species.is_some() && !f.is_empty() {
-        f.newline();
-
-        let latin = post.species.as_ref().unwrap();
-        let species = db.species_by_latin(latin).unwrap();
-        let latin = latin.as_str();
-
-        let pl = format_species(PL_EMOJI, &species.pl);
-        let en = format_species(EN_EMOJI, &species.en);
-        f.writeln(match (pl, en) {
-            (None, None) => latin.to_owned(),
-            (Some(pl), None) => format!("{latin} ({pl})"),
-            (None, Some(en)) => format!("{latin} ({en})"),
-            (Some(pl), Some(en)) => format!("{latin} ({pl} {en})"),
-        });
-    }
-
-    if f.is_empty() && post.species.is_some() {
-        let latin = post.species.as_ref().unwrap();
-        let species = db.species_by_latin(latin).unwrap();
-
-        if !species.pl.is_empty() {
-            f.writeln(format!(
-                "{PL_EMOJI} {} ({})",
-                species.pl,
-                &species.latin.as_str()
-            ));
-            if !species.en.is_empty() {
-                f.writeln(format!("{EN_EMOJI} {}", species.en));
+    /// Sets all species, the first one becomes the primary; repeated
+    /// names are dropped.
+    pub fn set_species_list(&mut self, list: Vec<Latin>) {
+        let mut list = list.into_iter();
+        self.species = list.next();
+        self.other_species.clear();
+        for latin in list {
+            if !self.has_species(&latin) {
+                self.other_species.push(latin);
             }
-        } else if !species.en.is_empty() {
-            f.writeln(format!(
-                "{EN_EMOJI} {} ({})",
-                species.en,
-                species.latin.as_str()
-            ));
-        } else {
-            f.writeln(species.latin.as_str().to_string());
         }
     }
 
-    if !f.is_empty() {
-        f.newline()
-    }
-
-    for (id, tag) in post.tags.iter().enumerate() {
-        if id > 0 {
-            f.write(format!(" #{tag}"));
-        } else {
-            f.write(format!("#{tag}"));
+    /// Replaces the species keeping its position; returns false when the
+    /// post does not have it.
+    pub fn replace_species(&mut self, from: &Latin, to: &Latin) -> bool {
+        if !self.has_species(from) {
+            return false;
         }
-    }
 
-    f.buf
-}
+        let list = self
+            .all_species()
+            .map(|latin| if latin == from { to } else { latin })
+            .cloned()
+            .collect();
+        self.set_species_list(list);
 
-fn format_species(emoji: &str, name: &str) -> Option<String> {
-    if name.is_empty() {
-        return None;
+        true
     }
 
-    Some(format!("{emoji} {name}"))
-}
-
-#[derive(Default)]
-struct Builder {
-    buf: String,
-}
-
-impl Builder {
-    fn writeln(&mut self, s: String) {
-        self.buf.push_str(&s);
-        self.buf.push('\n');
+    pub fn is_unpublished(&self) -> bool {
+        !self.published.as_bool()
     }
 
-    fn write(&mut self, s: String) {
-        self.buf.push_str(&s);
+    pub fn is_published(&self) -> bool {
+        self.published.as_bool()
     }
+}
 
-    fn newline(&mut self) {
-        self.buf.push('\n');
-    }
+#[derive(Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct FileMetadata {
+    pub rel_path: PathBuf,
+    pub image_size: Option<ImageSize>,
+    /// Description of the photo for screen readers
+    #[serde(default)]
+    pub alt_pl: String,
+    #[serde(default)]
+    pub alt_en: String,
+    /// Example of the species even when the post is not
+    #[serde(default)]
+    pub is_example: bool,
 
-    fn is_empty(&self) -> bool {
-        self.buf.is_empty()
-    }
+    #[serde(skip)]
+    pub uri: String,
+
+    #[serde(skip)]
+    pub full_path: PathBuf,
 }
 
 #[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
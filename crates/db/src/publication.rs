@@ -0,0 +1,206 @@
+use crate::LocalDateTime;
+use crate::PublishedState;
+use crate::edit_details::SocialMediaLink;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Publication status of a post on each social media platform.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Platforms(BTreeMap<SocialMediaLink, PlatformStatus>);
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlatformStatus {
+    pub state: PlatformState,
+    /// When the post was published or the last attempt failed
+    #[serde(default)]
+    pub timestamp: Option<LocalDateTime>,
+    /// Identifier of the post assigned by the platform, like
+    /// an `at://` URI on Bluesky
+    #[serde(default)]
+    pub remote_id: String,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub last_error: String,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlatformState {
+    #[default]
+    None,
+    Published,
+    Failed,
+}
+
+impl Platforms {
+    pub fn get(&self, link: SocialMediaLink) -> Option<&PlatformStatus> {
+        self.0.get(&link)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (SocialMediaLink, &PlatformStatus)> {
+        self.0.iter().map(|(link, status)| (*link, status))
+    }
+
+    pub fn url(&self, link: SocialMediaLink) -> &str {
+        self.get(link)
+            .map(|status| status.url.as_str())
+            .unwrap_or_default()
+    }
+
+    pub fn is_published_on(&self, link: SocialMediaLink) -> bool {
+        self.get(link)
+            .is_some_and(|status| status.state == PlatformState::Published)
+    }
+
+    /// Sets the status; the default one removes the entry.
+    pub fn set(&mut self, link: SocialMediaLink, status: PlatformStatus) {
+        if status == PlatformStatus::default() {
+            self.0.remove(&link);
+        } else {
+            self.0.insert(link, status);
+        }
+    }
+
+    /// Sets URL entered by the user; a post having URL is considered
+    /// published.
+    pub fn set_url(&mut self, link: SocialMediaLink, url: String) {
+        let mut status = self.get(link).cloned().unwrap_or_default();
+        if url.is_empty() {
+            if status.state == PlatformState::Published {
+                status.state = PlatformState::None;
+                status.remote_id.clear();
+            }
+        } else {
+            status.state = PlatformState::Published;
+        }
+
+        status.url = url;
+        self.set(link, status);
+    }
+}
+
+impl PlatformStatus {
+    pub fn published(remote_id: String, url: String, timestamp: LocalDateTime) -> Self {
+        Self {
+            state: PlatformState::Published,
+            timestamp: Some(timestamp),
+            remote_id,
+            url,
+            last_error: String::new(),
+        }
+    }
+
+    /// Records an unsuccessful attempt; a post published earlier remains
+    /// published.
+    pub fn failed(&self, error: String, timestamp: LocalDateTime) -> Self {
+        let mut result = self.clone();
+        if result.state != PlatformState::Published {
+            result.state = PlatformState::Failed;
+            result.timestamp = Some(timestamp);
+        }
+        result.last_error = error;
+
+        result
+    }
+}
+
+// --------------------------------------------------
+
+/// Links to social media, as stored in the older versions of the database.
+#[derive(Default, Deserialize)]
+pub struct LegacySocialMedia {
+    #[serde(default)]
+    facebook_url: String,
+    #[serde(default)]
+    instagram_url: String,
+    #[serde(default)]
+    mastodon_url: String,
+    #[serde(default)]
+    bluesky_url: String,
+}
+
+impl LegacySocialMedia {
+    pub(crate) fn migrate(self, published: &PublishedState, platforms: &mut Platforms) {
+        let timestamp = match published {
+            PublishedState::Timestamp(timestamp) => Some(*timestamp),
+            _ => None,
+        };
+
+        let urls = [
+            (SocialMediaLink::Facebook, self.facebook_url),
+            (SocialMediaLink::Instagram, self.instagram_url),
+            (SocialMediaLink::Mastodon, self.mastodon_url),
+            (SocialMediaLink::Bluesky, self.bluesky_url),
+        ];
+
+        for (link, url) in urls {
+            if url.is_empty() || platforms.get(link).is_some() {
+                continue;
+            }
+
+            let status = PlatformStatus {
+                state: PlatformState::Published,
+                timestamp,
+                url,
+                ..PlatformStatus::default()
+            };
+            platforms.set(link, status);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Database;
+    use crate::Post;
+
+    #[test]
+    fn test_migrate_social_media() {
+        let post = Post {
+            published: PublishedState::timestamp_now(),
+            ..Post::default()
+        };
+
+        let legacy = format!(
+            "{}\n[social_media]\nfacebook_url = \"https://www.facebook.com/1234/posts/555\"\ninstagram_url = \"\"\n",
+            toml::to_string(&post).unwrap()
+        );
+
+        let mut db = Database::default();
+        db.posts.push(toml::from_str(&legacy).unwrap());
+        db.refresh_all_records();
+
+        let post = db.posts.iter().next().unwrap();
+        let facebook = post.platforms.get(SocialMediaLink::Facebook).unwrap();
+        assert_eq!(facebook.state, PlatformState::Published);
+        assert_eq!(facebook.url, "https://www.facebook.com/1234/posts/555");
+        assert!(facebook.timestamp.is_some());
+        assert!(post.platforms.get(SocialMediaLink::Instagram).is_none());
+
+        let saved = toml::to_string(post).unwrap();
+        assert!(!saved.contains("facebook_url"));
+
+        let post: Post = toml::from_str(&saved).unwrap();
+        assert!(post.platforms.is_published_on(SocialMediaLink::Facebook));
+        assert!(!post.platforms.is_published_on(SocialMediaLink::Instagram));
+    }
+
+    #[test]
+    fn test_set_url() {
+        let mut platforms = Platforms::default();
+        platforms.set_url(SocialMediaLink::Mastodon, "https://m.example/1".to_owned());
+        assert!(platforms.is_published_on(SocialMediaLink::Mastodon));
+
+        platforms.set_url(SocialMediaLink::Mastodon, String::new());
+        assert!(platforms.get(SocialMediaLink::Mastodon).is_none());
+
+        let status = PlatformStatus::default().failed("HTTP 500".to_owned(), chrono::Local::now());
+        platforms.set(SocialMediaLink::Bluesky, status.clone());
+        platforms.set_url(SocialMediaLink::Bluesky, String::new());
+        assert_eq!(platforms.get(SocialMediaLink::Bluesky), Some(&status));
+    }
+}
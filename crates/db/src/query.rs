@@ -0,0 +1,1037 @@
+use crate::Database;
+use crate::Date;
+use crate::Day;
+use crate::Month;
+use crate::PlatformState;
+use crate::Post;
+use crate::Rank;
+use crate::Species;
+use crate::edit_details::SocialMediaLink;
+use crate::text::fold;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::ops::Bound;
+use std::ops::Range;
+use std::str::FromStr;
+
+/// Query entered in the posts search box.
+///
+/// The syntax is a sequence of terms, implicitly joined with AND.
+/// A term is either a free-text word, a "quoted phrase" or a field
+/// in form `name:value` (for instance `tag:fox`, `files:>2`,
+/// `date:2024-05..2024-06`). Terms can be combined with `AND`, `OR`,
+/// `NOT` (also `&`, `|` and the `-` prefix) and grouped with parentheses.
+/// Platforms are queried with `published:instagram` or `failed:bluesky`.
+/// Taxa are queried by rank, for instance `order:odonata`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Query {
+    #[default]
+    All,
+    Term(Term),
+    Not(Box<Query>),
+    And(Vec<Query>),
+    Or(Vec<Query>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    Text(String),
+    Tag(String),
+    Species(String),
+    Category(String),
+    Taxon(Rank, String),
+    Published(bool),
+    /// Publishing on the platform failed and has not succeeded since
+    Failed(SocialMediaLink),
+    Example(bool),
+    Files(Comparison, usize),
+    Has(Property),
+    Date(DateRange),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Property {
+    /// Published on the platform
+    Platform(SocialMediaLink),
+    Species,
+    Tags,
+    Polish,
+    English,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    pub from: Bound<Date>,
+    pub to: Bound<Date>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    /// Byte range of the input the error refers to.
+    pub span: Range<usize>,
+}
+
+// --------------------------------------------------
+
+impl Query {
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        let tokens = tokenize(s)?;
+        if tokens.is_empty() {
+            return Ok(Self::All);
+        }
+
+        let mut parser = Parser {
+            src: s,
+            tokens,
+            pos: 0,
+        };
+
+        let query = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(ParseError::new("unmatched ')'", token.span.clone()));
+        }
+
+        Ok(query)
+    }
+
+    pub fn is_all(&self) -> bool {
+        matches!(self, Self::All)
+    }
+
+    pub fn matches(&self, post: &Post, db: &Database) -> bool {
+        self.score(post, db).is_some()
+    }
+
+    /// Returns the relevance of the post, provided it matches the query.
+    /// Only free-text terms contribute to the score.
+    pub fn score(&self, post: &Post, db: &Database) -> Option<u32> {
+        match self {
+            Self::All => Some(0),
+            Self::Term(term) => term.score(post, db),
+            Self::Not(query) => match query.score(post, db) {
+                Some(_) => None,
+                None => Some(0),
+            },
+            Self::And(list) => list.iter().map(|query| query.score(post, db)).sum(),
+            Self::Or(list) => list.iter().filter_map(|query| query.score(post, db)).max(),
+        }
+    }
+
+    /// Checks if the query contains any free-text term.
+    pub fn has_text(&self) -> bool {
+        match self {
+            Self::All => false,
+            Self::Term(term) => matches!(term, Term::Text(_)),
+            Self::Not(query) => query.has_text(),
+            Self::And(list) | Self::Or(list) => list.iter().any(|query| query.has_text()),
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Term {
+    fn score(&self, post: &Post, db: &Database) -> Option<u32> {
+        if let Self::Text(phrase) = self {
+            let post_score = post.search_parts.term_score(phrase);
+            let species_score = self
+                .species(post, db)
+                .map(|species| species.search_parts.term_score(phrase))
+                .max()
+                .unwrap_or_default();
+
+            let score = post_score.max(species_score);
+            return (score > 0).then_some(score);
+        }
+
+        self.matches(post, db).then_some(0)
+    }
+
+    /// All species of the post.
+    fn species<'a>(
+        &self,
+        post: &'a Post,
+        db: &'a Database,
+    ) -> impl Iterator<Item = &'a Species> + 'a {
+        post.all_species()
+            .filter_map(|latin| db.species_by_latin(latin))
+    }
+
+    fn matches(&self, post: &Post, db: &Database) -> bool {
+        let species = || self.species(post, db);
+
+        match self {
+            Self::Text(_) => self.score(post, db).is_some(),
+            Self::Tag(tag) => post.tags.iter().any(|item| fold(item) == *tag),
+            Self::Species(name) => species().any(|species| {
+                [species.latin.as_str(), &species.pl, &species.en]
+                    .into_iter()
+                    .chain(species.synonyms.iter().map(|synonym| synonym.as_str()))
+                    .any(|item| fold(item).contains(name))
+            }),
+            Self::Category(name) => species()
+                .filter_map(|species| species.category.as_ref())
+                .any(|category| fold(category).contains(name)),
+            Self::Taxon(rank, name) => species().any(|species| species.rank_contains(*rank, name)),
+            Self::Published(flag) => post.is_published() == *flag,
+            Self::Failed(link) => post
+                .platforms
+                .get(*link)
+                .is_some_and(|status| status.state == PlatformState::Failed),
+            Self::Example(flag) => post.has_examples() == *flag,
+            Self::Files(cmp, count) => cmp.apply(post.files.len(), *count),
+            Self::Has(property) => property.present(post),
+            Self::Date(range) => range.contains(&post.date),
+        }
+    }
+}
+
+impl Comparison {
+    fn apply(&self, lhs: usize, rhs: usize) -> bool {
+        match self {
+            Self::Eq => lhs == rhs,
+            Self::Lt => lhs < rhs,
+            Self::Le => lhs <= rhs,
+            Self::Gt => lhs > rhs,
+            Self::Ge => lhs >= rhs,
+        }
+    }
+
+    fn split(s: &str) -> (Self, &str) {
+        if let Some(rest) = s.strip_prefix(">=") {
+            (Self::Ge, rest)
+        } else if let Some(rest) = s.strip_prefix("<=") {
+            (Self::Le, rest)
+        } else if let Some(rest) = s.strip_prefix('>') {
+            (Self::Gt, rest)
+        } else if let Some(rest) = s.strip_prefix('<') {
+            (Self::Lt, rest)
+        } else if let Some(rest) = s.strip_prefix('=') {
+            (Self::Eq, rest)
+        } else {
+            (Self::Eq, s)
+        }
+    }
+}
+
+impl Property {
+    fn from_name(s: &str) -> Option<Self> {
+        match s {
+            "species" => Some(Self::Species),
+            "tags" => Some(Self::Tags),
+            "pl" | "polish" => Some(Self::Polish),
+            "en" | "english" => Some(Self::English),
+            _ => platform_from_name(s).map(Self::Platform),
+        }
+    }
+
+    fn present(&self, post: &Post) -> bool {
+        match self {
+            Self::Platform(link) => post.platforms.is_published_on(*link),
+            Self::Species => post.species.is_some(),
+            Self::Tags => !post.tags.is_empty(),
+            Self::Polish => !post.pl.is_empty(),
+            Self::English => !post.en.is_empty(),
+        }
+    }
+}
+
+impl DateRange {
+    pub fn contains(&self, date: &Date) -> bool {
+        let after_start = match &self.from {
+            Bound::Included(from) => date >= from,
+            Bound::Excluded(from) => date > from,
+            Bound::Unbounded => true,
+        };
+
+        let before_end = match &self.to {
+            Bound::Included(to) => date <= to,
+            Bound::Excluded(to) => date < to,
+            Bound::Unbounded => true,
+        };
+
+        after_start && before_end
+    }
+
+    /// Parses `2024`, `2024-05`, `2024-05-01`, ranges `A..B` (either
+    /// side can be omitted) and comparisons `>A`, `>=A`, `<A`, `<=A`.
+    fn parse(s: &str) -> Result<Self, &'static str> {
+        if let Some((lhs, rhs)) = s.split_once("..") {
+            if lhs.is_empty() && rhs.is_empty() {
+                return Err("expected a date before or after '..'");
+            }
+
+            let from = if lhs.is_empty() {
+                Bound::Unbounded
+            } else {
+                Bound::Included(parse_partial_date(lhs)?.0)
+            };
+
+            let to = if rhs.is_empty() {
+                Bound::Unbounded
+            } else {
+                Bound::Included(parse_partial_date(rhs)?.1)
+            };
+
+            return Ok(Self { from, to });
+        }
+
+        let (cmp, rest) = Comparison::split(s);
+        let (first, last) = parse_partial_date(rest)?;
+
+        let (from, to) = match cmp {
+            Comparison::Eq => (Bound::Included(first), Bound::Included(last)),
+            Comparison::Lt => (Bound::Unbounded, Bound::Excluded(first)),
+            Comparison::Le => (Bound::Unbounded, Bound::Included(last)),
+            Comparison::Gt => (Bound::Excluded(last), Bound::Unbounded),
+            Comparison::Ge => (Bound::Included(first), Bound::Unbounded),
+        };
+
+        Ok(Self { from, to })
+    }
+}
+
+/// Returns the first and the last day matching `YYYY`, `YYYY-MM` or `YYYY-MM-DD`.
+fn parse_partial_date(s: &str) -> Result<(Date, Date), &'static str> {
+    const MALFORMED: &str = "expected date in form YYYY, YYYY-MM or YYYY-MM-DD";
+
+    let parts: Vec<&str> = s.split('-').collect();
+    let number = |s: &str, len: usize| -> Result<usize, &'static str> {
+        if s.len() != len || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(MALFORMED);
+        }
+
+        s.parse::<usize>().map_err(|_| MALFORMED)
+    };
+
+    match parts.as_slice() {
+        [year] => {
+            let year = number(year, 4)? as u16;
+            let first = Date {
+                year,
+                month: Month::new(1)?,
+                day: Day::new(1)?,
+            };
+            let last = Date {
+                year,
+                month: Month::new(12)?,
+                day: Day::new(31)?,
+            };
+
+            Ok((first, last))
+        }
+        [year, month] => {
+            let year = number(year, 4)? as u16;
+            let month = Month::new(number(month, 2)?)?;
+            let first = Date {
+                year,
+                month,
+                day: Day::new(1)?,
+            };
+            let last = Date {
+                year,
+                month,
+                day: Day::new(31)?,
+            };
+
+            Ok((first, last))
+        }
+        [_, _, _] => {
+            let date = Date::from_str(s)?;
+
+            Ok((date, date))
+        }
+        _ => Err(MALFORMED),
+    }
+}
+
+fn platform_from_name(s: &str) -> Option<SocialMediaLink> {
+    match s {
+        "facebook" | "fb" => Some(SocialMediaLink::Facebook),
+        "instagram" | "ig" => Some(SocialMediaLink::Instagram),
+        "mastodon" => Some(SocialMediaLink::Mastodon),
+        "bluesky" | "bsky" => Some(SocialMediaLink::Bluesky),
+        _ => None,
+    }
+}
+
+fn parse_bool(s: &str) -> Option<bool> {
+    match s {
+        "yes" | "true" | "1" => Some(true),
+        "no" | "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+// --------------------------------------------------
+
+impl ParseError {
+    fn new(message: impl Into<String>, span: Range<usize>) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// --------------------------------------------------
+
+#[derive(Debug)]
+struct Token {
+    kind: TokenKind,
+    span: Range<usize>,
+}
+
+#[derive(Debug)]
+enum TokenKind {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Word(Word),
+}
+
+#[derive(Debug)]
+struct Word {
+    field: Option<(String, Range<usize>)>,
+    value: String,
+    value_span: Range<usize>,
+}
+
+fn is_delimiter(b: u8) -> bool {
+    b.is_ascii_whitespace() || matches!(b, b'(' | b')' | b'"')
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, ParseError> {
+    let bytes = s.as_bytes();
+    let mut tokens = Vec::<Token>::new();
+    let mut pos = 0;
+
+    let single = |kind: TokenKind, pos: usize| Token {
+        kind,
+        span: pos..pos + 1,
+    };
+
+    while pos < bytes.len() {
+        let start = pos;
+        match bytes[pos] {
+            b if b.is_ascii_whitespace() => {
+                pos += 1;
+            }
+            b'(' => {
+                tokens.push(single(TokenKind::LParen, pos));
+                pos += 1;
+            }
+            b')' => {
+                tokens.push(single(TokenKind::RParen, pos));
+                pos += 1;
+            }
+            b'|' => {
+                tokens.push(single(TokenKind::Or, pos));
+                pos += 1;
+            }
+            b'&' => {
+                tokens.push(single(TokenKind::And, pos));
+                pos += 1;
+            }
+            b'-' => {
+                tokens.push(single(TokenKind::Not, pos));
+                pos += 1;
+            }
+            b'"' => {
+                let (value, end) = quoted(s, pos)?;
+                tokens.push(Token {
+                    kind: TokenKind::Word(Word {
+                        field: None,
+                        value,
+                        value_span: start..end,
+                    }),
+                    span: start..end,
+                });
+                pos = end;
+            }
+            _ => {
+                while pos < bytes.len() && !is_delimiter(bytes[pos]) {
+                    pos += 1;
+                }
+
+                let text = &s[start..pos];
+                let kind = match text {
+                    "AND" => TokenKind::And,
+                    "OR" => TokenKind::Or,
+                    "NOT" => TokenKind::Not,
+                    _ => match text.split_once(':') {
+                        Some((field, value)) if !field.is_empty() => {
+                            let field_span = start..start + field.len();
+                            let field = Some((field.to_lowercase(), field_span));
+                            if !value.is_empty() {
+                                TokenKind::Word(Word {
+                                    field,
+                                    value: value.to_owned(),
+                                    value_span: start + text.len() - value.len()..pos,
+                                })
+                            } else if bytes.get(pos) == Some(&b'"') {
+                                let quote = pos;
+                                let (value, end) = quoted(s, pos)?;
+                                pos = end;
+                                TokenKind::Word(Word {
+                                    field,
+                                    value,
+                                    value_span: quote..end,
+                                })
+                            } else {
+                                return Err(ParseError::new(
+                                    format!("missing value after '{text}'"),
+                                    start..pos,
+                                ));
+                            }
+                        }
+                        _ => TokenKind::Word(Word {
+                            field: None,
+                            value: text.to_owned(),
+                            value_span: start..pos,
+                        }),
+                    },
+                };
+
+                tokens.push(Token {
+                    kind,
+                    span: start..pos,
+                });
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Reads a quoted string starting at `start`; returns its contents and
+/// the position just after the closing quote.
+fn quoted(s: &str, start: usize) -> Result<(String, usize), ParseError> {
+    let tail = &s[start + 1..];
+    match tail.find('"') {
+        Some(len) => Ok((tail[..len].to_owned(), start + 1 + len + 1)),
+        None => Err(ParseError::new("unterminated quote", start..s.len())),
+    }
+}
+
+// --------------------------------------------------
+
+struct Parser<'a> {
+    src: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn starts_term(&self) -> bool {
+        matches!(
+            self.peek().map(|token| &token.kind),
+            Some(TokenKind::LParen | TokenKind::Not | TokenKind::Word(_))
+        )
+    }
+
+    fn expect_term_after(&self, span: &Range<usize>) -> Result<(), ParseError> {
+        if self.starts_term() {
+            Ok(())
+        } else {
+            let operator = &self.src[span.clone()];
+            Err(ParseError::new(
+                format!("expected a term after '{operator}'"),
+                span.clone(),
+            ))
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Query, ParseError> {
+        let mut list = vec![self.parse_and()?];
+        while let Some(token) = self.peek() {
+            if !matches!(token.kind, TokenKind::Or) {
+                break;
+            }
+
+            let span = token.span.clone();
+            self.pos += 1;
+            self.expect_term_after(&span)?;
+            list.push(self.parse_and()?);
+        }
+
+        Ok(if list.len() == 1 {
+            list.pop().unwrap()
+        } else {
+            Query::Or(list)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Query, ParseError> {
+        let mut list = vec![self.parse_unary()?];
+        while let Some(token) = self.peek() {
+            match token.kind {
+                TokenKind::And => {
+                    let span = token.span.clone();
+                    self.pos += 1;
+                    self.expect_term_after(&span)?;
+                    list.push(self.parse_unary()?);
+                }
+                TokenKind::LParen | TokenKind::Not | TokenKind::Word(_) => {
+                    list.push(self.parse_unary()?);
+                }
+                TokenKind::Or | TokenKind::RParen => break,
+            }
+        }
+
+        Ok(if list.len() == 1 {
+            list.pop().unwrap()
+        } else {
+            Query::And(list)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<Query, ParseError> {
+        let Some(token) = self.tokens.get(self.pos) else {
+            let end = self.src.len();
+            return Err(ParseError::new("expected a term", end..end));
+        };
+
+        let span = token.span.clone();
+        match &token.kind {
+            TokenKind::Not => {
+                self.pos += 1;
+                self.expect_term_after(&span)?;
+                let query = self.parse_unary()?;
+
+                Ok(Query::Not(Box::new(query)))
+            }
+            TokenKind::LParen => {
+                self.pos += 1;
+                if matches!(
+                    self.peek().map(|token| &token.kind),
+                    Some(TokenKind::RParen)
+                ) {
+                    let end = self.peek().unwrap().span.end;
+                    return Err(ParseError::new("empty group", span.start..end));
+                }
+
+                if self.peek().is_none() {
+                    return Err(ParseError::new("missing closing ')'", span));
+                }
+
+                let query = self.parse_or()?;
+                match self.peek().map(|token| &token.kind) {
+                    Some(TokenKind::RParen) => {
+                        self.pos += 1;
+                        Ok(query)
+                    }
+                    _ => Err(ParseError::new("missing closing ')'", span)),
+                }
+            }
+            TokenKind::RParen => Err(ParseError::new("unmatched ')'", span)),
+            TokenKind::And | TokenKind::Or => {
+                let operator = &self.src[span.clone()];
+                Err(ParseError::new(
+                    format!("expected a term before '{operator}'"),
+                    span,
+                ))
+            }
+            TokenKind::Word(word) => {
+                let term = make_term(word)?;
+                self.pos += 1;
+
+                Ok(term)
+            }
+        }
+    }
+}
+
+fn make_term(word: &Word) -> Result<Query, ParseError> {
+    let value = word.value.to_lowercase();
+    let invalid = |message: &str| ParseError::new(message, word.value_span.clone());
+
+    let Some((field, field_span)) = &word.field else {
+        return Ok(Query::Term(Term::Text(fold(&value))));
+    };
+
+    let term = match field.as_str() {
+        "tag" => Term::Tag(fold(value.trim_start_matches('#'))),
+        "species" => Term::Species(fold(&value)),
+        "category" => Term::Category(fold(&value)),
+        "published" => match (parse_bool(&value), platform_from_name(&value)) {
+            (Some(flag), _) => Term::Published(flag),
+            (None, Some(link)) => Term::Has(Property::Platform(link)),
+            (None, None) => {
+                return Err(invalid(
+                    "expected 'yes', 'no' or one of: facebook, instagram, mastodon, bluesky",
+                ));
+            }
+        },
+        "failed" => match platform_from_name(&value) {
+            Some(link) => Term::Failed(link),
+            None => {
+                return Err(invalid(
+                    "expected one of: facebook, instagram, mastodon, bluesky",
+                ));
+            }
+        },
+        "example" => match parse_bool(&value) {
+            Some(flag) => Term::Example(flag),
+            None => return Err(invalid("expected 'yes' or 'no'")),
+        },
+        "files" => {
+            let (cmp, count) = Comparison::split(&value);
+            match count.parse::<usize>() {
+                Ok(count) => Term::Files(cmp, count),
+                Err(_) => return Err(invalid("expected a number, like 2 or >2")),
+            }
+        }
+        "has" | "no" => {
+            let Some(property) = Property::from_name(&value) else {
+                return Err(invalid(
+                    "expected one of: facebook, instagram, mastodon, bluesky, species, tags, pl, en",
+                ));
+            };
+
+            let term = Query::Term(Term::Has(property));
+            return Ok(if field == "no" {
+                Query::Not(Box::new(term))
+            } else {
+                term
+            });
+        }
+        "date" => match DateRange::parse(&value) {
+            Ok(range) => Term::Date(range),
+            Err(message) => return Err(invalid(message)),
+        },
+        _ => match Rank::from_name(field) {
+            Some(rank) => Term::Taxon(rank, fold(&value)),
+            None => {
+                return Err(ParseError::new(
+                    format!("unknown field '{field}'"),
+                    field_span.clone(),
+                ));
+            }
+        },
+    };
+
+    Ok(Query::Term(term))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Latin;
+    use crate::PlatformStatus;
+    use crate::TagList;
+    use crate::test_util::DatabaseBuilder;
+    use crate::test_util::red_fox;
+
+    fn date(s: &str) -> Date {
+        Date::from_str(s).unwrap()
+    }
+
+    fn text(s: &str) -> Query {
+        Query::Term(Term::Text(s.to_owned()))
+    }
+
+    fn tag(s: &str) -> Query {
+        Query::Term(Term::Tag(s.to_owned()))
+    }
+
+    #[test]
+    fn test_parse() {
+        let cases = [
+            ("", Query::All),
+            ("   ", Query::All),
+            ("Fox", text("fox")),
+            ("\"Red Fox\"", text("red fox")),
+            ("red fox", Query::And(vec![text("red"), text("fox")])),
+            ("red AND fox", Query::And(vec![text("red"), text("fox")])),
+            ("red OR fox", Query::Or(vec![text("red"), text("fox")])),
+            ("red | fox", Query::Or(vec![text("red"), text("fox")])),
+            (
+                "red or fox",
+                Query::And(vec![text("red"), text("or"), text("fox")]),
+            ),
+            ("NOT fox", Query::Not(Box::new(text("fox")))),
+            ("-fox", Query::Not(Box::new(text("fox")))),
+            (
+                "a b OR c",
+                Query::Or(vec![Query::And(vec![text("a"), text("b")]), text("c")]),
+            ),
+            (
+                "a (b OR c)",
+                Query::And(vec![text("a"), Query::Or(vec![text("b"), text("c")])]),
+            ),
+            ("tag:Fox", tag("fox")),
+            ("tag:#fox", tag("fox")),
+            ("tag:\"red fox\"", tag("red fox")),
+            (
+                "species:vulpes",
+                Query::Term(Term::Species("vulpes".to_owned())),
+            ),
+            (
+                "category:Odonata",
+                Query::Term(Term::Category("odonata".to_owned())),
+            ),
+            (
+                "family:Nymphalidae",
+                Query::Term(Term::Taxon(Rank::Family, "nymphalidae".to_owned())),
+            ),
+            ("published:no", Query::Term(Term::Published(false))),
+            ("example:yes", Query::Term(Term::Example(true))),
+            ("files:>2", Query::Term(Term::Files(Comparison::Gt, 2))),
+            ("files:3", Query::Term(Term::Files(Comparison::Eq, 3))),
+            ("files:<=1", Query::Term(Term::Files(Comparison::Le, 1))),
+            (
+                "has:facebook",
+                Query::Term(Term::Has(Property::Platform(SocialMediaLink::Facebook))),
+            ),
+            (
+                "published:instagram -published:fb",
+                Query::And(vec![
+                    Query::Term(Term::Has(Property::Platform(SocialMediaLink::Instagram))),
+                    Query::Not(Box::new(Query::Term(Term::Has(Property::Platform(
+                        SocialMediaLink::Facebook,
+                    ))))),
+                ]),
+            ),
+            (
+                "failed:bsky",
+                Query::Term(Term::Failed(SocialMediaLink::Bluesky)),
+            ),
+            (
+                "no:species",
+                Query::Not(Box::new(Query::Term(Term::Has(Property::Species)))),
+            ),
+            (
+                "date:2024",
+                Query::Term(Term::Date(DateRange {
+                    from: Bound::Included(date("2024-01-01")),
+                    to: Bound::Included(date("2024-12-31")),
+                })),
+            ),
+            (
+                "date:2024-05..2024-06-15",
+                Query::Term(Term::Date(DateRange {
+                    from: Bound::Included(date("2024-05-01")),
+                    to: Bound::Included(date("2024-06-15")),
+                })),
+            ),
+            (
+                "date:..2023",
+                Query::Term(Term::Date(DateRange {
+                    from: Bound::Unbounded,
+                    to: Bound::Included(date("2023-12-31")),
+                })),
+            ),
+            (
+                "date:>2024-03",
+                Query::Term(Term::Date(DateRange {
+                    from: Bound::Excluded(date("2024-03-31")),
+                    to: Bound::Unbounded,
+                })),
+            ),
+        ];
+
+        for (input, expected) in cases {
+            let got = Query::parse(input);
+            assert_eq!(got, Ok(expected), "input = '{input}'");
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let cases = [
+            ("fox OR", "expected a term after 'OR'", 4..6),
+            ("AND fox", "expected a term before 'AND'", 0..3),
+            ("(fox", "missing closing ')'", 0..1),
+            ("fox)", "unmatched ')'", 3..4),
+            ("a () b", "empty group", 2..4),
+            ("\"red fox", "unterminated quote", 0..8),
+            ("-", "expected a term after '-'", 0..1),
+            ("color:red", "unknown field 'color'", 0..5),
+            ("tag:", "missing value after 'tag:'", 0..4),
+            (
+                "published:maybe",
+                "expected 'yes', 'no' or one of: facebook, instagram, mastodon, bluesky",
+                10..15,
+            ),
+            (
+                "failed:yes",
+                "expected one of: facebook, instagram, mastodon, bluesky",
+                7..10,
+            ),
+            ("files:many", "expected a number, like 2 or >2", 6..10),
+            (
+                "has:flickr",
+                "expected one of: facebook, instagram, mastodon, bluesky, species, tags, pl, en",
+                4..10,
+            ),
+            (
+                "date:2024-5",
+                "expected date in form YYYY, YYYY-MM or YYYY-MM-DD",
+                5..11,
+            ),
+            (
+                "żółw date:2024-13",
+                "the month must be a number in range 1..12",
+                13..20,
+            ),
+        ];
+
+        for (input, message, span) in cases {
+            let got = Query::parse(input);
+            let expected = Err(ParseError::new(message, span));
+            assert_eq!(got, expected, "input = '{input}'");
+        }
+    }
+
+    #[test]
+    fn test_failed_after_retry() {
+        let db = Database::default();
+        let now = chrono::Local::now();
+        let failed = Query::parse("failed:bluesky").unwrap();
+
+        let mut post = Post::default();
+        let status = PlatformStatus::default().failed("HTTP 502".to_owned(), now);
+        post.platforms.set(SocialMediaLink::Bluesky, status);
+        assert!(failed.matches(&post, &db));
+
+        let status =
+            PlatformStatus::published("1".to_owned(), "https://bsky.app/1".to_owned(), now);
+        post.platforms.set(SocialMediaLink::Bluesky, status);
+        assert!(!failed.matches(&post, &db));
+
+        // a failed repeated attempt does not undo the publication
+        let status = post
+            .platforms
+            .get(SocialMediaLink::Bluesky)
+            .unwrap()
+            .failed("HTTP 502".to_owned(), now);
+        post.platforms.set(SocialMediaLink::Bluesky, status);
+        assert!(!failed.matches(&post, &db));
+    }
+
+    #[test]
+    fn test_matches() {
+        let mut fox = red_fox();
+        fox.category = Some("mammals".to_owned());
+        fox.taxonomy.set(Rank::Order, "Carnivora");
+        fox.taxonomy.set(Rank::Family, "Canidae");
+        let mut db = DatabaseBuilder::new().species(fox).build();
+
+        let mut post = Post {
+            date: date("2024-05-17"),
+            pl: "Lis w lesie".to_owned(),
+            tags: TagList(vec!["fox".to_owned(), "forest".to_owned()]),
+            species: Some(Latin::from("Vulpes vulpes".to_owned())),
+            files: vec![Default::default(), Default::default()],
+            ..Post::default()
+        };
+        post.platforms.set_url(
+            SocialMediaLink::Instagram,
+            "https://instagram.com/p/1".to_owned(),
+        );
+        post.platforms.set(
+            SocialMediaLink::Bluesky,
+            PlatformStatus::default().failed("HTTP 502".to_owned(), chrono::Local::now()),
+        );
+        post.refresh();
+
+        let cases = [
+            ("", true),
+            ("lesie", true),
+            ("\"w lesie\"", true),
+            ("lesie wolf", false),
+            ("lesie OR wolf", true),
+            ("-wolf", true),
+            ("tag:FOX", true),
+            ("tag:fo", false),
+            ("species:vulpes", true),
+            ("species:lis", true),
+            ("category:mammal", true),
+            ("category:birds", false),
+            ("order:carnivora", true),
+            ("family:felidae", false),
+            ("genus:vulpes", true),
+            ("kingdom:animalia", false),
+            ("published:no", true),
+            ("published:yes", false),
+            ("example:no", true),
+            ("files:2", true),
+            ("files:>2", false),
+            ("has:species", true),
+            ("no:species", false),
+            ("has:en", false),
+            ("has:facebook", false),
+            ("published:instagram -published:facebook", true),
+            ("published:bluesky", false),
+            ("failed:bluesky", true),
+            ("failed:instagram", false),
+            ("date:2024", true),
+            ("date:2024-05-17", true),
+            ("date:2024-06..", false),
+            ("date:<2024-05-17", false),
+            ("date:<=2024-05", true),
+            ("(tag:wolf OR tag:fox) date:2024", true),
+            ("forst", true),
+            ("vulpse", true),
+            ("fore lis", true),
+            ("forest wolf", false),
+        ];
+
+        for (input, expected) in cases {
+            let query = Query::parse(input).unwrap();
+            assert_eq!(query.matches(&post, &db), expected, "input = '{input}'");
+        }
+
+        // any species of the post matches
+        db.add_species(&Species {
+            latin: Latin::from("Lepus europaeus".to_owned()),
+            pl: "zając".to_owned(),
+            ..Species::default()
+        });
+        db.refresh_caches();
+        post.set_species_list(vec![
+            Latin::from("Vulpes vulpes".to_owned()),
+            Latin::from("Lepus europaeus".to_owned()),
+        ]);
+        for (input, expected) in [
+            ("species:lepus", true),
+            ("species:vulpes -species:lepus", false),
+            ("genus:lepus order:carnivora", true),
+            ("zajac", true),
+        ] {
+            let query = Query::parse(input).unwrap();
+            assert_eq!(query.matches(&post, &db), expected, "input = '{input}'");
+        }
+    }
+}
@@ -0,0 +1,333 @@
+//! Changing the Latin name of a species.
+//!
+//! Posts refer to species by the Latin name, thus renaming must update
+//! all of them at once. The old name is kept as a synonym. Renames are
+//! undone in the reverse order with [`Database::undo_rename`].
+
+use crate::Database;
+use crate::Latin;
+use crate::PostId;
+use crate::Species;
+use crate::SpeciesId;
+use crate::edit_details::EditDetails;
+
+/// Rename of a species with the posts which would be updated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rename {
+    pub species: SpeciesId,
+    pub from: Latin,
+    pub to: Latin,
+    pub posts: Vec<PostId>,
+}
+
+pub(crate) struct Applied {
+    rename: Rename,
+    /// The old name was not a synonym before
+    added_synonym: bool,
+    /// The new name was a synonym before
+    removed_synonym: bool,
+}
+
+impl Database {
+    /// Checks the new name and collects posts referring to the species.
+    pub fn plan_rename(&self, id: SpeciesId, to: Latin) -> Result<Rename, String> {
+        let Some(species) = self.species_by_id(&id) else {
+            return Err(format!("unknown species #{}", id.0));
+        };
+
+        if to.as_str().trim().is_empty() {
+            return Err("name cannot be empty".to_owned());
+        }
+
+        if species.latin == to {
+            return Err(format!("the species is already named '{to}'"));
+        }
+
+        if let Some(other) = self.species.iter().find(|other| other.latin == to) {
+            let name = if other.pl.is_empty() {
+                other.latin.as_str()
+            } else {
+                other.pl.as_str()
+            };
+            return Err(format!(
+                "'{to}' is already used by another species ({name})"
+            ));
+        }
+
+        if let Some(other) = self.species_by_synonym(&to).filter(|other| other.id != id) {
+            return Err(format!(
+                "'{to}' is a synonym of another species ({})",
+                other.latin
+            ));
+        }
+
+        let posts = self.posts_of_species(&species.latin);
+
+        Ok(Rename {
+            species: id,
+            from: species.latin.clone(),
+            to,
+            posts,
+        })
+    }
+
+    /// Renames the species and updates the posts; fails without changing
+    /// anything when the plan is out of date.
+    pub fn rename_species(&mut self, rename: &Rename) -> Result<(), String> {
+        if self.plan_rename(rename.species, rename.to.clone()).as_ref() != Ok(rename) {
+            return Err(format!(
+                "renaming '{}' to '{}' must be planned again, the database has changed",
+                rename.from, rename.to
+            ));
+        }
+
+        let species = &mut self.species[rename.species.0];
+        let added_synonym = !species.synonyms.contains(&rename.from);
+        if added_synonym {
+            species.synonyms.push(rename.from.clone());
+        }
+        let removed_synonym = species.synonyms.contains(&rename.to);
+        species.synonyms.retain(|synonym| *synonym != rename.to);
+
+        self.replace_latin(rename.species, &rename.posts, &rename.from, &rename.to);
+
+        self.renames.push(Applied {
+            rename: rename.clone(),
+            added_synonym,
+            removed_synonym,
+        });
+
+        Ok(())
+    }
+
+    /// Species which was known under the name before.
+    pub fn species_by_synonym(&self, name: &Latin) -> Option<&Species> {
+        self.species
+            .iter()
+            .find(|species| species.synonyms.contains(name))
+    }
+
+    pub fn can_undo_rename(&self) -> bool {
+        !self.renames.is_empty()
+    }
+
+    /// Reverts the last rename; returns it when there was one. All posts
+    /// referring to the new name get the old one back, also those edited
+    /// after the rename. The history is forgotten when the species no
+    /// longer has the name it was renamed to.
+    pub fn undo_rename(&mut self) -> Option<Rename> {
+        let applied = self.renames.pop()?;
+        let rename = applied.rename;
+
+        if self.species[rename.species.0].latin != rename.to {
+            self.renames.clear();
+            return None;
+        }
+
+        let species = &mut self.species[rename.species.0];
+        if applied.added_synonym {
+            species.synonyms.retain(|synonym| *synonym != rename.from);
+        }
+        if applied.removed_synonym {
+            species.synonyms.push(rename.to.clone());
+        }
+
+        let posts = self.posts_of_species(&rename.to);
+        self.replace_latin(rename.species, &posts, &rename.to, &rename.from);
+
+        Some(rename)
+    }
+
+    /// Sets the name of the species and of its posts, also in their undo
+    /// history, so that undoing a post edit does not bring back the old name.
+    fn replace_latin(&mut self, id: SpeciesId, posts: &[PostId], from: &Latin, to: &Latin) {
+        let species = &mut self.species[id.0];
+        species.latin = to.clone();
+        species.refresh();
+
+        for id in posts {
+            self.post_mut(id).replace_species(from, to);
+        }
+
+        self.replace_in_undo(from, to);
+
+        self.current_version.species += 1;
+        self.current_version.posts += 1;
+    }
+
+    pub fn posts_of_species(&self, latin: &Latin) -> Vec<PostId> {
+        self.posts
+            .iter()
+            .filter(|post| post.has_species(latin))
+            .map(|post| post.id)
+            .collect()
+    }
+
+    /// Replaces the name in the undo history of all posts.
+    pub(crate) fn replace_in_undo(&mut self, from: &Latin, to: &Latin) {
+        for post in self.posts.iter_mut() {
+            for action in post.undo.iter_mut() {
+                let list = match action {
+                    EditDetails::SetSpecies(_, latin) => latin.iter_mut().collect(),
+                    EditDetails::SetSpeciesList(_, list) => list.iter_mut().collect(),
+                    _ => Vec::new(),
+                };
+                for latin in list {
+                    if latin == from {
+                        *latin = to.clone();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::edit_details;
+    use crate::test_util::DatabaseBuilder;
+    use crate::test_util::latin;
+    use crate::test_util::post_of;
+    use crate::test_util::species;
+
+    fn database() -> Database {
+        DatabaseBuilder::new()
+            .species(species("Lutra lutra"))
+            .species(species("Vulpes vulpes"))
+            .post(post_of("Lutra lutra"))
+            .post(post_of("Vulpes vulpes"))
+            .post(post_of("Lutra lutra"))
+            .build()
+    }
+
+    #[test]
+    fn test_update_keeps_latin() {
+        let mut db = database();
+
+        let mut otter = db.species[0].clone();
+        otter.latin = latin("Lutra europaea");
+        otter.pl = "wydra".to_owned();
+        db.update_species(&otter);
+
+        assert_eq!(db.species[0].latin, latin("Lutra lutra"));
+        assert_eq!(db.species[0].pl, "wydra");
+        assert_eq!(db.posts_of_species(&latin("Lutra lutra")).len(), 2);
+    }
+
+    #[test]
+    fn test_plan_rename() {
+        let mut db = database();
+
+        let rename = db
+            .plan_rename(SpeciesId(0), latin("Lutra europaea"))
+            .unwrap();
+        assert_eq!(rename.posts, vec![PostId(0), PostId(2)]);
+
+        assert_eq!(
+            db.plan_rename(SpeciesId(0), latin("Vulpes vulpes")).err(),
+            Some("'Vulpes vulpes' is already used by another species (Vulpes vulpes)".to_owned())
+        );
+        db.species[1].pl = "lis".to_owned();
+        assert_eq!(
+            db.plan_rename(SpeciesId(0), latin("Vulpes vulpes")).err(),
+            Some("'Vulpes vulpes' is already used by another species (lis)".to_owned())
+        );
+        assert!(db.plan_rename(SpeciesId(0), latin("Lutra lutra")).is_err());
+        assert!(db.plan_rename(SpeciesId(0), latin(" ")).is_err());
+        assert!(db.plan_rename(SpeciesId(5), latin("Canis lupus")).is_err());
+    }
+
+    #[test]
+    fn test_rename_and_undo() {
+        let mut db = database();
+        edit_details::apply(
+            EditDetails::SetSpecies(PostId(1), Some(latin("Lutra lutra"))),
+            &mut db,
+        );
+        edit_details::apply(
+            EditDetails::SetSpecies(PostId(1), Some(latin("Vulpes vulpes"))),
+            &mut db,
+        );
+
+        let rename = db
+            .plan_rename(SpeciesId(0), latin("Lutra europaea"))
+            .unwrap();
+        db.rename_species(&rename).unwrap();
+        db.refresh_caches();
+
+        assert_eq!(db.species[0].latin.as_str(), "Lutra europaea");
+        assert_eq!(db.species[0].synonyms, vec![latin("Lutra lutra")]);
+        assert_eq!(db.post(&PostId(0)).species, Some(latin("Lutra europaea")));
+        assert_eq!(db.post(&PostId(1)).species, Some(latin("Vulpes vulpes")));
+        assert!(db.species_by_latin(&latin("Lutra europaea")).is_some());
+        assert_eq!(
+            db.species_by_synonym(&latin("Lutra lutra"))
+                .map(|species| species.id),
+            Some(SpeciesId(0))
+        );
+
+        // the plan is stale now
+        assert!(db.rename_species(&rename).is_err());
+
+        // undo of a post edit made before the rename uses the new name
+        edit_details::apply(EditDetails::Undo(PostId(1)), &mut db);
+        assert_eq!(db.post(&PostId(1)).species, Some(latin("Lutra europaea")));
+        edit_details::apply(
+            EditDetails::SetSpecies(PostId(1), Some(latin("Vulpes vulpes"))),
+            &mut db,
+        );
+
+        // renaming back drops the synonym
+        let back = db.plan_rename(SpeciesId(0), latin("Lutra lutra")).unwrap();
+        assert_eq!(back.posts, vec![PostId(0), PostId(2)]);
+        db.rename_species(&back).unwrap();
+        assert_eq!(db.species[0].synonyms, vec![latin("Lutra europaea")]);
+
+        assert!(db.undo_rename().is_some());
+        assert_eq!(db.species[0].latin.as_str(), "Lutra europaea");
+        assert_eq!(db.species[0].synonyms, vec![latin("Lutra lutra")]);
+
+        assert!(db.undo_rename().is_some());
+        assert!(!db.can_undo_rename());
+        assert_eq!(db.species[0].latin.as_str(), "Lutra lutra");
+        assert!(db.species[0].synonyms.is_empty());
+        assert_eq!(db.post(&PostId(2)).species, Some(latin("Lutra lutra")));
+    }
+
+    #[test]
+    fn test_undo_rename_updates_later_posts() {
+        let mut db = database();
+        let rename = db
+            .plan_rename(SpeciesId(0), latin("Lutra europaea"))
+            .unwrap();
+        db.rename_species(&rename).unwrap();
+
+        edit_details::apply(
+            EditDetails::SetSpecies(PostId(1), Some(latin("Lutra europaea"))),
+            &mut db,
+        );
+
+        assert!(db.undo_rename().is_some());
+        db.refresh_caches();
+        for id in 0..3 {
+            assert_eq!(db.post(&PostId(id)).species, Some(latin("Lutra lutra")));
+        }
+        assert!(db.species_by_latin(&latin("Lutra europaea")).is_none());
+    }
+
+    #[test]
+    fn test_undo_rename_of_renamed_species() {
+        let mut db = database();
+        let rename = db
+            .plan_rename(SpeciesId(0), latin("Lutra europaea"))
+            .unwrap();
+        db.rename_species(&rename).unwrap();
+        db.species[0].latin = latin("Lutra felina");
+
+        assert_eq!(db.undo_rename(), None);
+        assert!(!db.can_undo_rename());
+        assert_eq!(db.species[0].latin.as_str(), "Lutra felina");
+        assert_eq!(db.post(&PostId(0)).species, Some(latin("Lutra europaea")));
+    }
+}
@@ -0,0 +1,157 @@
+use crate::Database;
+use crate::PostId;
+use crate::Selector;
+use crate::query::Query;
+use serde::Deserialize;
+use serde::Serialize;
+use std::borrow::Cow;
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct SavedSearchList(Vec<SavedSearch>);
+
+/// Named query, kept in the database like a smart album.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+}
+
+impl SavedSearch {
+    pub fn new(name: &str, query: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            query: query.to_owned(),
+        }
+    }
+
+    fn validate(&self) -> Result<Query, String> {
+        if self.name.trim().is_empty() {
+            return Err("name of a saved search cannot be empty".to_owned());
+        }
+
+        Query::parse(&self.query).map_err(|err| format!("invalid query: {err}"))
+    }
+}
+
+impl SavedSearchList {
+    pub fn add(&mut self, search: SavedSearch) -> Result<(), String> {
+        search.validate()?;
+        if self.get(&search.name).is_some() {
+            return Err(format!("saved search '{}' already exists", search.name));
+        }
+
+        self.0.push(search);
+
+        Ok(())
+    }
+
+    /// Replaces the search `name`; the search might get renamed.
+    pub fn update(&mut self, name: &str, search: SavedSearch) -> Result<bool, String> {
+        search.validate()?;
+        if search.name != name && self.get(&search.name).is_some() {
+            return Err(format!("saved search '{}' already exists", search.name));
+        }
+
+        let Some(existing) = self.0.iter_mut().find(|item| item.name == name) else {
+            return Err(format!("cannot find saved search '{name}'"));
+        };
+
+        if *existing == search {
+            return Ok(false);
+        }
+
+        *existing = search;
+
+        Ok(true)
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        let Some(index) = self.0.iter().position(|item| item.name == name) else {
+            return false;
+        };
+
+        self.0.remove(index);
+
+        true
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SavedSearch> {
+        self.0.iter().find(|item| item.name == name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SavedSearch> {
+        self.0.iter()
+    }
+}
+
+// --------------------------------------------------
+
+/// Set of posts to browse: either a date-based selector or a saved search.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Source {
+    Dates(Selector),
+    SavedSearch(String),
+}
+
+impl Source {
+    pub fn posts<'a>(&self, db: &'a Database) -> Cow<'a, [PostId]> {
+        let view = match self {
+            Self::Dates(selector) => db.picture_views.get(*selector),
+            Self::SavedSearch(name) => db
+                .saved_search_view(name)
+                .map(|list| Cow::Borrowed(list.as_slice())),
+        };
+
+        view.unwrap_or_default()
+    }
+}
+
+impl From<Selector> for Source {
+    fn from(selector: Selector) -> Self {
+        Self::Dates(selector)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_add_update_remove() {
+        let mut list = SavedSearchList::default();
+
+        assert!(list.add(SavedSearch::new("foxes", "tag:fox")).is_ok());
+        assert!(list.add(SavedSearch::new("foxes", "tag:vulpes")).is_err());
+        assert!(list.add(SavedSearch::new("", "tag:fox")).is_err());
+        assert!(list.add(SavedSearch::new("broken", "tag:fox OR")).is_err());
+        assert!(list.add(SavedSearch::new("wolves", "tag:wolf")).is_ok());
+
+        assert_eq!(
+            list.update("foxes", SavedSearch::new("foxes", "tag:fox")),
+            Ok(false)
+        );
+        assert_eq!(
+            list.update("foxes", SavedSearch::new("red foxes", "tag:fox")),
+            Ok(true)
+        );
+        assert!(list.get("foxes").is_none());
+        assert!(list.get("red foxes").is_some());
+        assert!(
+            list.update("red foxes", SavedSearch::new("wolves", "tag:fox"))
+                .is_err()
+        );
+        assert!(list.update("bears", SavedSearch::new("bears", "")).is_err());
+
+        assert!(list.remove("wolves"));
+        assert!(!list.remove("wolves"));
+        assert_eq!(list.len(), 1);
+    }
+}
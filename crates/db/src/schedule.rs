@@ -0,0 +1,186 @@
+use crate::Database;
+use crate::LocalDateTime;
+use crate::PostId;
+use crate::edit_details::SocialMediaLink;
+use chrono::Days;
+use chrono::Local;
+use chrono::NaiveDateTime;
+use chrono::NaiveTime;
+use chrono::TimeZone;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Hour of the first slot, when nothing is scheduled yet.
+pub const DEFAULT_HOUR: u32 = 18;
+
+pub const DATE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Planned publication of a post.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Schedule {
+    pub at: LocalDateTime,
+    /// Platforms where the post is still to be published
+    pub platforms: Vec<SocialMediaLink>,
+}
+
+impl Schedule {
+    pub fn is_due(&self, now: &LocalDateTime) -> bool {
+        self.at <= *now
+    }
+}
+
+/// Parses date and time in form `YYYY-MM-DD HH:MM`, in the local time zone.
+pub fn parse_date_time(s: &str) -> Result<LocalDateTime, String> {
+    let naive = NaiveDateTime::parse_from_str(s.trim(), DATE_TIME_FORMAT)
+        .map_err(|_| "expected date and time in form YYYY-MM-DD HH:MM".to_owned())?;
+
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .ok_or_else(|| format!("{s} does not exist in the local time zone"))
+}
+
+pub fn format_date_time(at: &LocalDateTime) -> String {
+    at.format(DATE_TIME_FORMAT).to_string()
+}
+
+impl Database {
+    /// Scheduled posts, ordered by their slots.
+    pub fn scheduled(&self) -> Vec<(PostId, &Schedule)> {
+        let mut result: Vec<_> = self
+            .posts
+            .iter()
+            .filter_map(|post| post.schedule.as_ref().map(|schedule| (post.id, schedule)))
+            .collect();
+
+        result.sort_by_key(|(id, schedule)| (schedule.at, *id));
+
+        result
+    }
+
+    /// Proposes a slot for the next post: a day after the last scheduled
+    /// one, at the same hour.
+    pub fn next_slot(&self, now: &LocalDateTime) -> LocalDateTime {
+        let last = self.scheduled().last().map(|(_, schedule)| schedule.at);
+        match last {
+            Some(at) if at > *now => at.checked_add_days(Days::new(1)).unwrap_or(at),
+            _ => {
+                let time = NaiveTime::from_hms_opt(DEFAULT_HOUR, 0, 0).unwrap();
+                let date = now.date_naive();
+                let date = if now.time() < time {
+                    date
+                } else {
+                    date.succ_opt().unwrap_or(date)
+                };
+
+                Local
+                    .from_local_datetime(&date.and_time(time))
+                    .earliest()
+                    .unwrap_or(*now)
+            }
+        }
+    }
+}
+
+/// Moves the post at position `from` to position `to`; slots stay in
+/// place, the posts between get shifted. Returns the changed schedules.
+pub fn reschedule(
+    queue: &[(PostId, &Schedule)],
+    from: usize,
+    to: usize,
+) -> Vec<(PostId, Schedule)> {
+    if from >= queue.len() || to >= queue.len() || from == to {
+        return Vec::new();
+    }
+
+    let mut order: Vec<usize> = (0..queue.len()).collect();
+    let item = order.remove(from);
+    order.insert(to, item);
+
+    order
+        .iter()
+        .enumerate()
+        .filter(|(slot, index)| *slot != **index)
+        .map(|(slot, index)| {
+            let (id, schedule) = &queue[*index];
+            let schedule = Schedule {
+                at: queue[slot].1.at,
+                platforms: schedule.platforms.clone(),
+            };
+
+            (*id, schedule)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn schedule(at: &str) -> Schedule {
+        Schedule {
+            at: parse_date_time(at).unwrap(),
+            platforms: vec![SocialMediaLink::Instagram],
+        }
+    }
+
+    #[test]
+    fn test_reschedule() {
+        let schedules = [
+            schedule("2025-05-01 18:00"),
+            schedule("2025-05-02 18:00"),
+            schedule("2025-05-03 09:30"),
+        ];
+        let queue: Vec<_> = schedules
+            .iter()
+            .enumerate()
+            .map(|(i, schedule)| (PostId(i), schedule))
+            .collect();
+
+        let changed = reschedule(&queue, 2, 0);
+        let changed: Vec<_> = changed
+            .iter()
+            .map(|(id, schedule)| (id.0, format_date_time(&schedule.at)))
+            .collect();
+        assert_eq!(
+            changed,
+            vec![
+                (2, "2025-05-01 18:00".to_owned()),
+                (0, "2025-05-02 18:00".to_owned()),
+                (1, "2025-05-03 09:30".to_owned()),
+            ]
+        );
+
+        let changed = reschedule(&queue, 0, 1);
+        assert_eq!(changed.len(), 2);
+        assert_eq!(changed[0].0, PostId(1));
+        assert_eq!(format_date_time(&changed[0].1.at), "2025-05-01 18:00");
+
+        assert!(reschedule(&queue, 1, 1).is_empty());
+        assert!(reschedule(&queue, 1, 5).is_empty());
+    }
+
+    #[test]
+    fn test_parse_date_time() {
+        let at = parse_date_time(" 2025-05-01 18:05 ").unwrap();
+        assert_eq!(format_date_time(&at), "2025-05-01 18:05");
+        assert!(parse_date_time("2025-05-01").is_err());
+        assert!(parse_date_time("2025-05-01 25:00").is_err());
+    }
+
+    #[test]
+    fn test_next_slot() {
+        let mut db = Database::default();
+        let now = parse_date_time("2025-05-01 10:00").unwrap();
+        assert_eq!(format_date_time(&db.next_slot(&now)), "2025-05-01 18:00");
+
+        let now = parse_date_time("2025-05-01 19:00").unwrap();
+        assert_eq!(format_date_time(&db.next_slot(&now)), "2025-05-02 18:00");
+
+        db.posts.push(crate::Post {
+            schedule: Some(schedule("2025-05-07 20:15")),
+            ..crate::Post::default()
+        });
+        assert_eq!(format_date_time(&db.next_slot(&now)), "2025-05-08 20:15");
+    }
+}
@@ -1,17 +1,61 @@
+use crate::text::fold;
+
+/// Kind of searchable text; matches in more specific kinds rank higher.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartKind {
+    #[default]
+    Description,
+    Tag,
+    Species,
+}
+
+impl PartKind {
+    const fn weight(&self) -> u32 {
+        match self {
+            Self::Description => 1,
+            Self::Tag => 2,
+            Self::Species => 3,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+struct Part {
+    kind: PartKind,
+    text: String,
+    words: Vec<String>,
+}
+
 #[derive(Default, Clone, PartialEq, Eq)]
 pub struct SearchParts {
-    parts: Vec<String>,
+    parts: Vec<Part>,
 }
 
+// How well a single term matches a word
+const EXACT: u32 = 10;
+const PREFIX: u32 = 6;
+const SUBSTRING: u32 = 3;
+const FUZZY: u32 = 2;
+const FUZZY_PREFIX: u32 = 1;
+
 impl SearchParts {
     pub fn clear(&mut self) {
         self.parts.clear();
     }
 
-    pub fn add(&mut self, s: &str) {
-        if !s.is_empty() {
-            self.parts.push(s.to_lowercase());
+    pub fn add(&mut self, kind: PartKind, s: &str) {
+        if s.is_empty() {
+            return;
         }
+
+        let text = fold(s);
+        let words = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| word.to_owned())
+            .collect();
+
+        self.parts.push(Part { kind, text, words });
     }
 
     pub fn matches(&self, phrase: &str) -> bool {
@@ -19,14 +63,207 @@ impl SearchParts {
             return true;
         }
 
-        self.parts.iter().any(|part| part.contains(phrase))
+        self.term_score(phrase) > 0
     }
 
+    /// Checks if all fragments match.
     pub fn matches_all(&self, fragments: &[&str]) -> bool {
-        if fragments.is_empty() {
-            return true;
+        self.score(fragments).is_some()
+    }
+
+    /// Returns the relevance of the fragments, provided all of them match.
+    pub fn score(&self, fragments: &[&str]) -> Option<u32> {
+        let mut total = 0;
+        for fragment in fragments {
+            let score = self.term_score(fragment);
+            if score == 0 {
+                return None;
+            }
+
+            total += score;
         }
 
-        fragments.iter().any(|phrase| self.matches(phrase))
+        Some(total)
+    }
+
+    /// Returns the relevance of a single term, zero if it doesn't match.
+    pub fn term_score(&self, term: &str) -> u32 {
+        let term = fold(term);
+        let term = term.as_str();
+        let word = term.trim_start_matches('#');
+
+        self.parts
+            .iter()
+            .map(|part| part.kind.weight() * part.quality(term, word))
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+impl Part {
+    fn quality(&self, term: &str, word: &str) -> u32 {
+        let mut best = 0;
+        if !word.is_empty() {
+            for candidate in &self.words {
+                best = best.max(word_quality(word, candidate));
+                if best == EXACT {
+                    return best;
+                }
+            }
+        }
+
+        if best < SUBSTRING && self.text.contains(term) {
+            best = SUBSTRING;
+        }
+
+        best
+    }
+}
+
+fn word_quality(term: &str, word: &str) -> u32 {
+    if term == word {
+        return EXACT;
+    }
+
+    if word.starts_with(term) {
+        return PREFIX;
+    }
+
+    let term: Vec<char> = term.chars().collect();
+    let word: Vec<char> = word.chars().collect();
+
+    let max = max_typos(term.len());
+    if max == 0 {
+        return 0;
+    }
+
+    if distance(&term, &word) <= max {
+        return FUZZY;
+    }
+
+    if word.len() > term.len() && distance(&term, &word[..term.len()]) <= max {
+        return FUZZY_PREFIX;
+    }
+
+    0
+}
+
+/// Number of typos tolerated in a term of given length.
+const fn max_typos(len: usize) -> usize {
+    match len {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Edit distance which counts transposition of adjacent characters as one edit.
+fn distance(a: &[char], b: &[char]) -> usize {
+    if a.len().abs_diff(b.len()) > max_typos(a.len()) {
+        return usize::MAX;
+    }
+
+    let n = b.len();
+    let mut prev2 = vec![0; n + 1];
+    let mut prev: Vec<usize> = (0..=n).collect();
+    let mut curr = vec![0; n + 1];
+
+    for i in 1..=a.len() {
+        curr[0] = i;
+        for j in 1..=n {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                curr[j] = curr[j].min(prev2[j - 2] + 1);
+            }
+        }
+
+        std::mem::swap(&mut prev2, &mut prev);
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[n]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parts() -> SearchParts {
+        let mut parts = SearchParts::default();
+        parts.add(PartKind::Description, "Ważka na trzcinie");
+        parts.add(PartKind::Tag, "#dragonfly");
+        parts.add(PartKind::Tag, "#insects");
+        parts.add(PartKind::Species, "Aeshna");
+        parts.add(PartKind::Species, "cyanea");
+
+        parts
+    }
+
+    #[test]
+    fn test_term_score() {
+        let parts = parts();
+        let cases = [
+            ("dragonfly", Tag, EXACT),
+            ("#dragonfly", Tag, EXACT),
+            ("dragon", Tag, PREFIX),
+            ("agonf", Tag, SUBSTRING),
+            ("dargonfly", Tag, FUZZY),
+            ("insect", Tag, PREFIX),
+            ("insetcs", Tag, FUZZY),
+            ("aeshna", Species, EXACT),
+            ("aesna", Species, FUZZY),
+            ("cyan", Species, PREFIX),
+            ("ważka", Description, EXACT),
+            ("trzc", Description, PREFIX),
+            ("na trzcinie", Description, SUBSTRING),
+            ("wazka", Description, EXACT),
+            ("Ważka", Description, EXACT),
+        ];
+
+        use PartKind::*;
+        for (term, kind, quality) in cases {
+            assert_eq!(
+                parts.term_score(term),
+                kind.weight() * quality,
+                "term = '{term}'"
+            );
+        }
+
+        for term in ["butterfly", "moth", "xyz"] {
+            assert_eq!(parts.term_score(term), 0, "term = '{term}'");
+        }
+    }
+
+    #[test]
+    fn test_all_terms_must_match() {
+        let parts = parts();
+
+        assert!(parts.matches_all(&[]));
+        assert!(parts.matches_all(&["dragonfly", "aeshna"]));
+        assert!(!parts.matches_all(&["dragonfly", "butterfly"]));
+        assert_eq!(parts.score(&["dragonfly", "butterfly"]), None);
+        assert_eq!(
+            parts.score(&["dragonfly", "aeshna"]),
+            Some(PartKind::Tag.weight() * EXACT + PartKind::Species.weight() * EXACT)
+        );
+    }
+
+    #[test]
+    fn test_distance() {
+        let cases = [
+            ("kitten", "kitten", 0),
+            ("kitten", "sitten", 1),
+            ("kitten", "kiten", 1),
+            ("kitten", "kittens", 1),
+            ("kitten", "iktten", 1),
+            ("kitten", "sitting", 3),
+        ];
+
+        for (a, b, expected) in cases {
+            let a: Vec<char> = a.chars().collect();
+            let b: Vec<char> = b.chars().collect();
+            assert_eq!(distance(&a, &b), expected, "{a:?} vs {b:?}");
+        }
     }
 }
@@ -1,31 +1,120 @@
 use crate::Date;
+use crate::Day;
 use crate::Month;
 use crate::Year;
+use chrono::NaiveDate;
+use chrono::Weekday;
 use serde::Deserialize;
 use serde::Serialize;
 use std::cmp::Ordering;
+use std::fmt::Display;
+use std::fmt::Formatter;
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
 pub enum Selector {
     ByDate(Date),
     ByMonth(Year, Month),
     ByYear(Year),
+    /// Inclusive range of dates
+    ByRange(Date, Date),
+    /// ISO 8601 week of the ISO year
+    ByWeek(Year, u8),
+    /// The given number of days, including today
+    LastDays(u16),
+    /// Season starting in the given year; the winter lasts until February
+    /// of the next year
+    BySeason(Year, Season),
+    /// The same day and month in years before the date
+    OnThisDay(Date),
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Deserialize, Serialize)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
 }
 
 impl Selector {
     pub fn matches(&self, v: &Date) -> bool {
+        self.matches_at(v, &Date::today())
+    }
+
+    pub fn matches_at(&self, v: &Date, today: &Date) -> bool {
         match self {
             Self::ByDate(date) => date == v,
             Self::ByMonth(year, month) => *year == v.year && *month == v.month,
             Self::ByYear(year) => *year == v.year,
+            Self::OnThisDay(date) => {
+                v.year < date.year && v.month == date.month && v.day == date.day
+            }
+            _ => self
+                .range(today)
+                .is_some_and(|(from, to)| from <= *v && *v <= to),
         }
     }
 
-    fn key(&self) -> (u16, u8, u8) {
+    /// Returns the inclusive range of dates covered by the selector;
+    /// `None` if the dates are not contiguous.
+    ///
+    /// Note that the returned dates might not exist (like 02-31), they
+    /// are meant only as bounds.
+    pub fn range(&self, today: &Date) -> Option<(Date, Date)> {
+        let date = |year: Year, month: u8, day: u8| Date {
+            year,
+            month: Month::new(month as usize).unwrap(),
+            day: Day::new(day as usize).unwrap(),
+        };
+
+        let range = match self {
+            Self::ByDate(d) => (*d, *d),
+            Self::ByMonth(year, month) => {
+                let month = month.as_u8();
+                (date(*year, month, 1), date(*year, month, 31))
+            }
+            Self::ByYear(year) => (date(*year, 1, 1), date(*year, 12, 31)),
+            Self::ByRange(from, to) => (*from, *to),
+            Self::ByWeek(year, week) => {
+                let monday = NaiveDate::from_isoywd_opt(*year as i32, *week as u32, Weekday::Mon)?;
+                let from = Date::from_naive(monday);
+                (from, from.add_days(6)?)
+            }
+            Self::LastDays(days) => {
+                let from = today.add_days(1 - (*days).max(1) as i64)?;
+                (from, *today)
+            }
+            Self::BySeason(year, season) => match season {
+                Season::Spring => (date(*year, 3, 1), date(*year, 5, 31)),
+                Season::Summer => (date(*year, 6, 1), date(*year, 8, 31)),
+                Season::Autumn => (date(*year, 9, 1), date(*year, 11, 31)),
+                Season::Winter => (date(*year, 12, 1), date(*year + 1, 2, 31)),
+            },
+            Self::OnThisDay(_) => return None,
+        };
+
+        Some(range)
+    }
+
+    /// Sort key: the chronological position comes first, so that a year
+    /// precedes its months and a month precedes its days; the variant and
+    /// the remaining fields make the order total.
+    fn key(&self) -> ((u16, u8, u8), u8, (u16, u8, u8)) {
+        fn tuple(date: &Date) -> (u16, u8, u8) {
+            (date.year, date.month.as_u8(), date.day.as_u8())
+        }
+
+        const NONE: (u16, u8, u8) = (0, 0, 0);
+
         match self {
-            Self::ByMonth(year, month) => (*year, month.as_u8(), u8::MAX),
-            Self::ByDate(date) => (date.year, date.month.as_u8(), date.day.as_u8()),
-            Self::ByYear(year) => (*year, u8::MAX, u8::MAX),
+            Self::ByDate(date) => (tuple(date), 0, NONE),
+            Self::ByMonth(year, month) => ((*year, month.as_u8(), u8::MAX), 1, NONE),
+            Self::ByYear(year) => ((*year, u8::MAX, u8::MAX), 2, NONE),
+            Self::ByRange(from, to) => (tuple(from), 3, tuple(to)),
+            Self::ByWeek(year, week) => ((*year, *week, 0), 4, NONE),
+            Self::LastDays(days) => ((u16::MAX, 0, 0), 5, (*days, 0, 0)),
+            Self::BySeason(year, season) => ((*year, *season as u8, 0), 6, NONE),
+            Self::OnThisDay(date) => (tuple(date), 7, NONE),
         }
     }
 }
@@ -41,3 +130,121 @@ impl PartialOrd for Selector {
         Some(self.cmp(other))
     }
 }
+
+impl Season {
+    pub const ALL: [Season; 4] = [Self::Spring, Self::Summer, Self::Autumn, Self::Winter];
+
+    pub fn of(date: &Date) -> (Year, Self) {
+        match date.month.as_u8() {
+            1 | 2 => (date.year - 1, Self::Winter),
+            3..=5 => (date.year, Self::Spring),
+            6..=8 => (date.year, Self::Summer),
+            9..=11 => (date.year, Self::Autumn),
+            _ => (date.year, Self::Winter),
+        }
+    }
+}
+
+impl Display for Season {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::Spring => f.write_str("spring"),
+            Self::Summer => f.write_str("summer"),
+            Self::Autumn => f.write_str("autumn"),
+            Self::Winter => f.write_str("winter"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    fn date(s: &str) -> Date {
+        Date::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_range() {
+        let today = date("2025-03-02");
+        let cases = [
+            (Selector::ByWeek(2025, 1), ("2024-12-30", "2025-01-05")),
+            (Selector::ByWeek(2020, 53), ("2020-12-28", "2021-01-03")),
+            (Selector::LastDays(1), ("2025-03-02", "2025-03-02")),
+            (Selector::LastDays(7), ("2025-02-24", "2025-03-02")),
+            (
+                Selector::BySeason(2024, Season::Winter),
+                ("2024-12-01", "2025-02-31"),
+            ),
+            (
+                Selector::BySeason(2024, Season::Summer),
+                ("2024-06-01", "2024-08-31"),
+            ),
+        ];
+
+        for (selector, (from, to)) in cases {
+            assert_eq!(
+                selector.range(&today),
+                Some((date(from), date(to))),
+                "{selector:?}"
+            );
+        }
+
+        assert_eq!(Selector::ByWeek(2025, 53).range(&today), None);
+        assert_eq!(Selector::OnThisDay(today).range(&today), None);
+    }
+
+    #[test]
+    fn test_matches() {
+        let today = date("2025-03-02");
+        let winter = Selector::BySeason(2024, Season::Winter);
+        assert!(winter.matches_at(&date("2024-12-24"), &today));
+        assert!(winter.matches_at(&date("2025-02-28"), &today));
+        assert!(!winter.matches_at(&date("2025-03-01"), &today));
+        assert!(!winter.matches_at(&date("2024-02-01"), &today));
+
+        let on_this_day = Selector::OnThisDay(today);
+        assert!(on_this_day.matches_at(&date("2019-03-02"), &today));
+        assert!(!on_this_day.matches_at(&date("2025-03-02"), &today));
+        assert!(!on_this_day.matches_at(&date("2019-03-03"), &today));
+
+        assert_eq!(Season::of(&date("2025-02-10")), (2024, Season::Winter));
+        assert_eq!(Season::of(&date("2025-12-10")), (2025, Season::Winter));
+    }
+
+    #[test]
+    fn test_order_is_consistent_with_eq() {
+        let day = date("2025-03-02");
+        let selectors = [
+            Selector::ByDate(day),
+            Selector::OnThisDay(day),
+            Selector::ByRange(day, date("2025-03-05")),
+            Selector::ByRange(day, date("2025-03-09")),
+            Selector::ByWeek(2025, 0),
+            Selector::ByWeek(2025, 1),
+            Selector::ByWeek(2025, 2),
+            Selector::ByWeek(2025, 3),
+            Selector::BySeason(2025, Season::Spring),
+            Selector::BySeason(2025, Season::Summer),
+            Selector::BySeason(2025, Season::Autumn),
+            Selector::BySeason(2025, Season::Winter),
+            Selector::LastDays(0),
+            Selector::LastDays(256),
+            Selector::ByMonth(2025, day.month),
+            Selector::ByYear(2025),
+        ];
+
+        let mut map = std::collections::BTreeMap::new();
+        for (i, selector) in selectors.iter().enumerate() {
+            assert_eq!(map.insert(*selector, i), None, "{selector:?}");
+        }
+
+        for (i, selector) in selectors.iter().enumerate() {
+            assert_eq!(map.get(selector), Some(&i), "{selector:?}");
+            for other in &selectors {
+                assert_eq!(selector == other, selector.cmp(other) == Ordering::Equal);
+            }
+        }
+    }
+}
@@ -1,13 +1,17 @@
+use super::ExampleOrder;
 use super::FileMetadata;
+use super::PartKind;
 use super::SearchParts;
 use super::SpeciesId;
+use super::Taxonomy;
 use serde::Deserialize;
 use serde::Serialize;
 use std::fmt::Display;
 use std::fmt::Error;
 use std::fmt::Formatter;
+use std::path::PathBuf;
 
-#[derive(Default, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Latin(String);
 
 impl Latin {
@@ -64,6 +68,18 @@ pub struct Species {
     pub wikipedia_en: String,
     pub category: Option<String>,
 
+    /// Former Latin names, recorded when the species gets renamed
+    #[serde(default)]
+    pub synonyms: Vec<Latin>,
+
+    /// Ranks from the kingdom down to the genus
+    #[serde(default, skip_serializing_if = "Taxonomy::is_empty")]
+    pub taxonomy: Taxonomy,
+
+    /// Cover and manual order of the examples
+    #[serde(default, skip_serializing_if = "ExampleOrder::is_empty")]
+    pub example_order: ExampleOrder,
+
     #[serde(skip)]
     pub id: SpeciesId,
 
@@ -75,17 +91,20 @@ pub struct Species {
 
     #[serde(skip)]
     pub current_example: usize,
+
+    /// Path of the current example, kept when the examples change
+    #[serde(skip)]
+    pub selected_example: Option<PathBuf>,
 }
 
 impl Species {
+    /// Copies the data except the Latin name, which changes only through
+    /// [`Database::rename_species`] as posts refer to it.
+    ///
+    /// [`Database::rename_species`]: crate::Database::rename_species
     pub fn update(&mut self, other: &Self) -> bool {
         let mut changed = false;
 
-        if self.latin != other.latin {
-            self.latin = other.latin.clone();
-            changed = true;
-        }
-
         if self.pl != other.pl {
             self.pl = other.pl.clone();
             changed = true;
@@ -116,6 +135,21 @@ impl Species {
             changed = true;
         }
 
+        if self.synonyms != other.synonyms {
+            self.synonyms = other.synonyms.clone();
+            changed = true;
+        }
+
+        if self.taxonomy != other.taxonomy {
+            self.taxonomy = other.taxonomy.clone();
+            changed = true;
+        }
+
+        if self.example_order != other.example_order {
+            self.example_order = other.example_order.clone();
+            changed = true;
+        }
+
         if changed {
             self.refresh();
         }
@@ -125,16 +159,21 @@ impl Species {
 
     pub fn refresh(&mut self) {
         let items = [&self.latin.0, &self.pl, &self.en];
+        let synonyms = self.synonyms.iter().map(|synonym| &synonym.0);
 
         self.search_parts.clear();
-        for item in items {
+        for item in items.into_iter().chain(synonyms) {
             for part in item.split_whitespace() {
-                self.search_parts.add(part);
+                self.search_parts.add(PartKind::Species, part);
             }
         }
 
         if let Some(category) = &self.category {
-            self.search_parts.add(category);
+            self.search_parts.add(PartKind::Species, category);
+        }
+
+        for (_, name) in self.taxonomy.iter() {
+            self.search_parts.add(PartKind::Species, name);
         }
     }
 
@@ -144,10 +183,7 @@ impl Species {
             return;
         }
 
-        self.current_example += 1;
-        if self.current_example >= n {
-            self.current_example = 0;
-        }
+        self.select_example((self.current_example + 1) % n);
     }
 
     pub fn prev_example(&mut self) {
@@ -156,10 +192,6 @@ impl Species {
             return;
         }
 
-        if self.current_example > 0 {
-            self.current_example -= 1;
-        } else {
-            self.current_example = n - 1;
-        }
+        self.select_example((self.current_example + n - 1) % n);
     }
 }
@@ -1,3 +1,4 @@
+use crate::text::compare;
 use serde::Deserialize;
 use serde::Serialize;
 use std::cmp::Ordering;
@@ -59,7 +60,7 @@ impl PartialOrd for TranslatedTag {
 
 impl Ord for TranslatedTag {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.base().cmp(other.base())
+        compare(self.base(), other.base())
     }
 }
 
@@ -0,0 +1,277 @@
+//! Taxonomic ranks of species and the tree of taxa built from them.
+//!
+//! All ranks are optional; the genus defaults to the first word of the
+//! Latin name. Species with missing ranks are attached to the nearest
+//! known ancestor.
+
+use crate::Database;
+use crate::PostId;
+use crate::Species;
+use crate::text::fold;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Rank {
+    Kingdom,
+    Phylum,
+    Class,
+    Order,
+    Family,
+    Genus,
+}
+
+impl Rank {
+    pub const ALL: [Self; 6] = [
+        Self::Kingdom,
+        Self::Phylum,
+        Self::Class,
+        Self::Order,
+        Self::Family,
+        Self::Genus,
+    ];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Kingdom => "kingdom",
+            Self::Phylum => "phylum",
+            Self::Class => "class",
+            Self::Order => "order",
+            Self::Family => "family",
+            Self::Genus => "genus",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|rank| rank.name() == name)
+    }
+}
+
+/// Explicitly set ranks of a species.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Taxonomy(BTreeMap<Rank, String>);
+
+impl Taxonomy {
+    pub fn get(&self, rank: Rank) -> Option<&str> {
+        self.0.get(&rank).map(|name| name.as_str())
+    }
+
+    /// Sets the rank; an empty name removes it.
+    pub fn set(&mut self, rank: Rank, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            self.0.remove(&rank);
+        } else {
+            self.0.insert(rank, name.to_owned());
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Rank, &str)> {
+        self.0.iter().map(|(rank, name)| (*rank, name.as_str()))
+    }
+}
+
+impl Species {
+    /// Name of the taxon of the rank the species belongs to.
+    pub fn rank(&self, rank: Rank) -> Option<&str> {
+        match self.taxonomy.get(rank) {
+            Some(name) => Some(name),
+            None if rank == Rank::Genus => self.latin.as_str().split_whitespace().next(),
+            None => None,
+        }
+    }
+
+    /// Known ranks from the most general one.
+    pub fn lineage(&self) -> Vec<(Rank, &str)> {
+        Rank::ALL
+            .into_iter()
+            .filter_map(|rank| self.rank(rank).map(|name| (rank, name)))
+            .collect()
+    }
+
+    /// Whether the rank contains the phrase, which should be folded.
+    pub fn rank_contains(&self, rank: Rank, phrase: &str) -> bool {
+        self.rank(rank)
+            .is_some_and(|name| fold(name).contains(phrase))
+    }
+}
+
+/// Taxon with the number of species and posts below it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaxonNode {
+    pub rank: Rank,
+    pub name: String,
+    pub species: usize,
+    /// Posts counted once, even when showing several species of the taxon
+    pub posts: usize,
+    pub children: Vec<TaxonNode>,
+    post_ids: BTreeSet<PostId>,
+}
+
+impl TaxonNode {
+    fn insert(nodes: &mut Vec<Self>, lineage: &[(Rank, &str)], posts: &[PostId]) {
+        let Some(((rank, name), rest)) = lineage.split_first() else {
+            return;
+        };
+
+        let index = match nodes
+            .iter()
+            .position(|node| node.rank == *rank && node.name == *name)
+        {
+            Some(index) => index,
+            None => {
+                nodes.push(Self {
+                    rank: *rank,
+                    name: (*name).to_owned(),
+                    species: 0,
+                    posts: 0,
+                    children: Vec::new(),
+                    post_ids: BTreeSet::new(),
+                });
+                nodes.len() - 1
+            }
+        };
+
+        let node = &mut nodes[index];
+        node.species += 1;
+        node.post_ids.extend(posts);
+        Self::insert(&mut node.children, rest, posts);
+    }
+
+    /// Counts the posts and sorts the nodes.
+    fn finish(nodes: &mut [Self]) {
+        nodes.sort_by(|a, b| (a.rank, &a.name).cmp(&(b.rank, &b.name)));
+        for node in nodes {
+            node.posts = std::mem::take(&mut node.post_ids).len();
+            Self::finish(&mut node.children);
+        }
+    }
+}
+
+impl Database {
+    /// Taxa of all species; each level is sorted by rank and name.
+    pub fn taxonomy_tree(&self) -> Vec<TaxonNode> {
+        let mut posts = HashMap::<_, Vec<PostId>>::new();
+        for post in self.posts.iter() {
+            for latin in post.all_species() {
+                posts.entry(latin).or_default().push(post.id);
+            }
+        }
+
+        let mut result = Vec::new();
+        for species in &self.species {
+            let ids = posts
+                .get(&species.latin)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            TaxonNode::insert(&mut result, &species.lineage(), ids);
+        }
+        TaxonNode::finish(&mut result);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Latin;
+    use crate::Post;
+
+    fn species(latin: &str, ranks: &[(Rank, &str)]) -> Species {
+        let mut taxonomy = Taxonomy::default();
+        for (rank, name) in ranks {
+            taxonomy.set(*rank, name);
+        }
+
+        Species {
+            latin: Latin::from(latin.to_owned()),
+            taxonomy,
+            ..Species::default()
+        }
+    }
+
+    #[test]
+    fn test_rank() {
+        let vanessa = species(
+            "Vanessa atalanta",
+            &[(Rank::Order, "Lepidoptera"), (Rank::Family, "Nymphalidae")],
+        );
+        assert_eq!(vanessa.rank(Rank::Genus), Some("Vanessa"));
+        assert_eq!(vanessa.rank(Rank::Kingdom), None);
+        assert_eq!(
+            vanessa.lineage(),
+            vec![
+                (Rank::Order, "Lepidoptera"),
+                (Rank::Family, "Nymphalidae"),
+                (Rank::Genus, "Vanessa")
+            ]
+        );
+        assert!(vanessa.rank_contains(Rank::Family, "nymphal"));
+
+        let mut taxonomy = vanessa.taxonomy.clone();
+        taxonomy.set(Rank::Order, " ");
+        assert_eq!(taxonomy.get(Rank::Order), None);
+    }
+
+    #[test]
+    fn test_taxonomy_tree() {
+        let mut db = Database::default();
+        let lepidoptera = (Rank::Order, "Lepidoptera");
+        db.add_species(&species(
+            "Vanessa atalanta",
+            &[lepidoptera, (Rank::Family, "Nymphalidae")],
+        ));
+        db.add_species(&species(
+            "Aglais io",
+            &[lepidoptera, (Rank::Family, "Nymphalidae")],
+        ));
+        db.add_species(&species("Papilio machaon", &[lepidoptera]));
+        db.add_species(&species("Vulpes vulpes", &[]));
+        for (i, latin) in ["Aglais io", "Aglais io", "Papilio machaon"]
+            .iter()
+            .enumerate()
+        {
+            db.posts.push(Post {
+                id: PostId(i),
+                species: Some(Latin::from((*latin).to_owned())),
+                ..Post::default()
+            });
+        }
+        // both species are nymphalids, the post is counted once
+        db.post_mut(&PostId(0))
+            .other_species
+            .push(Latin::from("Vanessa atalanta".to_owned()));
+
+        let tree = db.taxonomy_tree();
+        assert_eq!(tree.len(), 2);
+        assert_eq!(
+            (tree[0].name.as_str(), tree[0].species, tree[0].posts),
+            ("Lepidoptera", 3, 3)
+        );
+        assert_eq!(tree[1].rank, Rank::Genus);
+        assert_eq!(tree[1].name, "Vulpes");
+
+        let children: Vec<_> = tree[0]
+            .children
+            .iter()
+            .map(|node| (node.rank, node.name.as_str(), node.posts))
+            .collect();
+        assert_eq!(
+            children,
+            vec![
+                (Rank::Family, "Nymphalidae", 2),
+                (Rank::Genus, "Papilio", 1)
+            ]
+        );
+        assert_eq!(tree[0].children[0].children.len(), 2);
+    }
+}
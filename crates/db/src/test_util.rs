@@ -0,0 +1,88 @@
+//! Databases for tests, shared with other crates through the `test-util`
+//! feature. Tests add only the species, posts and settings they check.
+
+use crate::Database;
+use crate::Latin;
+use crate::Post;
+use crate::Species;
+use crate::Translation;
+use std::path::Path;
+
+pub fn latin(name: &str) -> Latin {
+    Latin::from(name.to_owned())
+}
+
+/// Species known only by the Latin name.
+pub fn species(name: &str) -> Species {
+    Species {
+        latin: latin(name),
+        ..Species::default()
+    }
+}
+
+/// Vulpes vulpes, "lis" in Polish and "red fox" in English.
+pub fn red_fox() -> Species {
+    Species {
+        pl: "lis".to_owned(),
+        en: "red fox".to_owned(),
+        ..species("Vulpes vulpes")
+    }
+}
+
+/// Post showing the species.
+pub fn post_of(name: &str) -> Post {
+    Post {
+        species: Some(latin(name)),
+        ..Post::default()
+    }
+}
+
+/// Database filled in a chain of calls; posts get ids in the order they
+/// were added.
+#[derive(Default)]
+pub struct DatabaseBuilder {
+    db: Database,
+}
+
+impl DatabaseBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Database stored in the directory, thus photos of posts are there.
+    pub fn in_dir(dir: &Path) -> Self {
+        Self {
+            db: Database::new(&dir.join("db.toml")),
+        }
+    }
+
+    pub fn species(mut self, species: Species) -> Self {
+        self.db.add_species(&species);
+        self
+    }
+
+    pub fn post(mut self, post: Post) -> Self {
+        self.db.posts.push(post);
+        self
+    }
+
+    pub fn translation(mut self, en: &str, pl: &str) -> Self {
+        self.db.tag_translations.0.push(Translation {
+            en: en.to_owned(),
+            pl: pl.to_owned(),
+        });
+        self
+    }
+
+    pub fn ignored_tag(mut self, tag: &str) -> Self {
+        self.db.ignored_tags.insert(tag.to_owned());
+        self
+    }
+
+    /// Assigns ids and refreshes caches.
+    pub fn build(mut self) -> Database {
+        self.db.refresh_all_records();
+        self.db.refresh_caches();
+        self.db
+    }
+}
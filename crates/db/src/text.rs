@@ -0,0 +1,150 @@
+use std::cmp::Ordering;
+
+/// Converts the text into a form suitable for searching: lower case,
+/// without diacritics, so that "zolw" finds "Żółw".
+pub fn fold(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars().flat_map(char::to_lowercase) {
+        match fold_char(c) {
+            Folded::Char(c) => result.push(c),
+            Folded::Str(s) => result.push_str(s),
+        }
+    }
+
+    result
+}
+
+/// Compares texts according to the Polish alphabet (a, ą, b, c, ć, ...).
+/// Letters with other diacritics sort along with their base letters;
+/// when texts differ only in case, the lower case goes first.
+pub fn compare(a: &str, b: &str) -> Ordering {
+    let lower = |s: &str| s.chars().flat_map(char::to_lowercase).collect::<Vec<_>>();
+    let a_lower = lower(a);
+    let b_lower = lower(b);
+
+    let a_weights = a_lower.iter().map(|c| primary_weight(*c));
+    let b_weights = b_lower.iter().map(|c| primary_weight(*c));
+
+    a_weights
+        .cmp(b_weights)
+        .then_with(|| a_lower.cmp(&b_lower))
+        .then_with(|| {
+            let a_case = a.chars().map(char::is_uppercase);
+            let b_case = b.chars().map(char::is_uppercase);
+            a_case.cmp(b_case)
+        })
+        .then_with(|| a.cmp(b))
+}
+
+const POLISH_ALPHABET: &str = "aąbcćdeęfghijklłmnńoópqrstuvwxyzźż";
+
+// All letters go after digits, punctuation and whitespace
+const LETTERS: u32 = char::MAX as u32 + 1;
+
+fn primary_weight(c: char) -> u32 {
+    if let Some(index) = POLISH_ALPHABET.chars().position(|letter| letter == c) {
+        return LETTERS + index as u32;
+    }
+
+    if let Folded::Char(base) = fold_char(c)
+        && base != c
+    {
+        return primary_weight(base);
+    }
+
+    if c.is_alphabetic() {
+        LETTERS + POLISH_ALPHABET.len() as u32 + c as u32
+    } else {
+        c as u32
+    }
+}
+
+enum Folded {
+    Char(char),
+    Str(&'static str),
+}
+
+fn fold_char(c: char) -> Folded {
+    let folded = match c {
+        'ą' | 'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' => 'a',
+        'ć' | 'ç' | 'č' | 'ĉ' | 'ċ' => 'c',
+        'ď' | 'đ' => 'd',
+        'ę' | 'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ě' | 'ė' => 'e',
+        'ğ' | 'ģ' => 'g',
+        'ì' | 'í' | 'î' | 'ï' | 'ī' | 'ı' | 'į' => 'i',
+        'ķ' => 'k',
+        'ł' | 'ľ' | 'ĺ' | 'ļ' => 'l',
+        'ń' | 'ñ' | 'ň' | 'ņ' => 'n',
+        'ó' | 'ò' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => 'o',
+        'ŕ' | 'ř' => 'r',
+        'ś' | 'š' | 'ş' | 'ș' => 's',
+        'ť' | 'ţ' | 'ț' => 't',
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' | 'ų' => 'u',
+        'ý' | 'ÿ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        'ß' => return Folded::Str("ss"),
+        'æ' => return Folded::Str("ae"),
+        'œ' => return Folded::Str("oe"),
+        _ => c,
+    };
+
+    Folded::Char(folded)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fold() {
+        assert_eq!(fold("Żółw błotny"), "zolw blotny");
+        assert_eq!(fold("ŚWIERSZCZ"), "swierszcz");
+        assert_eq!(fold("Crème brûlée"), "creme brulee");
+        assert_eq!(fold("Straße"), "strasse");
+    }
+
+    #[test]
+    fn test_compare() {
+        let mut words = vec![
+            "żuraw",
+            "zięba",
+            "łabędź",
+            "lis",
+            "śmieszka",
+            "sikora",
+            "ćma",
+            "czapla",
+            "cyranka",
+            "Łoś",
+            "łoś",
+            "ąb",
+            "az",
+            "éclair",
+            "ezop",
+            "echo",
+        ];
+        words.sort_by(|a, b| compare(a, b));
+
+        assert_eq!(
+            words,
+            [
+                "az",
+                "ąb",
+                "cyranka",
+                "czapla",
+                "ćma",
+                "echo",
+                "éclair",
+                "ezop",
+                "lis",
+                "łabędź",
+                "łoś",
+                "Łoś",
+                "sikora",
+                "śmieszka",
+                "zięba",
+                "żuraw",
+            ]
+        );
+    }
+}
@@ -0,0 +1,261 @@
+//! Darwin Core Archive: a ZIP file with the occurrence core, the
+//! multimedia extension, the descriptor `meta.xml` and the metadata
+//! `eml.xml`.
+
+use crate::Occurrence;
+use crate::Summary;
+use crate::escape_xml;
+use crate::occurrences;
+use db::Database;
+use db::Date;
+use db::PostId;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use zip::CompressionMethod;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+const DWC: &str = "http://rs.tdwg.org/dwc/terms/";
+const DC: &str = "http://purl.org/dc/terms/";
+
+const OCCURRENCE_TERMS: [&str; 10] = [
+    "occurrenceID",
+    "basisOfRecord",
+    "scientificName",
+    "vernacularName",
+    "eventDate",
+    "decimalLatitude",
+    "decimalLongitude",
+    "geodeticDatum",
+    "recordedBy",
+    "occurrenceRemarks",
+];
+
+const MULTIMEDIA_TERMS: [&str; 7] = [
+    "type",
+    "format",
+    "identifier",
+    "description",
+    "created",
+    "creator",
+    "license",
+];
+
+/// Exports the posts, or all posts when `None`.
+pub fn export_archive(
+    db: &Database,
+    posts: Option<&[PostId]>,
+    path: &Path,
+) -> Result<Summary, String> {
+    let (occurrences, summary) = occurrences(db, posts)?;
+
+    let settings = &db.observation_settings;
+    let error = |err: &dyn std::fmt::Display| format!("{}: {err}", path.display());
+    let file = File::create(path).map_err(|err| error(&err))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let entries = [
+        ("meta.xml", meta()),
+        ("eml.xml", eml(&settings.observer, &settings.license)),
+        (
+            "occurrence.txt",
+            occurrence_table(&occurrences, &settings.observer),
+        ),
+        (
+            "multimedia.txt",
+            multimedia_table(&occurrences, &settings.observer, &settings.license),
+        ),
+    ];
+
+    for (name, contents) in entries {
+        zip.start_file(name, options).map_err(|err| error(&err))?;
+        zip.write_all(contents.as_bytes())
+            .map_err(|err| error(&err))?;
+    }
+
+    zip.finish().map_err(|err| error(&err))?;
+
+    Ok(summary)
+}
+
+fn occurrence_table(occurrences: &[Occurrence], observer: &str) -> String {
+    let mut result = row(&[&["id"], &OCCURRENCE_TERMS[..]].concat());
+    for occurrence in occurrences {
+        let (latitude, longitude, datum) = match occurrence.location {
+            Some(location) => (
+                location.latitude.to_string(),
+                location.longitude.to_string(),
+                "WGS84",
+            ),
+            None => (String::new(), String::new(), ""),
+        };
+
+        result.push_str(&row(&[
+            &occurrence.id,
+            &occurrence.id,
+            "HumanObservation",
+            occurrence.scientific_name,
+            occurrence.vernacular_name,
+            &occurrence.date.to_string(),
+            &latitude,
+            &longitude,
+            datum,
+            observer,
+            occurrence.remarks,
+        ]));
+    }
+
+    result
+}
+
+fn multimedia_table(occurrences: &[Occurrence], observer: &str, license: &str) -> String {
+    let mut result = row(&[&["coreid"], &MULTIMEDIA_TERMS[..]].concat());
+    for occurrence in occurrences {
+        for medium in &occurrence.media {
+            result.push_str(&row(&[
+                &occurrence.id,
+                "StillImage",
+                "image/jpeg",
+                &medium.identifier,
+                &medium.description,
+                &occurrence.date.to_string(),
+                observer,
+                license,
+            ]));
+        }
+    }
+
+    result
+}
+
+/// Tab-separated line; the archive declares no quoting, thus tabs and
+/// line breaks in values are replaced with spaces.
+fn row(values: &[&str]) -> String {
+    let values: Vec<String> = values
+        .iter()
+        .map(|value| value.replace(['\t', '\r', '\n'], " "))
+        .collect();
+
+    format!("{}\n", values.join("\t"))
+}
+
+fn meta() -> String {
+    let fields = |namespace: &str, terms: &[&str]| -> String {
+        terms
+            .iter()
+            .enumerate()
+            .map(|(i, term)| {
+                format!(
+                    "    <field index=\"{}\" term=\"{namespace}{term}\"/>\n",
+                    i + 1
+                )
+            })
+            .collect()
+    };
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <archive xmlns=\"http://rs.tdwg.org/dwc/text/\" metadata=\"eml.xml\">\n\
+         \x20 <core encoding=\"UTF-8\" fieldsTerminatedBy=\"\\t\" linesTerminatedBy=\"\\n\" fieldsEnclosedBy=\"\" ignoreHeaderLines=\"1\" rowType=\"{DWC}Occurrence\">\n\
+         \x20   <files><location>occurrence.txt</location></files>\n\
+         \x20   <id index=\"0\"/>\n\
+         {}\
+         \x20 </core>\n\
+         \x20 <extension encoding=\"UTF-8\" fieldsTerminatedBy=\"\\t\" linesTerminatedBy=\"\\n\" fieldsEnclosedBy=\"\" ignoreHeaderLines=\"1\" rowType=\"http://rs.gbif.org/terms/1.0/Multimedia\">\n\
+         \x20   <files><location>multimedia.txt</location></files>\n\
+         \x20   <coreid index=\"0\"/>\n\
+         {}\
+         \x20 </extension>\n\
+         </archive>\n",
+        fields(DWC, &OCCURRENCE_TERMS),
+        fields(DC, &MULTIMEDIA_TERMS),
+    )
+}
+
+fn eml(observer: &str, license: &str) -> String {
+    let observer = escape_xml(observer);
+    let title = if observer.is_empty() {
+        "Photo observations".to_owned()
+    } else {
+        format!("Photo observations by {observer}")
+    };
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <eml:eml xmlns:eml=\"eml://ecoinformatics.org/eml-2.1.1\" packageId=\"publish-photos\" system=\"publish-photos\" xml:lang=\"en\">\n\
+         <dataset>\n\
+         <title>{title}</title>\n\
+         <creator><individualName><surName>{observer}</surName></individualName></creator>\n\
+         <pubDate>{}</pubDate>\n\
+         <intellectualRights><para>{}</para></intellectualRights>\n\
+         </dataset>\n\
+         </eml:eml>\n",
+        Date::today(),
+        escape_xml(license),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::database;
+    use std::io::Read;
+    use zip::ZipArchive;
+
+    #[test]
+    fn test_export_archive() {
+        let db = database();
+        let path = std::env::temp_dir().join(format!("dwc-archive-{}.zip", std::process::id()));
+
+        let summary = export_archive(&db, None, &path).unwrap();
+        assert_eq!(summary.occurrences, 2);
+
+        let mut zip = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut read = |name: &str| {
+            let mut contents = String::new();
+            zip.by_name(name)
+                .unwrap()
+                .read_to_string(&mut contents)
+                .unwrap();
+            contents
+        };
+
+        let occurrence = read("occurrence.txt");
+        let lines: Vec<&str> = occurrence.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            "id\toccurrenceID\tbasisOfRecord\tscientificName\tvernacularName\teventDate\t\
+             decimalLatitude\tdecimalLongitude\tgeodeticDatum\trecordedBy\toccurrenceRemarks"
+        );
+        assert_eq!(
+            lines[1],
+            "2024/lis.jpg\t2024/lis.jpg\tHumanObservation\tVulpes vulpes\tred fox\t2024-12-24\t\
+             \t\t\tJan Kowalski\tFox in the forest"
+        );
+        assert_eq!(
+            lines[2],
+            "2025/lis rudy.jpg\t2025/lis rudy.jpg\tHumanObservation\tVulpes vulpes\tred fox\t\
+             2025-05-02\t52.2297\t21.0122\tWGS84\tJan Kowalski\tFox in the forest"
+        );
+
+        let multimedia = read("multimedia.txt");
+        assert!(multimedia.contains(
+            "2025/lis rudy.jpg\tStillImage\timage/jpeg\t\
+             https://example.com/photos/2025/lis%20rudy.jpg\tRed fox (Vulpes vulpes): forest, winter\t\
+             2025-05-02\tJan Kowalski\tCC-BY-4.0\n"
+        ));
+
+        let meta = read("meta.xml");
+        assert!(
+            meta.contains("<field index=\"5\" term=\"http://rs.tdwg.org/dwc/terms/eventDate\"/>")
+        );
+        assert!(meta.contains("<field index=\"3\" term=\"http://purl.org/dc/terms/identifier\"/>"));
+
+        assert!(read("eml.xml").contains("<title>Photo observations by Jan Kowalski</title>"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
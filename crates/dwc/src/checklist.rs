@@ -0,0 +1,810 @@
+//! Species checklists: CSV files, Darwin Core taxon files (`taxon.txt`)
+//! or whole Darwin Core Archives with a taxon core.
+//!
+//! Importing is done in three steps: [`read_table`] loads the raw
+//! columns, a [`Mapping`] assigns them to species fields and [`preview`]
+//! matches rows with existing species by their Latin name or a synonym.
+//! After the user picks a [`Resolution`] for each row, [`apply`] stores
+//! the result.
+
+use db::Database;
+use db::Latin;
+use db::Rank;
+use db::Species;
+use db::SpeciesId;
+use std::collections::BTreeSet;
+use std::io::Read;
+use std::path::Path;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Table {
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// Number of lines preceding the rows
+    pub header_lines: usize,
+}
+
+/// Layout of a delimited text file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Format {
+    delimiter: u8,
+    quote: Option<u8>,
+    /// Lines to skip, the first one holds column names
+    header_lines: usize,
+}
+
+/// Core file of a Darwin Core Archive as declared in `meta.xml`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CoreFile {
+    location: String,
+    format: Format,
+    /// Index and term of the declared columns
+    fields: Vec<(usize, String)>,
+}
+
+impl CoreFile {
+    /// Column names taken from the declared terms; columns without a term
+    /// keep the name from the file, if any.
+    fn header(&self, table: &Table) -> Vec<String> {
+        let width = self
+            .fields
+            .iter()
+            .map(|(index, _)| index + 1)
+            .chain(table.rows.iter().map(Vec::len))
+            .chain([table.header.len()])
+            .max()
+            .unwrap_or_default();
+
+        let mut header = table.header.clone();
+        header.resize(width, String::new());
+        for (index, term) in &self.fields {
+            header[*index] = term.clone();
+        }
+
+        header
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Latin,
+    Polish,
+    WikipediaPl,
+    InsektariumPl,
+    English,
+    WikipediaEn,
+    Category,
+    Rank(Rank),
+}
+
+impl Field {
+    pub const ALL: [Self; 13] = [
+        Self::Latin,
+        Self::Polish,
+        Self::WikipediaPl,
+        Self::InsektariumPl,
+        Self::English,
+        Self::WikipediaEn,
+        Self::Category,
+        Self::Rank(Rank::Kingdom),
+        Self::Rank(Rank::Phylum),
+        Self::Rank(Rank::Class),
+        Self::Rank(Rank::Order),
+        Self::Rank(Rank::Family),
+        Self::Rank(Rank::Genus),
+    ];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Latin => "Latin",
+            Self::Polish => "Polish",
+            Self::WikipediaPl => "Wikipedia (pl)",
+            Self::InsektariumPl => "insektarium",
+            Self::English => "English",
+            Self::WikipediaEn => "Wikipedia (en)",
+            Self::Category => "category",
+            Self::Rank(rank) => rank.name(),
+        }
+    }
+
+    fn get<'a>(&self, species: &'a Species) -> &'a str {
+        match self {
+            Self::Latin => species.latin.as_str(),
+            Self::Polish => &species.pl,
+            Self::WikipediaPl => &species.wikipedia_pl,
+            Self::InsektariumPl => &species.insektarium_pl,
+            Self::English => &species.en,
+            Self::WikipediaEn => &species.wikipedia_en,
+            Self::Category => species.category.as_deref().unwrap_or_default(),
+            Self::Rank(rank) => species.taxonomy.get(*rank).unwrap_or_default(),
+        }
+    }
+
+    fn set(&self, species: &mut Species, value: String) {
+        match self {
+            Self::Latin => species.latin = Latin::from(value),
+            Self::Polish => species.pl = value,
+            Self::WikipediaPl => species.wikipedia_pl = value,
+            Self::InsektariumPl => species.insektarium_pl = value,
+            Self::English => species.en = value,
+            Self::WikipediaEn => species.wikipedia_en = value,
+            Self::Category => species.category = Some(value).filter(|s| !s.is_empty()),
+            Self::Rank(rank) => species.taxonomy.set(*rank, &value),
+        }
+    }
+
+    /// Field for a column name used by Darwin Core or by our own export.
+    fn guess(column: &str) -> Option<Self> {
+        let column = column.trim().to_lowercase();
+        let column = column.rsplit(['/', ':']).next().unwrap_or_default();
+        let field = match column {
+            "latin" | "scientificname" | "scientific name" | "species" => Self::Latin,
+            "pl" | "polish" | "polski" | "nazwa polska" => Self::Polish,
+            "wikipedia_pl" | "wikipedia pl" => Self::WikipediaPl,
+            "insektarium" | "insektarium_pl" => Self::InsektariumPl,
+            "en" | "english" | "vernacularname" | "vernacular name" | "common name" => {
+                Self::English
+            }
+            "wikipedia_en" | "wikipedia en" => Self::WikipediaEn,
+            "category" => Self::Category,
+            _ => return Rank::from_name(column).map(Self::Rank),
+        };
+
+        Some(field)
+    }
+}
+
+/// Field assigned to each column; unassigned columns are ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping(pub Vec<Option<Field>>);
+
+impl Mapping {
+    /// Assigns fields based on column names, each field at most once.
+    pub fn guess(header: &[String]) -> Self {
+        let mut used = Vec::new();
+        let fields = header
+            .iter()
+            .map(|column| {
+                let field = Field::guess(column).filter(|field| !used.contains(field))?;
+                used.push(field);
+                Some(field)
+            })
+            .collect();
+
+        Self(fields)
+    }
+
+    pub fn check(&self) -> Result<(), String> {
+        for field in Field::ALL {
+            let count = self.0.iter().filter(|f| **f == Some(field)).count();
+            if field == Field::Latin && count == 0 {
+                return Err("no column is mapped to the Latin name".to_owned());
+            }
+            if count > 1 {
+                return Err(format!("{} is mapped to {count} columns", field.name()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RowKind {
+    New,
+    /// Existing species, the list of fields which would change
+    Update(SpeciesId, Vec<FieldChange>),
+    Unchanged(SpeciesId),
+    Invalid(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: Field,
+    pub old: String,
+    pub new: String,
+}
+
+impl FieldChange {
+    /// Whether the imported value would replace a value set by hand.
+    pub fn is_conflict(&self) -> bool {
+        !self.old.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Skip,
+    /// Fill in only fields which are empty now
+    FillEmpty,
+    /// Take all non-empty imported values
+    Overwrite,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Row {
+    /// Line in the file, counting from 1 with the header
+    pub line: usize,
+    /// Imported values; empty cells are empty strings
+    pub imported: Species,
+    pub kind: RowKind,
+    pub resolution: Resolution,
+}
+
+impl Row {
+    pub fn has_conflicts(&self) -> bool {
+        match &self.kind {
+            RowKind::Update(_, changes) => changes.iter().any(FieldChange::is_conflict),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub updated: usize,
+}
+
+/// Reads a CSV or tab separated file, or the core of a Darwin Core Archive.
+pub fn read_table(path: &Path) -> Result<Table, String> {
+    let error = |err: &dyn std::fmt::Display| format!("{}: {err}", path.display());
+
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    if extension == "zip" {
+        let file = std::fs::File::open(path).map_err(|err| error(&err))?;
+        let mut zip = zip::ZipArchive::new(file).map_err(|err| error(&err))?;
+        let mut read = |name: &str| -> Result<String, String> {
+            let mut contents = String::new();
+            zip.by_name(name)
+                .map_err(|err| error(&format!("{name}: {err}")))?
+                .read_to_string(&mut contents)
+                .map_err(|err| error(&format!("{name}: {err}")))?;
+            Ok(contents)
+        };
+
+        let meta = read("meta.xml")?;
+        let core = core_file(&meta).map_err(|err| error(&err))?;
+        let contents = read(&core.location)?;
+
+        let mut table = parse_table(&contents, &core.format).map_err(|err| error(&err))?;
+        if !core.fields.is_empty() {
+            table.header = core.header(&table);
+        }
+
+        return Ok(table);
+    }
+
+    let contents = std::fs::read_to_string(path).map_err(|err| error(&err))?;
+    let format = if extension == "txt" || extension == "tsv" {
+        Format {
+            delimiter: b'\t',
+            quote: None,
+            header_lines: 1,
+        }
+    } else {
+        Format {
+            delimiter: b',',
+            quote: Some(b'"'),
+            header_lines: 1,
+        }
+    };
+
+    parse_table(&contents, &format).map_err(|err| error(&err))
+}
+
+fn parse_table(contents: &str, format: &Format) -> Result<Table, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(format.delimiter)
+        .quote(format.quote.unwrap_or(b'"'))
+        .quoting(format.quote.is_some())
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(contents.trim_start_matches('\u{feff}').as_bytes());
+
+    let mut header = Vec::new();
+    let mut rows = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let record = record.map_err(|err| err.to_string())?;
+        let cells = record.iter().map(|cell| cell.trim().to_owned()).collect();
+        if index == 0 && format.header_lines > 0 {
+            header = cells;
+        } else if index >= format.header_lines {
+            rows.push(cells);
+        }
+    }
+
+    Ok(Table {
+        header,
+        rows,
+        header_lines: format.header_lines,
+    })
+}
+
+/// The core file declared in `meta.xml`.
+fn core_file(meta: &str) -> Result<CoreFile, String> {
+    let start = meta.find("<core").ok_or("meta.xml: missing core")?;
+    let core = &meta[start..];
+    let core = &core[..core.find("</core>").unwrap_or(core.len())];
+    let tag = &core[..core.find('>').unwrap_or(core.len())];
+
+    let location = core
+        .split_once("<location>")
+        .and_then(|(_, rest)| rest.split_once("</location>"))
+        .map(|(location, _)| location.trim().to_owned())
+        .ok_or("meta.xml: missing core location")?;
+
+    let delimiter = match attribute(tag, "fieldsTerminatedBy").as_deref() {
+        None | Some("\\t") => b'\t',
+        Some(",") => b',',
+        Some(";") => b';',
+        Some("|") => b'|',
+        Some(other) => return Err(format!("meta.xml: unsupported delimiter '{other}'")),
+    };
+
+    let quote = match attribute(tag, "fieldsEnclosedBy").as_deref() {
+        None => Some(b'"'),
+        Some("") => None,
+        Some(value) if value.len() == 1 => Some(value.as_bytes()[0]),
+        Some(other) => return Err(format!("meta.xml: unsupported quote '{other}'")),
+    };
+
+    let header_lines = match attribute(tag, "ignoreHeaderLines") {
+        None => 0,
+        Some(value) => value
+            .parse()
+            .map_err(|_| format!("meta.xml: invalid ignoreHeaderLines '{value}'"))?,
+    };
+
+    let mut fields = Vec::new();
+    for (name, element) in elements(core, &["id", "coreid", "field"]) {
+        // fields with only a default value have no column
+        let Some(index) = attribute(element, "index") else {
+            continue;
+        };
+        let index = index
+            .parse()
+            .map_err(|_| format!("meta.xml: invalid field index '{index}'"))?;
+        let term = match name {
+            "field" => attribute(element, "term").unwrap_or_default(),
+            _ => name.to_owned(),
+        };
+        fields.push((index, term));
+    }
+
+    Ok(CoreFile {
+        location,
+        format: Format {
+            delimiter,
+            quote,
+            header_lines,
+        },
+        fields,
+    })
+}
+
+/// Start tags with one of the names, as pairs of the name and the tag.
+fn elements<'a>(xml: &'a str, names: &[&'a str]) -> Vec<(&'a str, &'a str)> {
+    let mut result = Vec::new();
+    for (start, _) in xml.match_indices('<') {
+        let tag = &xml[start + 1..];
+        let tag = &tag[..tag.find('>').unwrap_or(tag.len())];
+        let name = tag
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+        if let Some(name) = names.iter().find(|candidate| **candidate == name) {
+            result.push((*name, tag));
+        }
+    }
+
+    result
+}
+
+/// Unescaped value of an attribute of a start tag.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let mut rest = tag;
+    while let Some(pos) = rest.find(name) {
+        let before = rest[..pos].chars().next_back();
+        let after = rest[pos + name.len()..].trim_start();
+        rest = &rest[pos + name.len()..];
+        if !before.is_some_and(char::is_whitespace) {
+            continue;
+        }
+        let Some(after) = after.strip_prefix('=') else {
+            continue;
+        };
+        let after = after.trim_start();
+        let quote = after.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let value = &after[1..];
+        let value = &value[..value.find(quote)?];
+
+        return Some(
+            value
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&amp;", "&"),
+        );
+    }
+
+    None
+}
+
+/// Matches rows with species by the Latin name. Caches of the database
+/// must be up to date.
+pub fn preview(db: &Database, table: &Table, mapping: &Mapping) -> Result<Vec<Row>, String> {
+    mapping.check()?;
+
+    let mut seen = BTreeSet::new();
+    let mut result = Vec::new();
+    for (index, cells) in table.rows.iter().enumerate() {
+        let mut imported = Species::default();
+        for (field, cell) in mapping.0.iter().zip(cells) {
+            if let Some(field) = field {
+                field.set(&mut imported, cell.clone());
+            }
+        }
+
+        let kind = if imported.latin.is_empty() {
+            RowKind::Invalid("empty Latin name".to_owned())
+        } else if !seen.insert(imported.latin.clone()) {
+            RowKind::Invalid(format!("'{}' repeated in the file", imported.latin))
+        } else if let Some(species) = db
+            .species_by_latin(&imported.latin)
+            .or_else(|| db.species_by_synonym(&imported.latin))
+        {
+            // a row matched by a synonym does not rename the species
+            let changes: Vec<_> = Field::ALL
+                .iter()
+                .filter(|field| **field != Field::Latin)
+                .filter_map(|field| {
+                    let old = field.get(species);
+                    let new = field.get(&imported);
+                    (!new.is_empty() && new != old).then(|| FieldChange {
+                        field: *field,
+                        old: old.to_owned(),
+                        new: new.to_owned(),
+                    })
+                })
+                .collect();
+
+            if changes.is_empty() {
+                RowKind::Unchanged(species.id)
+            } else {
+                RowKind::Update(species.id, changes)
+            }
+        } else {
+            RowKind::New
+        };
+
+        let resolution = match kind {
+            RowKind::New => Resolution::Overwrite,
+            RowKind::Update(..) => Resolution::FillEmpty,
+            RowKind::Unchanged(_) | RowKind::Invalid(_) => Resolution::Skip,
+        };
+
+        result.push(Row {
+            line: index + table.header_lines + 1,
+            imported,
+            kind,
+            resolution,
+        });
+    }
+
+    Ok(result)
+}
+
+/// Adds and updates species according to the resolution of each row.
+pub fn apply(db: &mut Database, rows: &[Row]) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+    for row in rows {
+        if row.resolution == Resolution::Skip {
+            continue;
+        }
+
+        match &row.kind {
+            RowKind::New => {
+                db.add_species(&row.imported);
+                summary.added += 1;
+            }
+            RowKind::Update(id, changes) => {
+                let Some(species) = db.species_by_id(id) else {
+                    continue;
+                };
+
+                let mut species = species.clone();
+                let mut changed = false;
+                for change in changes {
+                    if row.resolution == Resolution::Overwrite || !change.is_conflict() {
+                        change.field.set(&mut species, change.new.clone());
+                        changed = true;
+                    }
+                }
+
+                if changed {
+                    db.update_species(&species);
+                    summary.updated += 1;
+                }
+            }
+            RowKind::Unchanged(_) | RowKind::Invalid(_) => (),
+        }
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use db::test_util::DatabaseBuilder;
+    use db::test_util::latin;
+    use db::test_util::species;
+
+    #[test]
+    fn test_parse_table() {
+        let csv = Format {
+            delimiter: b',',
+            quote: Some(b'"'),
+            header_lines: 1,
+        };
+        let table = parse_table("\u{feff}latin,pl\n\"Vulpes vulpes\",\" lis \"\n", &csv).unwrap();
+        assert_eq!(table.header, vec!["latin", "pl"]);
+        assert_eq!(table.rows, vec![vec!["Vulpes vulpes", "lis"]]);
+
+        let tsv = Format {
+            delimiter: b'\t',
+            quote: None,
+            header_lines: 1,
+        };
+        let table = parse_table("taxonID\tscientificName\n1\t\"Meles\" meles\n", &tsv).unwrap();
+        assert_eq!(table.rows, vec![vec!["1", "\"Meles\" meles"]]);
+
+        let headerless = Format {
+            delimiter: b';',
+            quote: Some(b'\''),
+            header_lines: 0,
+        };
+        let table = parse_table("1;'Meles; meles'\n", &headerless).unwrap();
+        assert!(table.header.is_empty());
+        assert_eq!(table.rows, vec![vec!["1", "Meles; meles"]]);
+    }
+
+    #[test]
+    fn test_core_file() {
+        let meta = "<archive><core fieldsTerminatedBy=\"\\t\" rowType=\"http://rs.tdwg.org/dwc/terms/Taxon\">\n\
+                    <files><location>taxa.txt</location></files></core>\n\
+                    <extension><files><location>vernacular.txt</location></files></extension></archive>";
+        let core = core_file(meta).unwrap();
+        assert_eq!(core.location, "taxa.txt");
+        assert_eq!(
+            core.format,
+            Format {
+                delimiter: b'\t',
+                quote: Some(b'"'),
+                header_lines: 0,
+            }
+        );
+        assert!(core.fields.is_empty());
+        assert!(core_file("<archive/>").is_err());
+
+        let meta = r#"<archive>
+            <core encoding="UTF-8" fieldsTerminatedBy="," linesTerminatedBy="\n"
+                  fieldsEnclosedBy='"' ignoreHeaderLines="1" rowType="http://rs.tdwg.org/dwc/terms/Taxon">
+              <files><location>taxon.csv</location></files>
+              <id index="0"/>
+              <field index="2" term="http://rs.tdwg.org/dwc/terms/scientificName"/>
+              <field term="http://rs.tdwg.org/dwc/terms/kingdom" default="Animalia"/>
+              <field index="1" term="http://rs.tdwg.org/dwc/terms/family"/>
+            </core>
+        </archive>"#;
+        let core = core_file(meta).unwrap();
+        assert_eq!(core.location, "taxon.csv");
+        assert_eq!(
+            core.format,
+            Format {
+                delimiter: b',',
+                quote: Some(b'"'),
+                header_lines: 1,
+            }
+        );
+        assert_eq!(
+            core.fields,
+            vec![
+                (0, "id".to_owned()),
+                (2, "http://rs.tdwg.org/dwc/terms/scientificName".to_owned()),
+                (1, "http://rs.tdwg.org/dwc/terms/family".to_owned()),
+            ]
+        );
+
+        let meta = "<archive><core fieldsEnclosedBy=\"\"><files><location>t.txt</location></files></core></archive>";
+        assert_eq!(core_file(meta).unwrap().format.quote, None);
+    }
+
+    #[test]
+    fn test_read_headerless_archive() {
+        use std::io::Write;
+        use zip::ZipWriter;
+        use zip::write::SimpleFileOptions;
+
+        let meta = r#"<archive xmlns="http://rs.tdwg.org/dwc/text/">
+            <core fieldsTerminatedBy="\t" fieldsEnclosedBy="" ignoreHeaderLines="0"
+                  rowType="http://rs.tdwg.org/dwc/terms/Taxon">
+              <files><location>taxon.txt</location></files>
+              <id index="0"/>
+              <field index="1" term="http://rs.tdwg.org/dwc/terms/scientificName"/>
+              <field index="2" term="http://rs.tdwg.org/dwc/terms/family"/>
+            </core>
+        </archive>"#;
+        let taxa = "1\tVulpes vulpes\tCanidae\n2\tMeles \"meles\"\tMustelidae\n";
+
+        let path = std::env::temp_dir().join(format!("dwc-checklist-{}.zip", std::process::id()));
+        let mut zip = ZipWriter::new(std::fs::File::create(&path).unwrap());
+        for (name, contents) in [("meta.xml", meta), ("taxon.txt", taxa)] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(contents.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let table = read_table(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[1], vec!["2", "Meles \"meles\"", "Mustelidae"]);
+        assert_eq!(
+            Mapping::guess(&table.header).0,
+            vec![None, Some(Field::Latin), Some(Field::Rank(Rank::Family))]
+        );
+
+        let rows = preview(&Database::default(), &table, &Mapping::guess(&table.header)).unwrap();
+        assert_eq!(rows[0].imported.latin.as_str(), "Vulpes vulpes");
+        assert_eq!(rows[0].line, 1);
+    }
+
+    #[test]
+    fn test_mapping() {
+        let header: Vec<String> = [
+            "taxonID",
+            "dwc:scientificName",
+            "vernacularName",
+            "class",
+            "en",
+            "family",
+        ]
+        .map(str::to_owned)
+        .to_vec();
+
+        let mapping = Mapping::guess(&header);
+        assert_eq!(
+            mapping.0,
+            vec![
+                None,
+                Some(Field::Latin),
+                Some(Field::English),
+                Some(Field::Rank(Rank::Class)),
+                None,
+                Some(Field::Rank(Rank::Family)),
+            ]
+        );
+        assert!(mapping.check().is_ok());
+
+        let mapping = Mapping(vec![Some(Field::Polish), Some(Field::Polish)]);
+        assert_eq!(
+            mapping.check(),
+            Err("no column is mapped to the Latin name".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_preview_and_apply() {
+        let mut db = DatabaseBuilder::new()
+            .species(Species {
+                pl: "lis".to_owned(),
+                ..species("Vulpes vulpes")
+            })
+            .species(Species {
+                pl: "borsuk".to_owned(),
+                en: "badger".to_owned(),
+                synonyms: vec![latin("Meles taxus")],
+                ..species("Meles meles")
+            })
+            .build();
+        let table = Table {
+            header: ["latin", "pl", "en", "category"]
+                .map(str::to_owned)
+                .to_vec(),
+            rows: vec![
+                vec!["Vulpes vulpes", "lis rudy", "red fox", "ssaki"],
+                vec!["Meles meles", "", "badger", ""],
+                vec!["Lutra lutra", "wydra", "otter", "ssaki"],
+                vec!["", "nic", "", ""],
+                vec!["Lutra lutra", "", "", ""],
+                vec!["Meles taxus", "borsuk europejski", "", ""],
+            ]
+            .into_iter()
+            .map(|row| row.into_iter().map(str::to_owned).collect())
+            .collect(),
+            header_lines: 1,
+        };
+        let mapping = Mapping::guess(&table.header);
+
+        let mut rows = preview(&db, &table, &mapping).unwrap();
+        assert_eq!(rows.len(), 6);
+        assert_eq!(
+            rows[0].kind,
+            RowKind::Update(
+                SpeciesId(0),
+                vec![
+                    FieldChange {
+                        field: Field::Polish,
+                        old: "lis".to_owned(),
+                        new: "lis rudy".to_owned()
+                    },
+                    FieldChange {
+                        field: Field::English,
+                        old: "".to_owned(),
+                        new: "red fox".to_owned()
+                    },
+                    FieldChange {
+                        field: Field::Category,
+                        old: "".to_owned(),
+                        new: "ssaki".to_owned()
+                    },
+                ]
+            )
+        );
+        assert!(rows[0].has_conflicts());
+        assert_eq!(rows[0].resolution, Resolution::FillEmpty);
+        assert_eq!(rows[1].kind, RowKind::Unchanged(SpeciesId(1)));
+        assert_eq!(rows[2].kind, RowKind::New);
+        assert_eq!(
+            rows[3].kind,
+            RowKind::Invalid("empty Latin name".to_owned())
+        );
+        assert_eq!(
+            rows[4].kind,
+            RowKind::Invalid("'Lutra lutra' repeated in the file".to_owned())
+        );
+        assert_eq!(rows[4].line, 6);
+        assert_eq!(
+            rows[5].kind,
+            RowKind::Update(
+                SpeciesId(1),
+                vec![FieldChange {
+                    field: Field::Polish,
+                    old: "borsuk".to_owned(),
+                    new: "borsuk europejski".to_owned()
+                }]
+            )
+        );
+
+        let version = db.current_version.species;
+        let summary = apply(&mut db, &rows);
+        assert_eq!(
+            summary,
+            ImportSummary {
+                added: 1,
+                updated: 1
+            }
+        );
+        assert_eq!(db.current_version.species, version + 2);
+        assert_eq!(db.species[0].pl, "lis");
+        assert_eq!(db.species[0].en, "red fox");
+        assert_eq!(db.species[2].latin.as_str(), "Lutra lutra");
+        assert_eq!(db.species[2].category.as_deref(), Some("ssaki"));
+
+        rows[0].resolution = Resolution::Overwrite;
+        rows[2].resolution = Resolution::Skip;
+        apply(&mut db, &rows);
+        assert_eq!(db.species[0].pl, "lis rudy");
+        assert_eq!(db.species.len(), 3);
+    }
+}
@@ -0,0 +1,103 @@
+//! CSV file for the iNaturalist bulk import; photos are uploaded
+//! separately, the file references them in the description.
+
+use crate::Summary;
+use crate::occurrences;
+use db::Database;
+use db::PostId;
+use std::path::Path;
+
+const HEADER: [&str; 8] = [
+    "Species guess",
+    "Date",
+    "Description",
+    "Place name",
+    "Latitude",
+    "Longitude",
+    "Tags",
+    "Geoprivacy",
+];
+
+/// Exports the posts, or all posts when `None`.
+pub fn export_inaturalist(
+    db: &Database,
+    posts: Option<&[PostId]>,
+    path: &Path,
+) -> Result<Summary, String> {
+    let (occurrences, summary) = occurrences(db, posts)?;
+
+    let error = |err: csv::Error| format!("{}: {err}", path.display());
+    let mut writer = csv::Writer::from_path(path).map_err(error)?;
+    writer.write_record(HEADER).map_err(error)?;
+
+    for occurrence in &occurrences {
+        let mut description = occurrence.remarks.to_owned();
+        for medium in &occurrence.media {
+            if !description.is_empty() {
+                description.push('\n');
+            }
+            description.push_str(&medium.identifier);
+        }
+
+        let (latitude, longitude) = match occurrence.location {
+            Some(location) => (
+                location.latitude.to_string(),
+                location.longitude.to_string(),
+            ),
+            None => (String::new(), String::new()),
+        };
+
+        let tags: Vec<&str> = occurrence
+            .post
+            .tags
+            .iter()
+            .filter(|tag| !db.ignored_tags.contains(*tag))
+            .map(|tag| tag.as_str())
+            .collect();
+
+        writer
+            .write_record([
+                occurrence.scientific_name,
+                &occurrence.date.to_string(),
+                &description,
+                "",
+                &latitude,
+                &longitude,
+                &tags.join(","),
+                "",
+            ])
+            .map_err(error)?;
+    }
+
+    writer
+        .flush()
+        .map_err(|err| format!("{}: {err}", path.display()))?;
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::database;
+
+    #[test]
+    fn test_export_inaturalist() {
+        let mut db = database();
+        db.ignored_tags.insert("winter".to_owned());
+        let path = std::env::temp_dir().join(format!("dwc-inaturalist-{}.csv", std::process::id()));
+
+        let summary = export_inaturalist(&db, None, &path).unwrap();
+        assert_eq!(summary.occurrences, 2);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            contents,
+            "Species guess,Date,Description,Place name,Latitude,Longitude,Tags,Geoprivacy\n\
+             Vulpes vulpes,2024-12-24,\"Fox\tin the\nforest\nhttps://example.com/photos/2024/lis.jpg\",,,,forest,\n\
+             Vulpes vulpes,2025-05-02,\"Fox\tin the\nforest\nhttps://example.com/photos/2025/lis%20rudy.jpg\",,52.2297,21.0122,forest,\n"
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
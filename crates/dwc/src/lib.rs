@@ -0,0 +1,336 @@
+//! Posts as biodiversity records: each species of a post is one
+//! occurrence, exported as a Darwin Core Archive or an iNaturalist CSV
+//! import file. Occurrences of further species get the position in the
+//! post appended to their id, like `2025/bee.jpg#2`.
+//!
+//! Species names are validated before anything is written; the export
+//! fails listing all posts which need fixing.
+//!
+//! The life list of species can be exported as a plain CSV file too.
+//!
+//! In the opposite direction, [`checklist`] imports species lists.
+
+mod archive;
+pub mod checklist;
+mod inaturalist;
+mod life_list;
+
+pub use archive::export_archive;
+pub use inaturalist::export_inaturalist;
+pub use life_list::export_life_list;
+
+use db::Database;
+use db::Date;
+use db::Location;
+use db::Post;
+use db::PostId;
+use db::Species;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub occurrences: usize,
+    /// Posts without species
+    pub skipped: usize,
+}
+
+/// Species shown in a post.
+struct Occurrence<'a> {
+    post: &'a Post,
+    id: String,
+    scientific_name: &'a str,
+    vernacular_name: &'a str,
+    date: Date,
+    location: Option<Location>,
+    remarks: &'a str,
+    media: Vec<Medium>,
+}
+
+struct Medium {
+    identifier: String,
+    description: String,
+}
+
+/// Posts to export; `None` selects all of them.
+fn selected<'a>(db: &'a Database, posts: Option<&[PostId]>) -> Vec<&'a Post> {
+    match posts {
+        Some(ids) => ids.iter().map(|id| db.post(id)).collect(),
+        None => db.posts.iter().collect(),
+    }
+}
+
+/// Collects occurrences ordered by date; fails when any species name
+/// is unknown or malformed.
+fn occurrences<'a>(
+    db: &'a Database,
+    posts: Option<&[PostId]>,
+) -> Result<(Vec<Occurrence<'a>>, Summary), String> {
+    let mut posts = selected(db, posts);
+    posts.sort_by_key(|post| (post.date, post.id));
+
+    let mut result = Vec::new();
+    let mut summary = Summary::default();
+    let mut errors = Vec::new();
+    for post in posts {
+        if post.species.is_none() {
+            summary.skipped += 1;
+            continue;
+        }
+
+        let post_id = match post.files.first() {
+            Some(file) => path_to_string(&file.rel_path),
+            None => format!("post-{}", post.id.0),
+        };
+
+        for (index, latin) in post.all_species().enumerate() {
+            let id = match index {
+                0 => post_id.clone(),
+                _ => format!("{post_id}#{}", index + 1),
+            };
+
+            let Some(species) = db.species_by_latin(latin) else {
+                errors.push(format!(
+                    "{id}: species '{latin}' is not in the species list"
+                ));
+                continue;
+            };
+
+            if let Err(err) = check_scientific_name(latin.as_str()) {
+                errors.push(format!("{id}: {err}"));
+                continue;
+            }
+
+            result.push(occurrence(db, post, id, species));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(format!(
+            "species names need fixing before export:\n{}",
+            errors.join("\n")
+        ));
+    }
+
+    summary.occurrences = result.len();
+
+    Ok((result, summary))
+}
+
+fn occurrence<'a>(
+    db: &Database,
+    post: &'a Post,
+    id: String,
+    species: &'a Species,
+) -> Occurrence<'a> {
+    let media = post
+        .files
+        .iter()
+        .enumerate()
+        .map(|(index, file)| Medium {
+            identifier: media_identifier(&db.observation_settings.media_url, &file.rel_path),
+            description: db.alt_text(post, index),
+        })
+        .collect();
+
+    Occurrence {
+        post,
+        id,
+        scientific_name: species.latin.as_str(),
+        vernacular_name: if species.en.is_empty() {
+            &species.pl
+        } else {
+            &species.en
+        },
+        date: post.date,
+        location: post.location,
+        remarks: if post.en.is_empty() {
+            &post.pl
+        } else {
+            &post.en
+        },
+        media,
+    }
+}
+
+/// Checks the form "Genus epithet [rank] [epithet]", like "Vulpes vulpes"
+/// or "Salix caprea var. pendula"; "Lasius sp." is accepted as well.
+fn check_scientific_name(name: &str) -> Result<(), String> {
+    let mut words = name.split_whitespace();
+    let Some(genus) = words.next() else {
+        return Err("empty species name".to_owned());
+    };
+
+    let mut chars = genus.chars();
+    let capitalized = chars.next().is_some_and(|c| c.is_ascii_uppercase())
+        && chars.all(|c| c.is_ascii_lowercase());
+    if !capitalized {
+        return Err(format!(
+            "'{name}': genus must be a capitalized word of Latin letters"
+        ));
+    }
+
+    for word in words {
+        let valid = word
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c == '-' || c == '.' || c == '×');
+        if !valid {
+            return Err(format!("'{name}': unexpected word '{word}'"));
+        }
+    }
+
+    Ok(())
+}
+
+fn path_to_string(path: &std::path::Path) -> String {
+    let parts: Vec<_> = path
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy())
+        .collect();
+
+    parts.join("/")
+}
+
+/// Address of the photo when the base URL is known, the relative path
+/// otherwise.
+fn media_identifier(base: &str, path: &std::path::Path) -> String {
+    let path = path_to_string(path);
+    if base.is_empty() {
+        return path;
+    }
+
+    let mut result = base.trim_end_matches('/').to_owned();
+    result.push('/');
+    for b in path.bytes() {
+        if b.is_ascii_alphanumeric() || b"/-_.~".contains(&b) {
+            result.push(b as char);
+        } else {
+            result.push_str(&format!("%{b:02X}"));
+        }
+    }
+
+    result
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use db::FileMetadata;
+    use db::ObservationSettings;
+    use db::TagList;
+    use db::test_util::DatabaseBuilder;
+    use db::test_util::latin;
+    use db::test_util::red_fox;
+    use db::test_util::species;
+    use std::path::PathBuf;
+
+    /// Observations of foxes, the newest one with a location, and a post
+    /// without species.
+    pub fn database() -> Database {
+        let mut builder = DatabaseBuilder::new().species(red_fox());
+        for (path, date, species) in [
+            ("2025/lis rudy.jpg", "2025-05-02", Some("Vulpes vulpes")),
+            ("2025/las.jpg", "2025-05-01", None),
+            ("2024/lis.jpg", "2024-12-24", Some("Vulpes vulpes")),
+        ] {
+            builder = builder.post(Post {
+                files: vec![FileMetadata {
+                    rel_path: PathBuf::from(path),
+                    ..FileMetadata::default()
+                }],
+                date: date.parse().unwrap(),
+                en: "Fox\tin the\nforest".to_owned(),
+                species: species.map(latin),
+                tags: TagList(vec!["forest".to_owned(), "winter".to_owned()]),
+                location: (path == "2025/lis rudy.jpg").then_some(Location {
+                    latitude: 52.2297,
+                    longitude: 21.0122,
+                }),
+                ..Post::default()
+            });
+        }
+
+        let mut db = builder.build();
+        db.observation_settings = ObservationSettings {
+            observer: "Jan Kowalski".to_owned(),
+            license: "CC-BY-4.0".to_owned(),
+            media_url: "https://example.com/photos/".to_owned(),
+        };
+
+        db
+    }
+
+    #[test]
+    fn test_occurrences() {
+        let mut db = database();
+
+        let (list, summary) = occurrences(&db, None).unwrap();
+        assert_eq!(
+            summary,
+            Summary {
+                occurrences: 2,
+                skipped: 1
+            }
+        );
+
+        let (only, summary) = occurrences(&db, Some(&[PostId(0), PostId(1)])).unwrap();
+        assert_eq!(
+            summary,
+            Summary {
+                occurrences: 1,
+                skipped: 1
+            }
+        );
+        assert_eq!(only[0].id, "2025/lis rudy.jpg");
+        assert_eq!(list[0].id, "2024/lis.jpg");
+        assert_eq!(list[1].vernacular_name, "red fox");
+        assert_eq!(
+            list[1].media[0].identifier,
+            "https://example.com/photos/2025/lis%20rudy.jpg"
+        );
+
+        // further species are separate occurrences of the same photos
+        db.add_species(&Species {
+            pl: "zając".to_owned(),
+            ..species("Lepus europaeus")
+        });
+        db.post_mut(&PostId(2))
+            .other_species
+            .push(latin("Lepus europaeus"));
+        db.refresh_caches();
+        let (list, summary) = occurrences(&db, None).unwrap();
+        assert_eq!(summary.occurrences, 3);
+        assert_eq!(list[1].id, "2024/lis.jpg#2");
+        assert_eq!(list[1].vernacular_name, "zając");
+        assert_eq!(list[1].media[0].identifier, list[0].media[0].identifier);
+        db.post_mut(&PostId(2)).other_species.clear();
+
+        db.post_mut(&PostId(1)).species = Some(latin("Canis lupus"));
+        db.add_species(&species("vulpes Vulpes"));
+        db.post_mut(&PostId(2)).species = Some(latin("vulpes Vulpes"));
+        db.refresh_caches();
+
+        assert_eq!(
+            occurrences(&db, None).err().unwrap(),
+            "species names need fixing before export:\n\
+             2024/lis.jpg: 'vulpes Vulpes': genus must be a capitalized word of Latin letters\n\
+             2025/las.jpg: species 'Canis lupus' is not in the species list"
+        );
+    }
+
+    #[test]
+    fn test_check_scientific_name() {
+        assert!(check_scientific_name("Vulpes vulpes").is_ok());
+        assert!(check_scientific_name("Salix caprea var. pendula").is_ok());
+        assert!(check_scientific_name("Lasius sp.").is_ok());
+        assert!(check_scientific_name("Lis").is_ok());
+        assert!(check_scientific_name("").is_err());
+        assert!(check_scientific_name("VULPES vulpes").is_err());
+        assert!(check_scientific_name("Vulpes Vulpes").is_err());
+    }
+}
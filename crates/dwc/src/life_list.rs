@@ -0,0 +1,88 @@
+//! Life list as a CSV file, one row per species in the order of the first
+//! record; months are the numbers of months with any post.
+
+use db::Database;
+use db::PostId;
+use std::path::Path;
+
+const HEADER: [&str; 8] = [
+    "Latin",
+    "Polish",
+    "English",
+    "First seen",
+    "Last seen",
+    "Posts",
+    "Photos",
+    "Months",
+];
+
+/// Exports species of the posts, or of all posts when `None`; returns
+/// the number of exported species.
+pub fn export_life_list(
+    db: &Database,
+    posts: Option<&[PostId]>,
+    path: &Path,
+) -> Result<usize, String> {
+    let life_list = match posts {
+        Some(ids) => db.life_list_of(ids.iter().map(|id| db.post(id))),
+        None => db.life_list(),
+    };
+
+    let error = |err: csv::Error| format!("{}: {err}", path.display());
+    let mut writer = csv::Writer::from_path(path).map_err(error)?;
+    writer.write_record(HEADER).map_err(error)?;
+
+    for record in &life_list {
+        let (pl, en) = match db.species_by_latin(&record.latin) {
+            Some(species) => (species.pl.as_str(), species.en.as_str()),
+            None => ("", ""),
+        };
+        let months: Vec<String> = record
+            .months_observed()
+            .iter()
+            .map(|month| month.to_string())
+            .collect();
+
+        writer
+            .write_record([
+                record.latin.as_str(),
+                pl,
+                en,
+                &record.first_seen.to_string(),
+                &record.last_seen.to_string(),
+                &record.posts.to_string(),
+                &record.photos.to_string(),
+                &months.join(" "),
+            ])
+            .map_err(error)?;
+    }
+
+    writer
+        .flush()
+        .map_err(|err| format!("{}: {err}", path.display()))?;
+
+    Ok(life_list.len())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::database;
+
+    #[test]
+    fn test_export_life_list() {
+        let db = database();
+        let path = std::env::temp_dir().join(format!("dwc-life-list-{}.csv", std::process::id()));
+
+        assert_eq!(export_life_list(&db, None, &path), Ok(1));
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            contents,
+            "Latin,Polish,English,First seen,Last seen,Posts,Photos,Months\n\
+             Vulpes vulpes,lis,red fox,2024-12-24,2025-05-02,2,2,5 12\n"
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
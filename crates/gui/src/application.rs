@@ -7,8 +7,10 @@ use crate::keyboard::KeyboardMapping;
 use crate::modal::ModalWindowTrait;
 use crate::modal_keyboard::ModalKeyboard;
 use crate::modal_settings::ModalSettings;
+use crate::publishing::Publishing;
 use crate::style::Style;
 use crate::tab_ignored_tags::TabIgnoredTags;
+
 use crate::tab_posts::Message as TabPostsMessage;
 use crate::tab_posts::TabPosts;
 use crate::tab_species::Message as TabSpeciesMessage;
@@ -17,9 +19,14 @@ use crate::tab_tag_groups::Message as TabTagGroupsMessage;
 use crate::tab_tag_groups::TabTagGroups;
 use crate::tab_tag_translations::Message as TabTagTranslationsMessage;
 use crate::tab_tag_translations::TabTagTranslations;
+use chrono::Local;
 use const_format::formatcp as fmt;
 use db::edit_details::EditDetails;
+use db::edit_details::SocialMediaLink;
+use db::render_caption;
 use db::Database;
+use db::ObservationSettings;
+use db::PlatformStatus;
 use db::PostId;
 use db::PublishedState;
 use eframe::egui::Context;
@@ -33,9 +40,12 @@ use egui::Modal;
 use egui::Sense;
 use egui::TopBottomPanel;
 use egui::ViewportCommand;
+use publisher::Draft;
+use publisher::PublishOptions;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::VecDeque;
+use std::time::Duration;
 
 use egui_material_icons::icons::ICON_HELP;
 use egui_material_icons::icons::ICON_SETTINGS;
@@ -60,6 +70,8 @@ pub struct Application {
     image_cache: ImageCache,
     clipboard: Clipboard,
 
+    publishing: Publishing,
+
     keyboard_mapping: KeyboardMapping,
 }
 
@@ -72,11 +84,12 @@ pub enum Message {
     TabTagTranslations(TabTagTranslationsMessage),
     OpenModal(Box<dyn ModalWindowTrait>),
     EditDetails(EditDetails),
-    StartPublishing(PostId),
+    StartPublishing(PostId, SocialMediaLink, PublishOptions),
     Copy(ClipboardKind, String),
     CloseModal,
     SaveDatabase,
     SetStyle(Style),
+    SetObservationSettings(ObservationSettings),
     Confirm(Confirm),
     ConfirmResult(Option<Box<Message>>),
     SoftClose,
@@ -99,11 +112,12 @@ impl Message {
             Self::TabTagTranslations(msg) => msg.name(),
             Self::OpenModal(_) => unreachable!(),
             Self::EditDetails(_) => unreachable!(),
-            Self::StartPublishing(_) => unreachable!(),
+            Self::StartPublishing(..) => unreachable!(),
             Self::Copy(..) => unreachable!(),
             Self::CloseModal => unreachable!(),
             Self::SaveDatabase => "save database",
             Self::SetStyle(_) => unreachable!(),
+            Self::SetObservationSettings(_) => unreachable!(),
             Self::Confirm(_) => unreachable!(),
             Self::SoftClose => unreachable!(),
             Self::AllowClose => unreachable!(),
@@ -128,11 +142,14 @@ impl Clone for Message {
             Self::TabTagTranslations(val) => Self::TabTagTranslations(val.clone()),
             Self::OpenModal(_) => unreachable!(),
             Self::EditDetails(val) => Self::EditDetails(val.clone()),
-            Self::StartPublishing(val) => Self::StartPublishing(*val),
+            Self::StartPublishing(id, link, options) => {
+                Self::StartPublishing(*id, *link, options.clone())
+            }
             Self::Copy(kind, val) => Self::Copy(*kind, val.clone()),
             Self::CloseModal => Self::CloseModal,
             Self::SaveDatabase => Self::SaveDatabase,
             Self::SetStyle(val) => Self::SetStyle(val.clone()),
+            Self::SetObservationSettings(val) => Self::SetObservationSettings(val.clone()),
             Self::Confirm(val) => Self::Confirm(val.clone()),
             Self::SoftClose => Self::SoftClose,
             Self::AllowClose => Self::AllowClose,
@@ -198,9 +215,15 @@ impl Application {
             can_close: false,
             keyboard_mapping: Self::create_mapping(),
             clipboard: Clipboard::default(),
+            publishing: Publishing::default(),
         }
     }
 
+    pub fn with_credentials(mut self, credentials: socmedia::Credentials) -> Self {
+        self.posts.credentials = credentials;
+        self
+    }
+
     fn create_mapping() -> KeyboardMapping {
         KeyboardMapping::default()
             .key(Key::F1, Message::OpenHelp)
@@ -258,9 +281,21 @@ impl Application {
             Message::EditDetails(action) => {
                 db::edit_details::apply(action, &mut self.db);
             }
-            Message::StartPublishing(id) => {
-                let action = EditDetails::SetPublished(id, PublishedState::timestamp_now());
-                db::edit_details::apply(action, &mut self.db);
+            Message::StartPublishing(id, link, options) => {
+                if self.publishing.is_pending(&id, link) {
+                    return;
+                }
+
+                if let Some(publisher) = publisher::publisher(&self.posts.credentials, link) {
+                    let post = self.db.post(&id);
+                    let draft = Draft::new(
+                        post,
+                        &self.db,
+                        render_caption(post, &self.db, Some(link)),
+                        options,
+                    );
+                    self.publishing.start(id, publisher, draft);
+                }
             }
             Message::CloseModal => {
                 let _ = self.modal_window.pop();
@@ -345,6 +380,9 @@ impl Application {
             Message::SetStyle(style) => {
                 self.style = style;
             }
+            Message::SetObservationSettings(settings) => {
+                self.db.set_observation_settings(settings);
+            }
             Message::SelectTabPosts => {
                 self.active_tab = Tab::Posts;
             }
@@ -384,11 +422,58 @@ impl Application {
         }
     }
 
+    fn finish_publishing(&mut self, ctx: &Context) {
+        for (id, link, result) in self.publishing.finished() {
+            match result {
+                Ok(published) => {
+                    let status =
+                        PlatformStatus::published(published.id, published.url, Local::now());
+                    let action = EditDetails::SetPlatformStatus(id, link, status);
+                    db::edit_details::apply(action, &mut self.db);
+
+                    let action = EditDetails::SetPublished(id, PublishedState::timestamp_now());
+                    db::edit_details::apply(action, &mut self.db);
+                }
+                Err(err) => {
+                    let post = self.db.post(&id);
+                    let status = post
+                        .platforms
+                        .get(link)
+                        .cloned()
+                        .unwrap_or_default()
+                        .failed(err.to_string(), Local::now());
+                    let action = EditDetails::SetPlatformStatus(id, link, status);
+                    db::edit_details::apply(action, &mut self.db);
+
+                    let files = &self.db.post(&id).files;
+                    let name = files
+                        .first()
+                        .map(|file| file.full_path.display().to_string())
+                        .unwrap_or_default();
+
+                    let confirm = Confirm::new(
+                        format!("Cannot publish {name} on {}: {err}", link.name()),
+                        vec![ConfirmOption::new("Close").with_key(Key::Escape)],
+                    );
+                    self.queue.push_back(Message::Confirm(confirm));
+                }
+            }
+        }
+
+        if !self.publishing.is_empty() {
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+    }
+
     fn load(&mut self, storage: &dyn eframe::Storage) {
         self.active_tab =
             eframe::get_value(storage, "main-active-tab").unwrap_or(self.active_tab.clone());
         self.style = eframe::get_value(storage, "main-style").unwrap_or(self.style.clone());
 
+        if let Some(selection) = eframe::get_value(storage, "main-example-selection") {
+            self.db.restore_example_selection(selection);
+        }
+
         let db_id = self.db.rootpath.display().to_string();
 
         self.posts.load(&db_id, storage);
@@ -411,6 +496,7 @@ impl eframe::App for Application {
         self.db.refresh_caches();
 
         self.keyboard(ctx);
+        self.finish_publishing(ctx);
 
         while let Some(msg) = self.queue.pop_front() {
             self.handle_message(ctx, msg);
@@ -452,13 +538,18 @@ impl eframe::App for Application {
 
                     if ui.button(ICON_SETTINGS).clicked() {
                         let window: Box<dyn ModalWindowTrait> =
-                            Box::new(ModalSettings::new(&self.style));
+                            Box::new(ModalSettings::new(&self.style, &self.db));
                         self.queue.push_back(Message::OpenModal(window));
                     }
 
                     if ui.button(ICON_HELP).clicked() {
                         self.queue.push_back(Message::OpenHelp);
                     }
+
+                    if !self.publishing.is_empty() {
+                        ui.label(format!("publishing {} post(s)", self.publishing.len()));
+                        ui.spinner();
+                    }
                 });
             });
         });
@@ -511,6 +602,11 @@ impl eframe::App for Application {
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         eframe::set_value(storage, "main-active-tab", &self.active_tab);
         eframe::set_value(storage, "main-style", &self.style);
+        eframe::set_value(
+            storage,
+            "main-example-selection",
+            &self.db.example_selection(),
+        );
 
         let db_id = self.db.rootpath.display().to_string();
 
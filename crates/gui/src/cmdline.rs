@@ -1,4 +1,6 @@
+use clap::Args;
 use clap::Parser;
+use clap::Subcommand;
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
@@ -20,4 +22,91 @@ pub struct Options {
     #[arg(default_value_t = false)]
     #[arg(long)]
     pub disable_socmedia: bool,
+
+    /// Run without the GUI and exit
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Print paths of photos matching the saved search
+    SavedSearch {
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+
+    /// Publish scheduled posts whose time has come
+    PublishDue {
+        /// Only print what would be sent
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Generate a static web gallery of published posts in the directory
+    Site {
+        /// Output directory
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+
+        /// Directory with templates overriding the built-in ones
+        #[arg(long, value_name = "DIR")]
+        theme: Option<PathBuf>,
+
+        /// Public address of the web gallery, needed to generate feeds
+        #[arg(long, value_name = "URL")]
+        url: Option<String>,
+
+        #[command(flatten)]
+        source: Source,
+    },
+
+    /// Generate only feeds of the web gallery in the directory
+    Feeds {
+        /// Output directory
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+
+        /// Public address of the web gallery
+        #[arg(long, value_name = "URL")]
+        url: String,
+
+        #[command(flatten)]
+        source: Source,
+    },
+
+    /// Export posts with species as a Darwin Core Archive
+    ExportDwca {
+        #[arg(value_name = "ZIP")]
+        path: PathBuf,
+
+        #[command(flatten)]
+        source: Source,
+    },
+
+    /// Export posts with species as an iNaturalist import CSV
+    ExportInaturalist {
+        #[arg(value_name = "CSV")]
+        path: PathBuf,
+
+        #[command(flatten)]
+        source: Source,
+    },
+
+    /// Export the life list of species as CSV
+    ExportLifeList {
+        #[arg(value_name = "CSV")]
+        path: PathBuf,
+
+        #[command(flatten)]
+        source: Source,
+    },
+}
+
+/// Posts taken by a command.
+#[derive(Args, Debug)]
+pub struct Source {
+    /// Only posts matching the saved search
+    #[arg(long, value_name = "NAME")]
+    pub saved_search: Option<String>,
 }
@@ -81,13 +81,9 @@ fn consume_key(input: &mut InputState, modifiers: Modifiers, key: Key) -> bool {
             modifiers: ev_mod,
             pressed: true,
             ..
-        } => {
-            if *ev_key == key && ev_mod.matches_exact(modifiers) {
-                found = true;
-                false
-            } else {
-                true
-            }
+        } if *ev_key == key && ev_mod.matches_exact(modifiers) => {
+            found = true;
+            false
         }
         _ => true,
     });
@@ -11,6 +11,7 @@ mod keyboard;
 mod modal;
 mod modal_keyboard;
 mod modal_settings;
+pub mod publishing;
 mod search_box;
 mod select_tags;
 mod species_view;
@@ -1,11 +1,15 @@
 use clap::Parser;
 use db::Database;
+use db::PostId;
 use env_logger::Builder;
 use log::error;
 use log::info;
+use log::warn;
 use log::LevelFilter;
 use photos::application::Application;
+use photos::cmdline::Command;
 use photos::cmdline::Options;
+use photos::cmdline::Source;
 use std::path::absolute;
 
 fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
@@ -49,6 +53,18 @@ fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
         }
     }
 
+    if let Some(command) = &opts.command {
+        return run_command(db, command, &opts);
+    }
+
+    let mut app = Application::new(db);
+    if !opts.disable_socmedia {
+        match socmedia::Credentials::from_file(&opts.socmedia) {
+            Ok(credentials) => app = app.with_credentials(credentials),
+            Err(err) => warn!("Publishing on social media is not available: {err}"),
+        }
+    }
+
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_fullscreen(false),
         ..Default::default()
@@ -57,6 +73,162 @@ fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
     Ok(eframe::run_native(
         &format!("Publish photos: {}", path.display()),
         native_options,
-        Box::new(|_cc| Ok(Box::new(Application::new(db)))),
+        Box::new(|_cc| Ok(Box::new(app))),
     )?)
 }
+
+fn run_command(
+    db: Database,
+    command: &Command,
+    opts: &Options,
+) -> Result<(), Box<dyn std::error::Error + 'static>> {
+    match command {
+        Command::SavedSearch { name } => print_saved_search(&db, name),
+        Command::PublishDue { dry_run } => publish_due(db, opts, *dry_run),
+        Command::Site {
+            dir,
+            theme,
+            url,
+            source,
+        } => {
+            let mut options = site::Options::new(dir);
+            options.theme = theme.clone();
+            options.url = url.clone();
+            options.posts = source_posts(&db, source)?;
+            let summary = site::generate(&db, &options)?;
+            print_site_summary(dir, &summary)
+        }
+        Command::Feeds { dir, url, source } => {
+            let mut options = site::Options::new(dir);
+            options.url = Some(url.clone());
+            options.posts = source_posts(&db, source)?;
+            let summary = site::generate_feeds(&db, &options)?;
+            print_site_summary(dir, &summary)
+        }
+        Command::ExportDwca { path, source } => {
+            let posts = source_posts(&db, source)?;
+            let summary = dwc::export_archive(&db, posts.as_deref(), path)?;
+            print_export_summary(path, &summary)
+        }
+        Command::ExportInaturalist { path, source } => {
+            let posts = source_posts(&db, source)?;
+            let summary = dwc::export_inaturalist(&db, posts.as_deref(), path)?;
+            print_export_summary(path, &summary)
+        }
+        Command::ExportLifeList { path, source } => {
+            let posts = source_posts(&db, source)?;
+            let count = dwc::export_life_list(&db, posts.as_deref(), path)?;
+            println!("{}: exported {count} species", path.display());
+            Ok(())
+        }
+    }
+}
+
+fn publish_due(
+    mut db: Database,
+    opts: &Options,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error + 'static>> {
+    let credentials = socmedia::Credentials::from_file(&opts.socmedia)?;
+    let options = publisher::PublishOptions::new(&credentials);
+    let lookup = |link| publisher::publisher(&credentials, link);
+
+    let path = db.rootpath.to_path_buf();
+    let summary = publisher::scheduler::publish_due(
+        &mut db,
+        chrono::Local::now(),
+        &lookup,
+        &options,
+        dry_run,
+        &mut std::io::stdout(),
+        &mut |db| {
+            if !db.is_dirty() {
+                return Ok(());
+            }
+
+            db.save(&path)
+                .map_err(|err| std::io::Error::other(format!("{}: {err}", path.display())))
+        },
+    )?;
+
+    if summary.failed > 0 {
+        return Err(format!("failed to publish {} item(s)", summary.failed).into());
+    }
+
+    Ok(())
+}
+
+fn print_site_summary(
+    dir: &std::path::Path,
+    summary: &site::Summary,
+) -> Result<(), Box<dyn std::error::Error + 'static>> {
+    println!(
+        "{}: written {} file(s), {} unchanged, resized {} photo(s), removed {} file(s)",
+        dir.display(),
+        summary.written,
+        summary.unchanged,
+        summary.resized,
+        summary.removed
+    );
+
+    Ok(())
+}
+
+fn print_export_summary(
+    path: &std::path::Path,
+    summary: &dwc::Summary,
+) -> Result<(), Box<dyn std::error::Error + 'static>> {
+    println!(
+        "{}: exported {} occurrence(s), skipped {} post(s) without species",
+        path.display(),
+        summary.occurrences,
+        summary.skipped
+    );
+
+    Ok(())
+}
+
+fn print_saved_search(
+    db: &Database,
+    name: &str,
+) -> Result<(), Box<dyn std::error::Error + 'static>> {
+    for post_id in saved_search(db, name)? {
+        for file in &db.post(post_id).files {
+            println!("{}", file.full_path.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Posts of the saved search given to a command; `None` means all posts.
+fn source_posts(
+    db: &Database,
+    source: &Source,
+) -> Result<Option<Vec<PostId>>, Box<dyn std::error::Error + 'static>> {
+    match &source.saved_search {
+        Some(name) => Ok(Some(saved_search(db, name)?.clone())),
+        None => Ok(None),
+    }
+}
+
+fn saved_search<'a>(
+    db: &'a Database,
+    name: &str,
+) -> Result<&'a Vec<PostId>, Box<dyn std::error::Error + 'static>> {
+    let Some(view) = db.saved_search_view(name) else {
+        let names: Vec<&str> = db
+            .saved_searches
+            .iter()
+            .map(|search| search.name.as_str())
+            .collect();
+
+        return Err(format!(
+            "saved search '{name}' not found, available: {}",
+            names.join(", ")
+        )
+        .into());
+    };
+
+    Ok(view)
+}
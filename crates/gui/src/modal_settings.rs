@@ -9,6 +9,7 @@ use crate::style::Style;
 use crate::widgets::color_box;
 use const_format::formatcp as fmt;
 use db::Database;
+use db::ObservationSettings;
 use egui::Align;
 use egui::CollapsingHeader;
 use egui::Color32;
@@ -18,6 +19,7 @@ use egui::Id;
 use egui::Key;
 use egui::Layout;
 use egui::Slider;
+use egui::TextEdit;
 use egui::Ui;
 
 use std::ops::RangeInclusive;
@@ -27,17 +29,21 @@ const ID_PREFIX: &str = "modal-settings";
 pub struct ModalSettings {
     new: Style,
     original: Style,
+    new_observations: ObservationSettings,
+    original_observations: ObservationSettings,
     live_preview_id: Id,
     keyboard_mapping: KeyboardMapping,
 }
 
 impl ModalSettings {
-    pub fn new(style: &Style) -> Self {
+    pub fn new(style: &Style, db: &Database) -> Self {
         let keyboard_mapping = KeyboardMapping::default().key(Key::Escape, Message::CloseModal);
 
         Self {
             new: style.clone(),
             original: style.clone(),
+            new_observations: db.observation_settings.clone(),
+            original_observations: db.observation_settings.clone(),
             live_preview_id: Id::new(fmt!("{ID_PREFIX}-live-preview")),
             keyboard_mapping,
         }
@@ -57,8 +63,10 @@ impl ModalWindowTrait for ModalSettings {
         self.show_image(ui);
         self.show_tags(ui);
         self.show_button(ui);
+        self.show_observations(ui);
 
-        let changed = self.new != self.original;
+        let style_changed = self.new != self.original;
+        let observations_changed = self.new_observations != self.original_observations;
 
         ui.separator();
 
@@ -73,7 +81,7 @@ impl ModalWindowTrait for ModalSettings {
                 });
             }
 
-            if flag && changed {
+            if flag && style_changed {
                 queue.push_back(Message::SetStyle(self.new.clone()));
             }
         });
@@ -81,8 +89,14 @@ impl ModalWindowTrait for ModalSettings {
         ui.separator();
 
         ui.with_layout(Layout::right_to_left(Align::Min), |ui| {
+            let changed = style_changed || observations_changed;
             if button::save(ui, changed, Some(style.button.save)) {
                 queue.push_back(Message::SetStyle(self.new.clone()));
+                if observations_changed {
+                    queue.push_back(Message::SetObservationSettings(
+                        self.new_observations.clone(),
+                    ));
+                }
                 queue.push_back(Message::CloseModal);
             }
             if button::cancel(ui) {
@@ -213,6 +227,35 @@ impl ModalSettings {
                     });
             });
     }
+
+    fn show_observations(&mut self, ui: &mut Ui) {
+        CollapsingHeader::new("Observations")
+            .id_salt(fmt!("{ID_PREFIX}-header-observations"))
+            .default_open(true)
+            .show(ui, |ui| {
+                Grid::new(fmt!("{ID_PREFIX}-grid-observations"))
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("observer");
+                        let edit = TextEdit::singleline(&mut self.new_observations.observer)
+                            .hint_text("full name");
+                        ui.add(edit);
+                        ui.end_row();
+
+                        ui.label("photo license");
+                        let edit = TextEdit::singleline(&mut self.new_observations.license)
+                            .hint_text("CC-BY-4.0");
+                        ui.add(edit);
+                        ui.end_row();
+
+                        ui.label("photos URL");
+                        let edit = TextEdit::singleline(&mut self.new_observations.media_url)
+                            .hint_text("https://example.com/photos/");
+                        ui.add(edit);
+                        ui.end_row();
+                    });
+            });
+    }
 }
 
 fn select_size(ui: &mut Ui, current_value: &mut f32, range: RangeInclusive<usize>) {
@@ -0,0 +1,69 @@
+use db::edit_details::SocialMediaLink;
+use db::PostId;
+use publisher::Draft;
+use publisher::Publisher;
+use socmedia::Published;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::TryRecvError;
+use std::thread;
+
+/// Posts being published in background threads.
+#[derive(Default)]
+pub struct Publishing {
+    jobs: Vec<Pending>,
+}
+
+struct Pending {
+    id: PostId,
+    link: SocialMediaLink,
+    receiver: Receiver<socmedia::Result<Published>>,
+}
+
+type Outcome = (PostId, SocialMediaLink, socmedia::Result<Published>);
+
+impl Publishing {
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    pub fn is_pending(&self, id: &PostId, link: SocialMediaLink) -> bool {
+        self.jobs
+            .iter()
+            .any(|job| job.id == *id && job.link == link)
+    }
+
+    /// Publishes the draft in a background thread.
+    pub fn start(&mut self, id: PostId, publisher: Box<dyn Publisher>, draft: Draft) {
+        let link = publisher.platform();
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(publisher.publish(&draft));
+        });
+
+        self.jobs.push(Pending { id, link, receiver });
+    }
+
+    /// Returns results of jobs finished since the last call.
+    pub fn finished(&mut self) -> Vec<Outcome> {
+        let mut result = Vec::new();
+        self.jobs.retain(|job| match job.receiver.try_recv() {
+            Ok(outcome) => {
+                result.push((job.id, job.link, outcome));
+                false
+            }
+            Err(TryRecvError::Empty) => true,
+            Err(TryRecvError::Disconnected) => {
+                let err = socmedia::Error::Transport("publishing thread stopped".to_owned());
+                result.push((job.id, job.link, Err(err)));
+                false
+            }
+        });
+
+        result
+    }
+}
@@ -1,13 +1,29 @@
+use db::query::ParseError;
+use egui::text::LayoutJob;
 use egui::Button;
 use egui::Context;
 use egui::Id;
 use egui::Label;
+use egui::Stroke;
+use egui::TextBuffer;
 use egui::TextEdit;
+use egui::TextFormat;
+use egui::TextStyle;
 use egui::Ui;
 
 use egui_material_icons::icons::ICON_BACKSPACE;
+use egui_material_icons::icons::ICON_ERROR;
 use egui_material_icons::icons::ICON_SEARCH;
 
+const QUERY_SYNTAX: &str = "\
+words and \"quoted phrases\" are searched in descriptions, tags and species
+tag:fox species:vulpes category:mammals order:odonata family:nymphalidae
+published:yes example:no files:>2
+published:instagram -published:facebook failed:bluesky
+no:species no:tags has:pl has:en
+date:2024 date:2024-05 date:2024-05-01..2024-06-15 date:>=2024-03
+combine with AND, OR, NOT (or &, |, -) and parentheses";
+
 pub struct SearchBox {
     pub id: Id,
 }
@@ -21,28 +37,63 @@ impl SearchBox {
         ctx.data_mut(|data| data.get_persisted(self.id).unwrap_or_default())
     }
 
+    pub fn set_phrase(&self, ctx: &Context, phrase: &str) {
+        ctx.data_mut(|data| data.insert_persisted(self.id, phrase.to_owned()));
+    }
+
     pub fn take_focus(&self, ctx: &Context) {
         ctx.memory_mut(|mem| mem.request_focus(self.id));
     }
 
     pub fn show(&self, ui: &mut Ui) -> Option<String> {
+        self.show_aux(ui, true, None)
+    }
+
+    /// Shows the search box for a query; the phrase keeps its case (the
+    /// operators are upper case) and the erroneous part gets highlighted.
+    pub fn show_query(&self, ui: &mut Ui, error: Option<&ParseError>) -> Option<String> {
+        self.show_aux(ui, false, error)
+    }
+
+    fn show_aux(&self, ui: &mut Ui, lowercase: bool, error: Option<&ParseError>) -> Option<String> {
         let mut phrase = self.phrase(ui.ctx());
         let prev = phrase.clone();
 
         ui.add(Label::new(ICON_SEARCH).selectable(false));
-        ui.add(
+
+        let mut layouter = |ui: &Ui, buf: &dyn TextBuffer, wrap_width: f32| {
+            let mut job = highlight(ui, buf.as_str(), error);
+            job.wrap.max_width = wrap_width;
+
+            ui.fonts_mut(|fonts| fonts.layout_job(job))
+        };
+
+        let resp = ui.add(
             TextEdit::singleline(&mut phrase)
                 .id(self.id)
-                .hint_text("search..."),
+                .hint_text("search...")
+                .layouter(&mut layouter),
         );
 
+        if !lowercase {
+            resp.on_hover_text(QUERY_SYNTAX);
+        }
+
         let enabled = !prev.is_empty();
         let button = Button::new(ICON_BACKSPACE);
         if ui.add_enabled(enabled, button).clicked() {
             phrase.clear();
         }
 
-        phrase = phrase.to_lowercase();
+        if let Some(error) = error {
+            let color = ui.visuals().error_fg_color;
+            ui.colored_label(color, format!("{ICON_ERROR} {}", error.message));
+        }
+
+        if lowercase {
+            phrase = phrase.to_lowercase();
+        }
+
         if phrase != prev {
             ui.ctx()
                 .data_mut(|data| data.insert_persisted(self.id, phrase.clone()));
@@ -52,3 +103,33 @@ impl SearchBox {
         }
     }
 }
+
+fn highlight(ui: &Ui, text: &str, error: Option<&ParseError>) -> LayoutJob {
+    let font_id = TextStyle::Body.resolve(ui.style());
+    let normal = TextFormat::simple(font_id, ui.visuals().text_color());
+
+    let mut job = LayoutJob::default();
+
+    // The error might refer to the text from the previous frame
+    let span = error
+        .map(|error| error.span.start.min(text.len())..error.span.end.min(text.len()))
+        .filter(|span| text.is_char_boundary(span.start) && text.is_char_boundary(span.end));
+
+    let Some(span) = span else {
+        job.append(text, 0.0, normal);
+        return job;
+    };
+
+    let color = ui.visuals().error_fg_color;
+    let erroneous = TextFormat {
+        color,
+        underline: Stroke::new(1.5, color),
+        ..normal.clone()
+    };
+
+    job.append(&text[..span.start], 0.0, normal.clone());
+    job.append(&text[span.clone()], 0.0, erroneous);
+    job.append(&text[span.end..], 0.0, normal);
+
+    job
+}
@@ -2,6 +2,7 @@ use crate::style::Style;
 use crate::widgets::tag_button;
 use const_format::formatcp as fmt;
 use db::edit_tags::Action;
+use db::text::fold;
 use db::Database;
 use db::TagList;
 use db::TranslatedTag;
@@ -216,8 +217,9 @@ impl SelectTags {
     ) -> Option<SelectTagsAction> {
         let mut result: Option<SelectTagsAction> = None;
 
+        let phrase = fold(&self.new_tag);
         for group in groups {
-            let empty_list = !group.tags.iter().any(|tag| self.tag_matches_filter(tag));
+            let empty_list = !group.tags.iter().any(|tag| self.tag_matches(tag, &phrase));
             if empty_list {
                 continue;
             }
@@ -243,7 +245,7 @@ impl SelectTags {
             ui.horizontal_wrapped(|ui| {
                 let mut needs_space = false;
                 for tag in group.tags.iter() {
-                    if !self.tag_matches_filter(tag) {
+                    if !self.tag_matches(tag, &phrase) {
                         continue;
                     }
 
@@ -282,10 +284,15 @@ impl SelectTags {
 
         self.filtered.clear();
 
+        let phrase = fold(&self.new_tag);
         let mut autocompletion = Vec::<TranslatedTag>::new();
         for group in &self.available {
             let mut filtered = TranslatedTagGroup::empty(&group.name);
-            for tag in group.tags.iter().filter(|tag| self.tag_matches_filter(tag)) {
+            for tag in group
+                .tags
+                .iter()
+                .filter(|tag| self.tag_matches(tag, &phrase))
+            {
                 autocompletion.push(tag.clone());
                 filtered.tags.add(tag.clone());
             }
@@ -296,16 +303,16 @@ impl SelectTags {
         self.autocompletion = autocompletion;
     }
 
-    fn tag_matches_filter(&self, tag: &TranslatedTag) -> bool {
+    fn tag_matches(&self, tag: &TranslatedTag, phrase: &str) -> bool {
         match tag {
             TranslatedTag::Translation(trans) => {
                 if self.show_pl {
-                    trans.pl.contains(&self.new_tag) | trans.en.contains(&self.new_tag)
+                    fold(&trans.pl).contains(phrase) | fold(&trans.en).contains(phrase)
                 } else {
-                    trans.en.contains(&self.new_tag)
+                    fold(&trans.en).contains(phrase)
                 }
             }
-            TranslatedTag::Untranslated(string) => string.contains(&self.new_tag),
+            TranslatedTag::Untranslated(string) => fold(string).contains(phrase),
         }
     }
 }
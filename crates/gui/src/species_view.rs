@@ -6,7 +6,9 @@ use crate::gui::overlay_label;
 use crate::image_cache::ImageCache;
 use crate::style::Style;
 use crate::ImageCounter;
+use db::text::compare;
 use db::Database;
+use db::Rank;
 use db::Species;
 use db::SpeciesId;
 use egui::Align;
@@ -16,6 +18,7 @@ use egui::RichText;
 use egui::Ui;
 use egui::UiBuilder;
 use egui::Vec2;
+use std::cmp::Reverse;
 
 use egui_material_icons::icons::ICON_ARROW_BACK_2;
 use egui_material_icons::icons::ICON_PLAY_ARROW;
@@ -25,6 +28,7 @@ pub struct SpeciesList {
     ids: Option<Vec<SpeciesId>>,
     view: Vec<SpeciesId>,
     phrase: String,
+    taxon: Option<(Rank, String)>,
     pub sort_order: SortOrder,
     pub image_width: f32,
     pub hovered: Option<SpeciesId>,
@@ -41,6 +45,7 @@ pub enum SortOrder {
     PolishDesc,
     EnglishAsc,
     EnglishDesc,
+    Relevance,
 }
 
 impl SpeciesList {
@@ -79,7 +84,16 @@ impl SpeciesList {
     }
 
     fn match_phrase(&self, species: &Species) -> bool {
-        species.search_parts.matches(&self.phrase)
+        let in_taxon = match &self.taxon {
+            Some((rank, name)) => species.rank(*rank) == Some(name.as_str()),
+            None => true,
+        };
+
+        in_taxon && species.search_parts.matches_all(&self.terms())
+    }
+
+    fn terms(&self) -> Vec<&str> {
+        terms(&self.phrase)
     }
 
     pub fn set_filter(&mut self, phrase: String, db: &Database) {
@@ -91,6 +105,20 @@ impl SpeciesList {
         self.refresh_view(db);
     }
 
+    pub fn taxon(&self) -> Option<&(Rank, String)> {
+        self.taxon.as_ref()
+    }
+
+    /// Shows only species belonging to the taxon.
+    pub fn set_taxon(&mut self, taxon: Option<(Rank, String)>, db: &Database) {
+        if taxon == self.taxon {
+            return;
+        }
+
+        self.taxon = taxon;
+        self.refresh_view(db);
+    }
+
     pub fn set_sort_order(&mut self, sort_order: SortOrder, db: &Database) {
         if self.sort_order == sort_order {
             return;
@@ -106,29 +134,32 @@ impl SpeciesList {
                 self.view.sort();
             }
             SortOrder::LatinAsc | SortOrder::LatinDesc => {
-                self.view
-                    .sort_by_key(|id| db.species_by_id(id).as_ref().unwrap().latin.as_str());
+                self.view.sort_by(|a, b| {
+                    let a = db.species_by_id(a).unwrap();
+                    let b = db.species_by_id(b).unwrap();
+                    compare(a.latin.as_str(), b.latin.as_str())
+                });
             }
             SortOrder::PolishAsc | SortOrder::PolishDesc => {
-                self.view.sort_by_key(|id| {
-                    let tmp = db.species_by_id(id);
-                    let species = tmp.as_ref().unwrap();
-                    if species.pl.is_empty() {
-                        species.latin.as_str()
-                    } else {
-                        &species.pl
-                    }
+                self.view.sort_by(|a, b| {
+                    let a = db.species_by_id(a).unwrap();
+                    let b = db.species_by_id(b).unwrap();
+                    compare(name_or_latin(&a.pl, a), name_or_latin(&b.pl, b))
                 });
             }
             SortOrder::EnglishAsc | SortOrder::EnglishDesc => {
+                self.view.sort_by(|a, b| {
+                    let a = db.species_by_id(a).unwrap();
+                    let b = db.species_by_id(b).unwrap();
+                    compare(name_or_latin(&a.en, a), name_or_latin(&b.en, b))
+                });
+            }
+            SortOrder::Relevance => {
+                let terms = terms(&self.phrase);
                 self.view.sort_by_key(|id| {
-                    let tmp = db.species_by_id(id);
-                    let species = tmp.as_ref().unwrap();
-                    if species.en.is_empty() {
-                        species.latin.as_str()
-                    } else {
-                        &species.en
-                    }
+                    let species = db.species_by_id(id).unwrap();
+                    let score = species.search_parts.score(&terms).unwrap_or_default();
+                    (Reverse(score), *id)
                 });
             }
         }
@@ -190,6 +221,18 @@ impl SpeciesList {
     }
 }
 
+fn name_or_latin<'a>(name: &'a str, species: &'a Species) -> &'a str {
+    if name.is_empty() {
+        species.latin.as_str()
+    } else {
+        name
+    }
+}
+
+fn terms(phrase: &str) -> Vec<&str> {
+    phrase.split_whitespace().collect()
+}
+
 #[derive(Default, Debug)]
 pub struct SpeciesListResponse {
     pub hovered: Option<Option<SpeciesId>>,
@@ -279,6 +322,15 @@ fn block(
                     ui.colored_label(color, category);
                 });
             }
+
+            if !species.taxonomy.is_empty() {
+                let lineage: Vec<_> = species
+                    .lineage()
+                    .into_iter()
+                    .map(|(_, name)| name)
+                    .collect();
+                ui.label(RichText::new(lineage.join(" › ")).weak());
+            }
         });
     });
 
@@ -155,6 +155,7 @@ fn is_regular_file(path: &Path) -> bool {
     !path.is_symlink() && path.is_file()
 }
 
+#[allow(clippy::while_let_loop)]
 fn strip_prefix(prefix: &Path, path: &Path) -> PathBuf {
     let mut path_iter = path.iter();
     let mut prefix_iter = prefix.iter();
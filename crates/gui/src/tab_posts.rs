@@ -1,3 +1,5 @@
+mod calendar;
+mod date_range;
 mod filter;
 mod group;
 mod modal_desc;
@@ -5,7 +7,10 @@ pub mod modal_publish;
 mod modal_species;
 mod modal_tags;
 mod modal_view;
+mod publishing_queue;
+mod saved_searches;
 
+use calendar::Calendar;
 use filter::Filter;
 use group::Group;
 use modal_desc::Message as ModalDescriptionMessage;
@@ -18,6 +23,7 @@ use modal_tags::Message as ModalTagsMessage;
 use modal_tags::ModalTags;
 use modal_view::Message as ModalViewMessage;
 use modal_view::ModalView;
+use saved_searches::SavedSearches;
 
 use crate::application::Message as MainMessage;
 use crate::application::MessageQueue as MainMessageQueue;
@@ -43,12 +49,18 @@ use crate::widgets::checkmark;
 use crate::ImageCounter;
 use const_format::formatcp as fmt;
 use db::edit_details::EditDetails;
+use db::edit_details::SocialMediaLink;
+use db::schedule::reschedule;
+use db::CaptionTemplate;
 use db::Database;
 use db::Date;
+use db::HashtagPolicy;
 use db::Month;
 use db::Post;
 use db::PostId;
+use db::SavedSearch;
 use db::Selector;
+use db::Source;
 use db::SpeciesId;
 use db::TagList;
 use db::Year;
@@ -70,6 +82,8 @@ use egui::SidePanel;
 use egui::TextEdit;
 use egui::TopBottomPanel;
 use egui::Ui;
+use log::error;
+use publisher::PublishOptions;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::BTreeMap;
@@ -85,6 +99,7 @@ use egui_material_icons::icons::ICON_EDIT;
 use egui_material_icons::icons::ICON_FULLSCREEN;
 use egui_material_icons::icons::ICON_GRID_ON;
 use egui_material_icons::icons::ICON_LIST;
+use egui_material_icons::icons::ICON_NEW_RELEASES;
 use egui_material_icons::icons::ICON_UNDO;
 
 const ID_PREFIX: &str = "tab-posts";
@@ -97,6 +112,8 @@ pub struct TabPosts {
     scroll_to_selected: bool,
     grid_columns: isize,
     filter: Filter,
+    saved_searches: SavedSearches,
+    calendar: Calendar,
     inline_editors: BTreeMap<(PostId, Field), InlineEditor>,
     group: Option<Group>,
     label_width: f32,
@@ -106,6 +123,9 @@ pub struct TabPosts {
     keyboard_mapping: KeyboardMapping,
 
     pub queue: MessageQueue,
+    /// Access to social media platforms, where posts can be published
+    /// using their API
+    pub credentials: socmedia::Credentials,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
@@ -154,7 +174,7 @@ pub enum Message {
     View(PostId),
     Select(PostId),
     Publish(PostId),
-    StartPublishing(PostId),
+    StartPublishing(PostId, SocialMediaLink, PublishOptions),
     InlineEditStart {
         id: PostId,
         field: Field,
@@ -212,9 +232,24 @@ pub enum Message {
     FocusSearch,
     FilterByDate(Date),
     FilterByMonth(Year, Month),
+    FilterBy(Selector),
+    CalendarPrevDay,
+    CalendarNextDay,
+    CalendarPrevWeek,
+    CalendarNextWeek,
     SetViewKind(ViewKind),
     SetGridColumns(isize),
     CopyPaths,
+    SelectSavedSearch(Option<String>),
+    SaveSearch(String),
+    UpdateSavedSearch(String),
+    DeleteSavedSearch(String),
+    LoadSavedSearch(String),
+    Search(String),
+    Reschedule(usize, usize),
+    SelectCaptionTemplate(String),
+    SaveCaptionTemplate(CaptionTemplate),
+    SetHashtagPolicy(SocialMediaLink, HashtagPolicy),
 
     EditSpeciesDetails(SpeciesId),
 }
@@ -270,9 +305,24 @@ impl Message {
             Self::FocusItem(_) => unreachable!(),
             Self::FilterByDate(_) => unreachable!(),
             Self::FilterByMonth(_, _) => unreachable!(),
+            Self::FilterBy(_) => unreachable!(),
+            Self::CalendarPrevDay => "show posts from the previous day in the calendar",
+            Self::CalendarNextDay => "show posts from the next day in the calendar",
+            Self::CalendarPrevWeek => "show posts from the previous week in the calendar",
+            Self::CalendarNextWeek => "show posts from the next week in the calendar",
             Self::SetViewKind(_) => unreachable!(),
             Self::SetGridColumns(_) => unreachable!(),
             Self::CopyPaths => unreachable!(),
+            Self::SelectSavedSearch(_) => unreachable!(),
+            Self::SaveSearch(_) => unreachable!(),
+            Self::UpdateSavedSearch(_) => unreachable!(),
+            Self::DeleteSavedSearch(_) => unreachable!(),
+            Self::LoadSavedSearch(_) => unreachable!(),
+            Self::Search(_) => unreachable!(),
+            Self::Reschedule(..) => unreachable!(),
+            Self::SelectCaptionTemplate(_) => unreachable!(),
+            Self::SaveCaptionTemplate(_) => unreachable!(),
+            Self::SetHashtagPolicy(..) => unreachable!(),
             Self::EditSpeciesDetails(_) => unreachable!(),
         }
     }
@@ -306,6 +356,13 @@ mod shortcut {
         }
     }
 
+    const fn alt(key: Key) -> KeyboardShortcut {
+        KeyboardShortcut {
+            logical_key: key,
+            modifiers: Modifiers::ALT,
+        }
+    }
+
     const fn key(key: Key) -> KeyboardShortcut {
         KeyboardShortcut {
             logical_key: key,
@@ -322,6 +379,12 @@ mod shortcut {
     pub const PREVIEW_1: KeyboardShortcut = key(Key::F);
     pub const PREVIEW_2: KeyboardShortcut = key(Key::V);
     pub const PREVIEW_3: KeyboardShortcut = key(Key::Space);
+
+    // The calendar shows weeks in columns and days of week in rows
+    pub const CALENDAR_PREV_DAY: KeyboardShortcut = alt(Key::ArrowUp);
+    pub const CALENDAR_NEXT_DAY: KeyboardShortcut = alt(Key::ArrowDown);
+    pub const CALENDAR_PREV_WEEK: KeyboardShortcut = alt(Key::ArrowLeft);
+    pub const CALENDAR_NEXT_WEEK: KeyboardShortcut = alt(Key::ArrowRight);
 }
 
 impl TabPosts {
@@ -337,6 +400,8 @@ impl TabPosts {
             grid_columns: 1,
             version: 0,
             filter: Filter::default(),
+            saved_searches: SavedSearches::default(),
+            calendar: Calendar::default(),
             queue,
             inline_editors: BTreeMap::new(),
             modal_window: ModalWindow::None,
@@ -344,6 +409,7 @@ impl TabPosts {
             label_width: 0.0,
             group: None,
             keyboard_mapping: Self::create_mapping(),
+            credentials: socmedia::Credentials::default(),
         }
     }
 
@@ -537,11 +603,11 @@ impl TabPosts {
             }
             Message::Publish(id) => {
                 assert!(self.modal_window.is_none());
-                let window = ModalPublish::new(id, db);
+                let window = ModalPublish::new(id, db, &self.credentials);
                 self.modal_window = ModalWindow::ModalPublish(Box::new(window));
             }
-            Message::StartPublishing(id) => {
-                main_queue.push_back(MainMessage::StartPublishing(id));
+            Message::StartPublishing(id, link, options) => {
+                main_queue.push_back(MainMessage::StartPublishing(id, link, options));
             }
             Message::Hovered(post_id) => {
                 self.hovered = post_id;
@@ -702,6 +768,27 @@ impl TabPosts {
                 self.scroll_to_selected = true;
                 queue.push_back(Message::RefreshView);
             }
+            Message::FilterBy(selector) => {
+                self.filter.set_current(selector);
+                self.scroll_to_selected = true;
+                queue.push_back(Message::RefreshView);
+            }
+            Message::CalendarPrevDay
+            | Message::CalendarNextDay
+            | Message::CalendarPrevWeek
+            | Message::CalendarNextWeek => {
+                let days = match message {
+                    Message::CalendarPrevDay => -1,
+                    Message::CalendarNextDay => 1,
+                    Message::CalendarPrevWeek => -7,
+                    _ => 7,
+                };
+
+                self.calendar.refresh(db);
+                if let Some(date) = self.calendar.step(&self.filter.source(), days) {
+                    queue.push_back(Message::FilterBy(Selector::ByDate(date)));
+                }
+            }
             Message::SetViewKind(view_kind) => {
                 self.view_kind = view_kind;
                 self.scroll_to_selected = true;
@@ -724,6 +811,73 @@ impl TabPosts {
 
                 main_queue.push_back(MainMessage::Copy(kind, text));
             }
+            Message::SelectSavedSearch(name) => {
+                self.filter.set_saved_search(name);
+                self.scroll_to_selected = true;
+                queue.push_back(Message::RefreshView);
+            }
+            Message::SaveSearch(name) => {
+                let search = SavedSearch::new(&name, self.filter.phrase());
+                match db.add_saved_search(search) {
+                    Ok(()) => {
+                        db.refresh_caches();
+                        self.saved_searches.clear();
+                        self.filter.set_saved_search(Some(name));
+                        self.filter.set_phrase(ctx, String::new());
+                        queue.push_back(Message::RefreshView);
+                    }
+                    Err(err) => error!("{err}"),
+                }
+            }
+            Message::UpdateSavedSearch(name) => {
+                let search = SavedSearch::new(&name, self.filter.phrase());
+                match db.update_saved_search(&name, search) {
+                    Ok(()) => {
+                        db.refresh_caches();
+                        queue.push_back(Message::RefreshView);
+                    }
+                    Err(err) => error!("{err}"),
+                }
+            }
+            Message::DeleteSavedSearch(name) => {
+                db.remove_saved_search(&name);
+                db.refresh_caches();
+                if self.filter.source() == Source::SavedSearch(name) {
+                    self.filter.set_saved_search(None);
+                }
+                queue.push_back(Message::RefreshView);
+            }
+            Message::LoadSavedSearch(name) => {
+                if let Some(search) = db.saved_searches.get(&name) {
+                    self.filter.set_phrase(ctx, search.query.clone());
+                    self.filter.set_saved_search(None);
+                    queue.push_back(Message::RefreshView);
+                }
+            }
+            Message::Search(query) => {
+                self.filter.set_phrase(ctx, query);
+                self.filter.set_saved_search(None);
+                queue.push_back(Message::RefreshView);
+            }
+            Message::Reschedule(from, to) => {
+                for (id, schedule) in reschedule(&db.scheduled(), from, to) {
+                    let edit_details = EditDetails::SetSchedule(id, Some(schedule));
+                    main_queue.push_back(edit_details.into());
+                }
+            }
+            Message::SelectCaptionTemplate(name) => {
+                db.select_caption_template(&name);
+            }
+            Message::SaveCaptionTemplate(template) => {
+                let name = template.name.clone();
+                match db.save_caption_template(template) {
+                    Ok(()) => db.select_caption_template(&name),
+                    Err(err) => error!("{err}"),
+                }
+            }
+            Message::SetHashtagPolicy(link, policy) => {
+                db.set_hashtag_policy(link, policy);
+            }
             Message::EditSpeciesDetails(id) => {
                 main_queue.push_back(MainMessage::SelectTabSpecies);
                 main_queue.push_back(TabSpeciesMessage::OpenModalEdit(id).into());
@@ -773,6 +927,10 @@ impl TabPosts {
             .key(Key::PageUp, msg(Message::SelectPrevRowsMany))
             .key(Key::Home, msg(Message::SelectFirst))
             .key(Key::End, msg(Message::SelectLast))
+            .shortcut(shortcut::CALENDAR_PREV_DAY, msg(Message::CalendarPrevDay))
+            .shortcut(shortcut::CALENDAR_NEXT_DAY, msg(Message::CalendarNextDay))
+            .shortcut(shortcut::CALENDAR_PREV_WEEK, msg(Message::CalendarPrevWeek))
+            .shortcut(shortcut::CALENDAR_NEXT_WEEK, msg(Message::CalendarNextWeek))
     }
 
     pub fn modal_opened(&self) -> bool {
@@ -865,6 +1023,35 @@ impl TabPosts {
                 });
         }
 
+        if self.filter.show_calendar() {
+            self.calendar.refresh(db);
+            SidePanel::left(fmt!("{ID_PREFIX}-calendar"))
+                .resizable(true)
+                .show(ctx, |ui| {
+                    ScrollArea::both()
+                        .id_salt(fmt!("{ID_PREFIX}-calendar-scroll"))
+                        .show(ui, |ui| {
+                            self.calendar.view(ui, &self.filter.source(), queue);
+                        });
+                });
+        }
+
+        if self.filter.show_saved_searches() {
+            SidePanel::right(fmt!("{ID_PREFIX}-saved-searches"))
+                .resizable(true)
+                .show(ctx, |ui| {
+                    self.saved_searches.view(ui, db, &self.filter, queue);
+                });
+        }
+
+        if self.filter.show_queue() {
+            SidePanel::right(fmt!("{ID_PREFIX}-publishing-queue"))
+                .resizable(true)
+                .show(ctx, |ui| {
+                    publishing_queue::view(ui, db, queue);
+                });
+        }
+
         CentralPanel::default().show(ctx, |ui| {
             match self.view_kind {
                 ViewKind::List => self.draw_main_list(ui, image_cache, style, db, queue, clipboard),
@@ -905,6 +1092,7 @@ impl TabPosts {
             });
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn draw_post(
         &self,
         ui: &mut Ui,
@@ -939,8 +1127,8 @@ impl TabPosts {
     }
 
     fn post_context_menu(&self, ui: &mut Ui, post: &Post, queue: &mut MessageQueue) {
-        let enabled = match self.filter.get_current() {
-            Selector::ByDate(date) => date != post.date,
+        let enabled = match self.filter.source() {
+            Source::Dates(Selector::ByDate(date)) => date != post.date,
             _ => true,
         };
         let button = Button::new(format!("Show posts from {}", post.date));
@@ -948,8 +1136,10 @@ impl TabPosts {
             queue.push_back(Message::FilterByDate(post.date));
         }
 
-        let enabled = match self.filter.get_current() {
-            Selector::ByMonth(year, month) => year != post.date.year || month != post.date.month,
+        let enabled = match self.filter.source() {
+            Source::Dates(Selector::ByMonth(year, month)) => {
+                year != post.date.year || month != post.date.month
+            }
             _ => true,
         };
         let button = Button::new(format!("Show posts from {}", post.date.month));
@@ -1068,6 +1258,7 @@ impl TabPosts {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn draw_post_inner(
         &self,
         ui: &mut Ui,
@@ -1147,23 +1338,17 @@ impl TabPosts {
                     self.show_species(ui, post, db, queue, clipboard);
                 });
 
-                if !post.social_media.facebook_url.is_empty() {
-                    ui.horizontal(|ui| {
-                        ui.horizontal(|ui| {
-                            ui.set_min_width(self.label_width);
-                        });
-
-                        Self::link(ui, "Facebook", &post.social_media.facebook_url, queue);
-                    });
-                }
+                for (link, status) in post.platforms.iter() {
+                    if status.url.is_empty() {
+                        continue;
+                    }
 
-                if !post.social_media.instagram_url.is_empty() {
                     ui.horizontal(|ui| {
                         ui.horizontal(|ui| {
                             ui.set_min_width(self.label_width);
                         });
 
-                        Self::link(ui, "Instagram", &post.social_media.instagram_url, queue);
+                        Self::link(ui, link.name(), &status.url, queue);
                     });
                 }
             });
@@ -1302,8 +1487,26 @@ impl TabPosts {
                 queue.push_back(Message::Copy(ClipboardKind::Species, latin.into()));
             }
             if let Some(latin) = &post.species {
-                let species = db.species_by_latin(latin).unwrap();
-                crate::species_view::singleline(ui, species);
+                let species = db.species_by_latin(latin);
+                match species {
+                    Some(species) => crate::species_view::singleline(ui, species),
+                    None => {
+                        ui.label(latin.as_str());
+                    }
+                }
+                for other in post
+                    .other_species
+                    .iter()
+                    .filter_map(|latin| db.species_by_latin(latin))
+                {
+                    ui.label("+");
+                    crate::species_view::format_latin(ui, other);
+                }
+
+                if db.is_first_record(&post.id) {
+                    ui.label(ICON_NEW_RELEASES)
+                        .on_hover_text("first record of the species");
+                }
 
                 if post.is_example {
                     if ui.button("🗙 Not a good example").clicked() {
@@ -1317,8 +1520,10 @@ impl TabPosts {
                     }
                 }
 
-                if ui.button("Edit species").clicked() {
-                    queue.push_back(Message::EditSpeciesDetails(species.id));
+                if let Some(species) = species {
+                    if ui.button("Edit species").clicked() {
+                        queue.push_back(Message::EditSpeciesDetails(species.id));
+                    }
                 }
             } else {
                 if clipboard.available(ClipboardKind::Species) {
@@ -0,0 +1,258 @@
+use super::Message;
+use super::MessageQueue;
+use chrono::Datelike;
+use db::Database;
+use db::Date;
+use db::Day;
+use db::Month;
+use db::Selector;
+use db::Source;
+use db::Year;
+use egui::pos2;
+use egui::vec2;
+use egui::Align2;
+use egui::Color32;
+use egui::CornerRadius;
+use egui::FontId;
+use egui::Rect;
+use egui::RichText;
+use egui::Sense;
+use egui::Stroke;
+use egui::StrokeKind;
+use egui::Ui;
+use std::collections::BTreeMap;
+
+const CELL: f32 = 11.0;
+const GAP: f32 = 2.0;
+const STEP: f32 = CELL + GAP;
+const WEEKS: usize = 54;
+
+const PUBLISHED: Color32 = crate::colors::MEDIUM_SEA_GREEN;
+const UNPUBLISHED: Color32 = crate::colors::DARK_ORANGE;
+
+/// Heatmap of posts: each day is coloured by the number of photos and
+/// the ratio of published ones.
+pub struct Calendar {
+    version: u64,
+    days: BTreeMap<Date, DayStats>,
+    max_count: usize,
+}
+
+#[derive(Default, Clone, Copy)]
+struct DayStats {
+    count: usize,
+    published: usize,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Self {
+            version: u64::MAX,
+            days: BTreeMap::new(),
+            max_count: 0,
+        }
+    }
+}
+
+impl Calendar {
+    pub fn refresh(&mut self, db: &Database) {
+        if self.version == db.current_version.posts {
+            return;
+        }
+
+        self.version = db.current_version.posts;
+        self.days.clear();
+        for (date, ids) in db.picture_views.days() {
+            let mut stats = DayStats::default();
+            for id in ids {
+                let post = db.post(id);
+                stats.count += post.files.len();
+                if post.is_published() {
+                    stats.published += post.files.len();
+                }
+            }
+            self.days.insert(date, stats);
+        }
+
+        self.max_count = self
+            .days
+            .values()
+            .map(|stats| stats.count)
+            .max()
+            .unwrap_or_default();
+    }
+
+    /// Finds the closest day having posts, moving by the given number of
+    /// days; moving by 7 days keeps the day of week, like in the grid.
+    pub fn step(&self, source: &Source, days: i64) -> Option<Date> {
+        let first = *self.days.keys().next()?;
+        let last = *self.days.keys().next_back()?;
+
+        let start = match source {
+            Source::Dates(Selector::ByDate(date)) => *date,
+            _ => return Some(if days > 0 { first } else { last }),
+        };
+
+        let mut date = start;
+        loop {
+            date = date.add_days(days)?;
+            if date < first || date > last {
+                return None;
+            }
+
+            if self.days.contains_key(&date) {
+                return Some(date);
+            }
+        }
+    }
+
+    pub fn view(&self, ui: &mut Ui, source: &Source, queue: &mut MessageQueue) {
+        let Some(first) = self.days.keys().next() else {
+            ui.label("no data");
+            return;
+        };
+        let last = self.days.keys().next_back().unwrap();
+
+        for year in (first.year..=last.year).rev() {
+            self.view_year(ui, year, source, queue);
+            ui.add_space(STEP);
+        }
+    }
+
+    fn view_year(&self, ui: &mut Ui, year: Year, source: &Source, queue: &mut MessageQueue) {
+        let selector = Selector::ByYear(year);
+        let selected = *source == Source::Dates(selector);
+        let label = RichText::new(year.to_string()).heading();
+        if ui.selectable_label(selected, label).clicked() && !selected {
+            queue.push_back(Message::FilterBy(selector));
+        }
+
+        let Some(jan1) = date(year, 1, 1).to_naive() else {
+            return;
+        };
+        let offset = jan1.weekday().num_days_from_monday() as usize;
+        let cell_of = |date: &Date| -> Option<(usize, usize)> {
+            let day = date.to_naive()?;
+            let index = day.ordinal0() as usize + offset;
+
+            Some((index / 7, index % 7))
+        };
+
+        // Month labels: clicking selects the whole month
+        let (rect, _) =
+            ui.allocate_exact_size(vec2(WEEKS as f32 * STEP, STEP + GAP), Sense::hover());
+        for month in 1..=12 {
+            let Some((column, _)) = cell_of(&date(year, month, 1)) else {
+                continue;
+            };
+
+            let month = Month::new(month as usize).unwrap();
+            let selector = Selector::ByMonth(year, month);
+            let has_posts = self
+                .days
+                .range(date(year, month.as_u8(), 1)..=date(year, month.as_u8(), 31))
+                .next()
+                .is_some();
+
+            let min = pos2(rect.min.x + column as f32 * STEP, rect.min.y);
+            let label_rect = Rect::from_min_size(min, vec2(3.0 * STEP, STEP));
+            let id = ui.id().with(("calendar-month", year, month.as_u8()));
+            let resp = ui.interact(label_rect, id, Sense::click());
+
+            let color = if *source == Source::Dates(selector) {
+                ui.visuals().strong_text_color()
+            } else if has_posts && resp.hovered() {
+                ui.visuals().hyperlink_color
+            } else if has_posts {
+                ui.visuals().text_color()
+            } else {
+                ui.visuals().weak_text_color()
+            };
+
+            let name = &month.to_string()[..3];
+            ui.painter().text(
+                min,
+                Align2::LEFT_TOP,
+                name,
+                FontId::proportional(CELL),
+                color,
+            );
+
+            if has_posts && resp.clicked() {
+                queue.push_back(Message::FilterBy(selector));
+            }
+        }
+
+        // Grid of days: columns are weeks, rows are days of week
+        let size = vec2(WEEKS as f32 * STEP, 7.0 * STEP);
+        let (rect, resp) = ui.allocate_exact_size(size, Sense::click());
+        let painter = ui.painter_at(rect);
+        let empty = ui.visuals().faint_bg_color;
+        let outline = Stroke::new(1.5, ui.visuals().strong_text_color());
+
+        let mut hovered: Option<(Date, DayStats)> = None;
+        let mut day = date(year, 1, 1);
+        while day.year == year {
+            let Some((column, row)) = cell_of(&day) else {
+                break;
+            };
+
+            let min = rect.min + vec2(column as f32 * STEP, row as f32 * STEP);
+            let cell = Rect::from_min_size(min, vec2(CELL, CELL));
+            let stats = self.days.get(&day).copied().unwrap_or_default();
+
+            painter.rect_filled(cell, CornerRadius::same(2), self.color(&stats, empty));
+
+            if let Source::Dates(selector) = source {
+                if stats.count > 0 && selector.matches(&day) {
+                    painter.rect_stroke(cell, CornerRadius::same(2), outline, StrokeKind::Outside);
+                }
+            }
+
+            if let Some(pos) = resp.hover_pos() {
+                if cell.expand(GAP / 2.0).contains(pos) {
+                    hovered = Some((day, stats));
+                }
+            }
+
+            let Some(next) = day.add_days(1) else {
+                break;
+            };
+            day = next;
+        }
+
+        if let Some((day, stats)) = hovered {
+            let text = match stats.count {
+                0 => format!("{day}: no photos"),
+                count => format!("{day}: {count} photo(s), {} published", stats.published),
+            };
+            let clicked = resp.clicked();
+            resp.on_hover_text_at_pointer(text);
+
+            if clicked && stats.count > 0 {
+                queue.push_back(Message::FilterBy(Selector::ByDate(day)));
+            }
+        }
+    }
+
+    fn color(&self, stats: &DayStats, empty: Color32) -> Color32 {
+        if stats.count == 0 || self.max_count == 0 {
+            return empty;
+        }
+
+        let ratio = stats.published as f32 / stats.count as f32;
+        let color = UNPUBLISHED.lerp_to_gamma(PUBLISHED, ratio);
+
+        let intensity = (1.0 + stats.count as f32).ln() / (1.0 + self.max_count as f32).ln();
+
+        empty.lerp_to_gamma(color, 0.35 + 0.65 * intensity)
+    }
+}
+
+fn date(year: Year, month: u8, day: u8) -> Date {
+    Date {
+        year,
+        month: Month::new(month as usize).unwrap(),
+        day: Day::new(day as usize).unwrap(),
+    }
+}
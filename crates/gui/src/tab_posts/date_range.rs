@@ -0,0 +1,152 @@
+use super::Message;
+use super::MessageQueue;
+use db::Date;
+use db::Season;
+use db::Selector;
+use db::Year;
+use egui::Button;
+use egui::ComboBox;
+use egui::DragValue;
+use egui::TextEdit;
+use egui::Ui;
+use std::str::FromStr;
+
+use egui_material_icons::icons::ICON_CALENDAR_VIEW_WEEK;
+use egui_material_icons::icons::ICON_DATE_RANGE;
+use egui_material_icons::icons::ICON_ECO;
+use egui_material_icons::icons::ICON_EVENT_REPEAT;
+use egui_material_icons::icons::ICON_HISTORY;
+
+/// Menu for picking selectors which are not listed in the combo box:
+/// arbitrary ranges, weeks, seasons and so on.
+pub struct DateRangeMenu {
+    from: String,
+    to: String,
+    days: u16,
+    week_year: Year,
+    week: u8,
+    season_year: Year,
+    season: Season,
+}
+
+impl Default for DateRangeMenu {
+    fn default() -> Self {
+        let today = Date::today();
+        let (week_year, week) = today.iso_week().unwrap_or((today.year, 1));
+        let (season_year, season) = Season::of(&today);
+
+        Self {
+            from: format!("{}-01-01", today.year),
+            to: today.to_string(),
+            days: 30,
+            week_year,
+            week,
+            season_year,
+            season,
+        }
+    }
+}
+
+impl DateRangeMenu {
+    pub fn view(&mut self, ui: &mut Ui, queue: &mut MessageQueue) {
+        ui.menu_button(ICON_DATE_RANGE, |ui| {
+            let mut selected: Option<Selector> = None;
+
+            ui.horizontal(|ui| {
+                ui.label(format!("{ICON_HISTORY} last"));
+                ui.add(DragValue::new(&mut self.days).range(1..=3650));
+                ui.label("days");
+                if ui.button("Show").clicked() {
+                    selected = Some(Selector::LastDays(self.days));
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(format!("{ICON_CALENDAR_VIEW_WEEK} week"));
+                ui.add(DragValue::new(&mut self.week).range(1..=53));
+                ui.label("of");
+                ui.add(DragValue::new(&mut self.week_year));
+                let selector = Selector::ByWeek(self.week_year, self.week);
+                let enabled = selector.range(&Date::today()).is_some();
+                if ui.add_enabled(enabled, Button::new("Show")).clicked() {
+                    selected = Some(selector);
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(ICON_ECO);
+                ComboBox::from_id_salt("tab-posts-season-combo-box")
+                    .selected_text(self.season.to_string())
+                    .show_ui(ui, |ui| {
+                        for season in Season::ALL {
+                            ui.selectable_value(&mut self.season, season, season.to_string());
+                        }
+                    });
+                ui.add(DragValue::new(&mut self.season_year));
+                if ui.button("Show").clicked() {
+                    selected = Some(Selector::BySeason(self.season_year, self.season));
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(ICON_DATE_RANGE);
+                ui.add(TextEdit::singleline(&mut self.from).desired_width(80.0));
+                ui.label("–");
+                ui.add(TextEdit::singleline(&mut self.to).desired_width(80.0));
+
+                let range = match (Date::from_str(&self.from), Date::from_str(&self.to)) {
+                    (Ok(from), Ok(to)) if from <= to => Ok((from, to)),
+                    (Err(err), _) | (_, Err(err)) => Err(err),
+                    _ => Err("the range is empty"),
+                };
+
+                let resp = ui.add_enabled(range.is_ok(), Button::new("Show"));
+                match range {
+                    Ok((from, to)) => {
+                        if resp.clicked() {
+                            selected = Some(Selector::ByRange(from, to));
+                        }
+                    }
+                    Err(err) => {
+                        resp.on_disabled_hover_text(err);
+                    }
+                }
+            });
+
+            ui.separator();
+
+            if ui
+                .button(format!("{ICON_EVENT_REPEAT} This day in previous years"))
+                .clicked()
+            {
+                selected = Some(Selector::OnThisDay(Date::today()));
+            }
+
+            if let Some(selector) = selected {
+                queue.push_back(Message::FilterBy(selector));
+                ui.close();
+            }
+        });
+    }
+}
+
+pub fn format_selector(selector: &Selector) -> String {
+    match selector {
+        Selector::ByRange(from, to) => format!("{ICON_DATE_RANGE} {from} – {to}"),
+        Selector::ByWeek(year, week) => format!("{ICON_CALENDAR_VIEW_WEEK} week {week} of {year}"),
+        Selector::LastDays(1) => format!("{ICON_HISTORY} today"),
+        Selector::LastDays(days) => format!("{ICON_HISTORY} last {days} days"),
+        Selector::BySeason(year, Season::Winter) => {
+            format!("{ICON_ECO} winter {year}/{}", year + 1)
+        }
+        Selector::BySeason(year, season) => format!("{ICON_ECO} {season} {year}"),
+        Selector::OnThisDay(date) => {
+            format!(
+                "{ICON_EVENT_REPEAT} {} {} in previous years",
+                date.day.as_u8(),
+                date.month
+            )
+        }
+        Selector::ByYear(_) | Selector::ByMonth(..) | Selector::ByDate(_) => unreachable!(),
+    }
+}
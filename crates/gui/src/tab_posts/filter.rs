@@ -1,3 +1,5 @@
+use super::date_range;
+use super::date_range::DateRangeMenu;
 use super::Message;
 use super::ID_PREFIX;
 use crate::file_stem;
@@ -5,27 +7,37 @@ use crate::gui::text_size;
 use crate::search_box::SearchBox;
 use crate::ImageCounter;
 use const_format::formatcp as fmt;
+use db::query::ParseError;
+use db::query::Query;
 use db::Database;
 use db::Date;
 use db::Post;
 use db::PostId;
 use db::Selector;
-use db::Species;
+use db::Source;
 use egui::ComboBox;
+use egui::Context;
 use egui::Ui;
 use serde::Deserialize;
 use serde::Serialize;
+use std::cmp::Reverse;
 use std::collections::VecDeque;
 
+use egui_material_icons::icons::ICON_BOOKMARK;
+use egui_material_icons::icons::ICON_BOOKMARKS;
 use egui_material_icons::icons::ICON_CALENDAR_MONTH;
+use egui_material_icons::icons::ICON_CALENDAR_VIEW_MONTH;
 use egui_material_icons::icons::ICON_CONTENT_COPY;
 use egui_material_icons::icons::ICON_FILTER_ALT;
 use egui_material_icons::icons::ICON_MENU;
 use egui_material_icons::icons::ICON_PUBLIC;
+use egui_material_icons::icons::ICON_SCHEDULE;
+use egui_material_icons::icons::ICON_SORT;
 
 pub struct Filter {
     pub search_box: SearchBox,
     pub filter: FilterState,
+    date_range: DateRangeMenu,
 
     icon_width: f32,
 }
@@ -38,6 +50,27 @@ pub struct FilterState {
     pub current: Selector,
     phrase: String,
 
+    #[serde(default)]
+    saved_search: Option<String>,
+
+    #[serde(default)]
+    show_saved_searches: bool,
+
+    #[serde(default)]
+    show_calendar: bool,
+
+    #[serde(default)]
+    show_queue: bool,
+
+    #[serde(default)]
+    order: Order,
+
+    #[serde(skip)]
+    query: Query,
+
+    #[serde(skip)]
+    error: Option<ParseError>,
+
     #[serde(skip)]
     count: ImageCounter,
 }
@@ -47,6 +80,7 @@ impl Default for Filter {
         Self {
             filter: FilterState::default(),
             search_box: SearchBox::new(fmt!("{ID_PREFIX}-phrase")),
+            date_range: DateRangeMenu::default(),
             icon_width: 0.0,
         }
     }
@@ -56,6 +90,9 @@ impl Filter {
     pub fn load(&mut self, storage: &dyn eframe::Storage) {
         self.filter =
             eframe::get_value(storage, fmt!("{ID_PREFIX}-filter")).unwrap_or(self.filter.clone());
+
+        let phrase = std::mem::take(&mut self.filter.phrase);
+        self.filter.set_phrase(phrase);
     }
 
     pub fn save(&self, storage: &mut dyn eframe::Storage) {
@@ -64,10 +101,49 @@ impl Filter {
 
     pub fn set_current(&mut self, selector: Selector) {
         self.filter.current = selector;
+        self.filter.saved_search = None;
+    }
+
+    /// Returns the set of posts being browsed.
+    pub fn source(&self) -> Source {
+        match &self.filter.saved_search {
+            Some(name) => Source::SavedSearch(name.clone()),
+            None => Source::Dates(self.filter.current),
+        }
+    }
+
+    pub fn set_saved_search(&mut self, name: Option<String>) {
+        self.filter.saved_search = name;
+    }
+
+    pub fn show_saved_searches(&self) -> bool {
+        self.filter.show_saved_searches
+    }
+
+    pub fn show_calendar(&self) -> bool {
+        self.filter.show_calendar
     }
 
-    pub fn get_current(&self) -> Selector {
-        self.filter.current
+    pub fn show_queue(&self) -> bool {
+        self.filter.show_queue
+    }
+
+    pub fn phrase(&self) -> &str {
+        &self.filter.phrase
+    }
+
+    pub fn is_phrase_valid(&self) -> bool {
+        self.filter.error.is_none()
+    }
+
+    pub fn set_phrase(&mut self, ctx: &Context, phrase: String) {
+        self.search_box.set_phrase(ctx, &phrase);
+        self.filter.set_phrase(phrase);
+    }
+
+    /// Counts posts from the source, respecting the published state filter.
+    pub fn count(&self, source: &Source, db: &Database) -> usize {
+        count_pictures(&source.posts(db), db, &self.filter.image_state)
     }
 
     pub fn is_extra_filter_enabled(&self) -> bool {
@@ -88,6 +164,13 @@ impl Filter {
             self.filter.current = *db.picture_views.selectors.first().unwrap();
         }
 
+        if let Some(name) = &self.filter.saved_search {
+            if db.saved_search_view(name).is_none() {
+                self.filter.saved_search = None;
+                queue.push_back(Message::RefreshView);
+            }
+        }
+
         let options = [
             ImageState::Any,
             ImageState::Unpublished,
@@ -103,43 +186,48 @@ impl Filter {
             }
         }
 
-        let selected_text = {
-            let view = db.picture_views.views.get(&self.filter.current).unwrap();
-
-            format_selector(
-                &self.filter.current,
-                count_pictures(view, db, &self.filter.image_state),
-            )
+        let selected_text = match &self.filter.saved_search {
+            Some(name) => format_saved_search(name, self.count(&self.source(), db)),
+            None => format_selector(&self.filter.current, self.count(&self.source(), db)),
         };
 
         ComboBox::from_id_salt("tab-images-filter-combo-box")
             .selected_text(selected_text)
             .show_ui(ui, |ui| {
-                let mut current = self.filter.current;
-
+                let mut chosen: Option<Selector> = None;
                 for selector in &db.picture_views.selectors {
-                    let Some(view) = db.picture_views.views.get(selector) else {
+                    let Some(view) = db.picture_views.get(*selector) else {
                         continue;
                     };
                     let label = format_selector(
                         selector,
-                        count_pictures(view, db, &self.filter.image_state),
+                        count_pictures(&view, db, &self.filter.image_state),
                     );
 
                     ui.horizontal(|ui| {
                         if matches!(selector, Selector::ByDate(_)) {
                             ui.add_space(self.icon_width);
                         }
-                        ui.selectable_value(&mut current, *selector, label);
+                        let selected =
+                            self.filter.saved_search.is_none() && *selector == self.filter.current;
+                        if ui.selectable_label(selected, label).clicked() && !selected {
+                            chosen = Some(*selector);
+                        }
                     });
                 }
 
-                if current != self.filter.current {
-                    self.filter.current = current;
+                if let Some(selector) = chosen {
+                    self.filter.current = selector;
+                    self.filter.saved_search = None;
                     queue.push_back(Message::RefreshView);
                 }
             });
 
+        self.date_range.view(ui, queue);
+
+        ui.toggle_value(&mut self.filter.show_calendar, ICON_CALENDAR_VIEW_MONTH)
+            .on_hover_text("calendar");
+
         if ui
             .toggle_value(&mut self.filter.extra, ICON_FILTER_ALT)
             .changed()
@@ -147,13 +235,29 @@ impl Filter {
             queue.push_back(Message::RefreshView);
         }
 
+        ui.toggle_value(&mut self.filter.show_saved_searches, ICON_BOOKMARKS)
+            .on_hover_text("saved searches");
+
+        ui.toggle_value(&mut self.filter.show_queue, ICON_SCHEDULE)
+            .on_hover_text("publishing queue");
+
         ui.separator();
 
-        if self.search_box.show(ui).is_some() {
-            queue.push_back(Message::RefreshView);
+        self.search_box.show_query(ui, self.filter.error.as_ref());
+
+        if self.filter.query.has_text() {
+            let label = self.filter.order.label();
+            if ui.button(label).on_hover_text("sort order").clicked() {
+                self.filter.order = self.filter.order.next();
+                queue.push_back(Message::RefreshView);
+            }
         }
 
-        self.filter.phrase = self.search_box.phrase(ui.ctx());
+        let phrase = self.search_box.phrase(ui.ctx());
+        if phrase != self.filter.phrase {
+            self.filter.set_phrase(phrase);
+            queue.push_back(Message::RefreshView);
+        }
 
         if self.filter.is_enabled() {
             ui.label(self.filter.count.to_string());
@@ -179,35 +283,29 @@ impl Filter {
     }
 
     pub fn make_view(&mut self, db: &Database) -> Vec<PostId> {
-        let mut tmp = Vec::<(PostId, (Date, String))>::new();
-        for post in db
-            .posts
+        let by_relevance = self.filter.order == Order::Relevance && self.filter.query.has_text();
+
+        let mut tmp = Vec::<(PostId, (Reverse<u32>, Date, String))>::new();
+        let source = self.source();
+        for post in source
+            .posts(db)
             .iter()
+            .map(|id| db.post(id))
             .filter(|post| self.filter.matches(post))
-            .filter(|post| {
-                if self.filter.post_matches_qs(post) {
-                    return true;
-                }
-
-                let Some(latin) = &post.species else {
-                    return false;
-                };
-
-                if let Some(species) = db.species_by_latin(latin) {
-                    self.filter.species_matches_qs(species)
-                } else {
-                    false
-                }
-            })
         {
+            let Some(score) = self.filter.query.score(post, db) else {
+                continue;
+            };
+
+            let score = if by_relevance { score } else { 0 };
             let stem = file_stem(&post.files[0].rel_path);
-            let item = (post.id, (post.date, stem));
+            let item = (post.id, (Reverse(score), post.date, stem));
             tmp.push(item);
         }
 
         self.filter.count = ImageCounter(tmp.len());
 
-        tmp.sort_by_key(|(_id, (date, stem))| (*date, stem.clone()));
+        tmp.sort_by(|(_, a), (_, b)| a.cmp(b));
 
         tmp.iter().map(|(id, _)| *id).collect()
     }
@@ -218,6 +316,7 @@ fn format_selector(selector: &Selector, count: usize) -> String {
         Selector::ByYear(year) => format!("{ICON_PUBLIC} {year}"),
         Selector::ByMonth(year, month) => format!("{ICON_CALENDAR_MONTH} {month} {year}"),
         Selector::ByDate(date) => format!("{:02}-{:02}", date.month.as_u8(), date.day.as_u8()),
+        _ => date_range::format_selector(selector),
     };
 
     if count > 0 {
@@ -227,6 +326,14 @@ fn format_selector(selector: &Selector, count: usize) -> String {
     }
 }
 
+fn format_saved_search(name: &str, count: usize) -> String {
+    if count > 0 {
+        format!("{ICON_BOOKMARK} {name} ({count})")
+    } else {
+        format!("{ICON_BOOKMARK} {name}")
+    }
+}
+
 fn count_pictures(view: &[PostId], db: &Database, image_state: &ImageState) -> usize {
     match image_state {
         ImageState::Any => view.len(),
@@ -270,6 +377,31 @@ impl ImageState {
 
 // --------------------------------------------------
 
+#[derive(Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum Order {
+    #[default]
+    Date,
+    Relevance,
+}
+
+impl Order {
+    const fn label(&self) -> &'static str {
+        match self {
+            Self::Date => fmt!("{ICON_CALENDAR_MONTH} by date"),
+            Self::Relevance => fmt!("{ICON_SORT} by relevance"),
+        }
+    }
+
+    const fn next(&self) -> Self {
+        match self {
+            Self::Date => Self::Relevance,
+            Self::Relevance => Self::Date,
+        }
+    }
+}
+
+// --------------------------------------------------
+
 impl Default for FilterState {
     fn default() -> Self {
         Self {
@@ -279,6 +411,13 @@ impl Default for FilterState {
             current: Selector::ByYear(0),
             count: ImageCounter(0),
             phrase: String::new(),
+            saved_search: None,
+            show_saved_searches: false,
+            show_calendar: false,
+            show_queue: false,
+            order: Order::Date,
+            query: Query::All,
+            error: None,
         }
     }
 }
@@ -293,10 +432,6 @@ impl FilterState {
             return false;
         }
 
-        if !self.current.matches(&post.date) {
-            return false;
-        }
-
         if self.extra {
             let no_tags = post.tags.is_empty();
             if self.no_tags != no_tags {
@@ -307,11 +442,19 @@ impl FilterState {
         true
     }
 
-    fn post_matches_qs(&self, post: &Post) -> bool {
-        post.search_parts.matches(&self.phrase)
-    }
+    /// Sets the search phrase; if it's not a valid query, the previous query
+    /// stays in use until the phrase gets fixed.
+    fn set_phrase(&mut self, phrase: String) {
+        match Query::parse(&phrase) {
+            Ok(query) => {
+                self.query = query;
+                self.error = None;
+            }
+            Err(error) => {
+                self.error = Some(error);
+            }
+        }
 
-    fn species_matches_qs(&self, species: &Species) -> bool {
-        species.search_parts.matches(&self.phrase)
+        self.phrase = phrase;
     }
 }
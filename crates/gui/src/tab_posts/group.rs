@@ -49,7 +49,7 @@ impl Group {
         let mut en = Vec::<String>::new();
         let mut raw_tags = Vec::<String>::new();
         let mut files = Vec::<FileMetadata>::new();
-        let mut species: Option<Latin> = None;
+        let mut species = Vec::<Latin>::new();
 
         for id in &self.items {
             let post = db.post(id);
@@ -65,8 +65,10 @@ impl Group {
                 raw_tags.push(tag.clone());
             }
 
-            if species.is_none() && post.species.is_some() {
-                species = post.species.clone();
+            for latin in post.all_species() {
+                if !species.contains(latin) {
+                    species.push(latin.clone());
+                }
             }
 
             for entry in &post.files {
@@ -87,7 +89,7 @@ impl Group {
         post.pl = pl;
         post.en = en;
         post.tags = tags;
-        post.species = species;
+        post.set_species_list(species);
 
         for id in self.iter().skip(1) {
             db.drop_post(id);
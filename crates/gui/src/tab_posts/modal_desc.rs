@@ -16,8 +16,10 @@ use const_format::formatcp as fmt;
 use db::edit_details::EditDetails;
 use db::edit_details::SocialMediaLink;
 use db::Database;
+use db::Location;
 use db::PostId;
 use egui::Align;
+use egui::Button;
 use egui::CentralPanel;
 use egui::Context;
 use egui::Id;
@@ -28,7 +30,10 @@ use egui::SidePanel;
 use egui::TextEdit;
 use egui::TopBottomPanel;
 use std::collections::VecDeque;
+use std::str::FromStr;
 
+use egui_material_icons::icons::ICON_AUTO_AWESOME;
+use egui_material_icons::icons::ICON_ERROR;
 use egui_material_icons::icons::ICON_WARNING;
 
 const ID_PREFIX: &str = "post-description";
@@ -51,8 +56,23 @@ type MessageQueue = VecDeque<Message>;
 struct Description {
     pl: String,
     en: String,
-    facebook_url: String,
-    instagram_url: String,
+    /// URLs of posts on social media, in order of `SocialMediaLink::ALL`
+    urls: Vec<String>,
+    /// Polish and English alt text of each photo
+    alt: Vec<(String, String)>,
+    /// Coordinates as "latitude, longitude", empty when unknown
+    location: String,
+}
+
+impl Description {
+    fn location(&self) -> Result<Option<Location>, &'static str> {
+        let text = self.location.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+
+        Location::from_str(text).map(Some)
+    }
 }
 
 #[derive(Clone)]
@@ -89,8 +109,19 @@ impl ModalDescription {
         let original = Description {
             pl: post.pl.clone(),
             en: post.en.clone(),
-            facebook_url: post.social_media.facebook_url.clone(),
-            instagram_url: post.social_media.instagram_url.clone(),
+            urls: SocialMediaLink::ALL
+                .iter()
+                .map(|link| post.platforms.url(*link).to_owned())
+                .collect(),
+            alt: post
+                .files
+                .iter()
+                .map(|file| (file.alt_pl.clone(), file.alt_en.clone()))
+                .collect(),
+            location: post
+                .location
+                .map(|location| location.to_string())
+                .unwrap_or_default(),
         };
         let new = original.clone();
 
@@ -162,21 +193,27 @@ impl ModalDescription {
                     let msg = EditDetails::SetEnglish(self.id, self.new.en.clone());
                     tab_queue.push_back(msg.into());
                 }
-                if self.new.facebook_url != self.original.facebook_url {
-                    let msg = EditDetails::SetSocialMediaLink(
-                        self.id,
-                        self.new.facebook_url.clone(),
-                        SocialMediaLink::Facebook,
-                    );
-                    tab_queue.push_back(msg.into());
+                for (i, (pl, en)) in self.new.alt.iter().enumerate() {
+                    if (pl, en) != (&self.original.alt[i].0, &self.original.alt[i].1) {
+                        let msg = EditDetails::SetAltText(self.id, i, pl.clone(), en.clone());
+                        tab_queue.push_back(msg.into());
+                    }
                 }
-                if self.new.instagram_url != self.original.instagram_url {
-                    let msg = EditDetails::SetSocialMediaLink(
-                        self.id,
-                        self.new.instagram_url.clone(),
-                        SocialMediaLink::Instagram,
-                    );
-                    tab_queue.push_back(msg.into());
+                if let Ok(location) = self.new.location() {
+                    if location != self.original.location().unwrap_or_default() {
+                        let msg = EditDetails::SetLocation(self.id, location);
+                        tab_queue.push_back(msg.into());
+                    }
+                }
+                for (i, link) in SocialMediaLink::ALL.iter().enumerate() {
+                    if self.new.urls[i] != self.original.urls[i] {
+                        let msg = EditDetails::SetSocialMediaLink(
+                            self.id,
+                            self.new.urls[i].clone(),
+                            *link,
+                        );
+                        tab_queue.push_back(msg.into());
+                    }
                 }
             }
             Message::FocusPolish => {
@@ -226,7 +263,8 @@ impl ModalDescription {
 
         TopBottomPanel::bottom(fmt!("{ID_PREFIX}-buttons")).show(ctx, |ui| {
             ui.with_layout(Layout::right_to_left(Align::Min), |ui| {
-                if button::save(ui, self.is_modified(), Some(style.button.save)) {
+                let enabled = self.is_modified() && self.new.location().is_ok();
+                if button::save(ui, enabled, Some(style.button.save)) {
                     self.queue.push_back(Message::SaveAndExit);
                 }
                 if button::cancel(ui) {
@@ -259,31 +297,72 @@ impl ModalDescription {
                     ui.add(edit);
                 });
 
-                ui.horizontal(|ui| {
-                    ui.label("Facebook");
+                ui.separator();
+                ui.label("Alt text");
+
+                let default = db.default_alt_text(post);
+                for (i, (pl, en)) in self.new.alt.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("#{}", i + 1));
+
+                        let width = (ui.available_width() - 60.0) / 2.0;
+                        icon_pl(ui);
+                        let edit = TextEdit::singleline(pl)
+                            .hint_text(default.pl.as_str())
+                            .desired_width(width);
+                        ui.add(edit);
+
+                        icon_en(ui);
+                        let edit = TextEdit::singleline(en)
+                            .hint_text(default.en.as_str())
+                            .desired_width(width);
+                        ui.add(edit);
+
+                        let enabled = pl.is_empty() || en.is_empty();
+                        let resp = ui
+                            .add_enabled(enabled, Button::new(ICON_AUTO_AWESOME))
+                            .on_hover_text("fill in the generated alt text");
+                        if resp.clicked() {
+                            if pl.is_empty() {
+                                pl.clone_from(&default.pl);
+                            }
+                            if en.is_empty() {
+                                en.clone_from(&default.en);
+                            }
+                        }
+                    });
+                }
+
+                ui.separator();
 
-                    let edit = TextEdit::singleline(&mut self.new.facebook_url).hint_text("URL");
+                ui.horizontal(|ui| {
+                    ui.label("Location");
 
+                    let edit = TextEdit::singleline(&mut self.new.location)
+                        .hint_text("latitude, longitude");
                     ui.add(edit);
 
-                    let url = &self.new.facebook_url;
-                    if !url.is_empty() {
-                        ui.hyperlink_to("visit", url).on_hover_text(url);
+                    if let Err(err) = self.new.location() {
+                        ui.colored_label(style.error, format!("{ICON_ERROR} {err}"));
                     }
                 });
 
-                ui.horizontal(|ui| {
-                    ui.label("Instagram");
+                ui.separator();
 
-                    let edit = TextEdit::singleline(&mut self.new.instagram_url).hint_text("URL");
+                for (link, url) in SocialMediaLink::ALL.iter().zip(self.new.urls.iter_mut()) {
+                    ui.horizontal(|ui| {
+                        ui.label(link.name());
 
-                    ui.add(edit);
+                        let edit = TextEdit::singleline(url).hint_text("URL");
 
-                    let url = &self.new.instagram_url;
-                    if !url.is_empty() {
-                        ui.hyperlink_to("visit", url).on_hover_text(url);
-                    }
-                });
+                        ui.add(edit);
+
+                        if !url.is_empty() {
+                            ui.hyperlink_to("visit", url.as_str())
+                                .on_hover_text(url.as_str());
+                        }
+                    });
+                }
             });
         });
     }
@@ -9,31 +9,65 @@ use crate::tab_posts::Message as TabMessage;
 use crate::tab_posts::MessageQueue as TabMessageQueue;
 use crate::widgets::checkmark;
 use const_format::formatcp as fmt;
+use db::caption::Variable;
 use db::edit_details::EditDetails;
+use db::edit_details::SocialMediaLink;
+use db::hashtags::HashtagLanguage;
+use db::hashtags::HashtagOrder;
+use db::hashtags::INSTAGRAM_MAX_HASHTAGS;
+use db::render_caption;
+use db::schedule::format_date_time;
+use db::schedule::parse_date_time;
+use db::CaptionTemplate;
 use db::Database;
+use db::HashtagPolicy;
 use db::Post;
 use db::PostId;
 use db::PublishedState;
+use db::Schedule;
 use egui::vec2;
 use egui::Align;
 use egui::Button;
 use egui::CentralPanel;
+use egui::ComboBox;
 use egui::Context;
+use egui::DragValue;
 use egui::Key;
 use egui::Layout;
 use egui::ScrollArea;
 use egui::SidePanel;
+use egui::TextEdit;
 use egui::TopBottomPanel;
+use egui::Ui;
+use publisher::Draft;
+use publisher::PublishOptions;
+use publisher::Publisher;
+use socmedia::mastodon::Visibility;
 use std::collections::VecDeque;
 
+use egui_material_icons::icons::ICON_CHECK;
 use egui_material_icons::icons::ICON_CONTENT_COPY;
+use egui_material_icons::icons::ICON_DESCRIPTION;
+use egui_material_icons::icons::ICON_EDIT;
 use egui_material_icons::icons::ICON_PUBLISH;
+use egui_material_icons::icons::ICON_SAVE;
+use egui_material_icons::icons::ICON_SCHEDULE;
+use egui_material_icons::icons::ICON_SHARE;
+use egui_material_icons::icons::ICON_TAG;
+use egui_material_icons::icons::ICON_WARNING;
 
 const ID_PREFIX: &str = "publish-image";
 
 pub struct ModalPublish {
     id: PostId,
     entries: Vec<Entry>,
+    platforms: Vec<Platform>,
+    options: PublishOptions,
+    schedule: ScheduleEditor,
+    template: TemplateEditor,
+    /// Versions of caption templates and hashtag policies the platform
+    /// limits were checked against
+    version: (u64, u64),
 
     pub queue: MessageQueue,
     pub keyboard_mapping: KeyboardMapping,
@@ -47,6 +81,117 @@ struct Entry {
     label: String,
 }
 
+struct Platform {
+    publisher: Box<dyn Publisher>,
+    link: SocialMediaLink,
+    /// URL of the post if it's already published
+    url: String,
+    /// Limits of the platform violated by the post
+    errors: Vec<String>,
+    /// Error of the previous attempt to publish
+    last_error: String,
+}
+
+/// Caption template being previewed and edited.
+struct TemplateEditor {
+    template: CaptionTemplate,
+    /// Platform of the previewed caption, `None` for the copied text
+    platform: Option<SocialMediaLink>,
+    show: bool,
+    error: Option<String>,
+    /// Caption rendered with the edited template; copying and publishing
+    /// use the saved one
+    preview: String,
+}
+
+impl TemplateEditor {
+    fn new(db: &Database) -> Self {
+        Self {
+            template: db.caption_templates.current().into_owned(),
+            platform: None,
+            show: false,
+            error: None,
+            preview: String::new(),
+        }
+    }
+
+    /// Updates the preview; keeps the previous one if the template is wrong.
+    fn render(&mut self, post: &Post, db: &Database) {
+        match self.template.render(post, db, self.platform) {
+            Ok(text) => {
+                self.error = None;
+                self.preview = text;
+            }
+            Err(err) => {
+                self.error = Some(err);
+            }
+        }
+    }
+}
+
+/// Planned date and platforms, as edited by the user.
+struct ScheduleEditor {
+    at: String,
+    platforms: Vec<(SocialMediaLink, bool)>,
+    scheduled: bool,
+}
+
+impl ScheduleEditor {
+    fn new(post: &Post, db: &Database, platforms: &[Platform]) -> Self {
+        let at = match &post.schedule {
+            Some(schedule) => schedule.at,
+            None => db.next_slot(&chrono::Local::now()),
+        };
+
+        let platforms = platforms
+            .iter()
+            .filter(|platform| platform.url.is_empty())
+            .map(|platform| {
+                let checked = post
+                    .schedule
+                    .as_ref()
+                    .is_none_or(|schedule| schedule.platforms.contains(&platform.link));
+
+                (platform.link, checked)
+            })
+            .collect();
+
+        Self {
+            at: format_date_time(&at),
+            platforms,
+            scheduled: post.schedule.is_some(),
+        }
+    }
+
+    fn schedule(&self) -> Result<Schedule, String> {
+        let at = parse_date_time(&self.at)?;
+        let platforms: Vec<SocialMediaLink> = self
+            .platforms
+            .iter()
+            .filter_map(|(link, checked)| checked.then_some(*link))
+            .collect();
+
+        if platforms.is_empty() {
+            return Err("no platform selected".to_owned());
+        }
+
+        Ok(Schedule { at, platforms })
+    }
+}
+
+impl Platform {
+    fn can_publish(&self) -> bool {
+        self.url.is_empty() && self.errors.is_empty()
+    }
+
+    /// Checks the caption rendered with the saved template.
+    fn validate(&mut self, post: &Post, db: &Database) {
+        let caption = render_caption(post, db, Some(self.link));
+        let draft = Draft::new(post, db, caption, PublishOptions::default());
+        self.errors = self.publisher.validate(&draft);
+    }
+}
+
 #[derive(Clone)]
 pub enum Message {
     Publish,
@@ -60,14 +205,30 @@ pub enum Message {
     Copy8,
     Copy9,
     Cancel,
-    PublishOnSocialMedia,
+    PublishOnSocialMedia(SocialMediaLink),
+    Schedule,
+    Unschedule,
+    SelectTemplate(String),
+    SaveTemplate,
+    SetHashtagPolicy(SocialMediaLink, HashtagPolicy),
 }
 
 impl Message {
     pub const fn name(&self) -> &str {
         match self {
             Self::Publish => "mark post as published and close window",
-            Self::PublishOnSocialMedia => unreachable!(),
+            Self::PublishOnSocialMedia(SocialMediaLink::Facebook) => {
+                "publish post on Facebook and close window"
+            }
+            Self::PublishOnSocialMedia(SocialMediaLink::Instagram) => {
+                "publish post on Instagram and close window"
+            }
+            Self::PublishOnSocialMedia(SocialMediaLink::Mastodon) => {
+                "publish post on Mastodon and close window"
+            }
+            Self::PublishOnSocialMedia(SocialMediaLink::Bluesky) => {
+                "publish post on Bluesky and close window"
+            }
             Self::Copy1 => "copy text to clipboard",
             Self::Copy2 => "copy path of 1st photo",
             Self::Copy3 => "copy path of 2nd photo",
@@ -78,15 +239,20 @@ impl Message {
             Self::Copy8 => "copy path of 7th photo",
             Self::Copy9 => "copy path of 8th photo",
             Self::Cancel => "cancel publishing",
+            Self::Schedule => unreachable!(),
+            Self::Unschedule => unreachable!(),
+            Self::SelectTemplate(_) => unreachable!(),
+            Self::SaveTemplate => unreachable!(),
+            Self::SetHashtagPolicy(..) => unreachable!(),
         }
     }
 }
 
 impl ModalPublish {
-    pub fn new(id: PostId, db: &Database) -> Self {
+    pub fn new(id: PostId, db: &Database, credentials: &socmedia::Credentials) -> Self {
         let post = db.post(&id);
 
-        let text = render_text(post, db);
+        let text = render_caption(post, db, None);
         let mut entries = vec![Entry {
             copied: false,
             label: "Copy text".to_owned(),
@@ -101,9 +267,38 @@ impl ModalPublish {
             });
         }
 
+        let platforms: Vec<Platform> = publisher::publishers(credentials)
+            .into_iter()
+            .map(|publisher| {
+                let link = publisher.platform();
+                let status = post.platforms.get(link).cloned().unwrap_or_default();
+
+                let mut platform = Platform {
+                    publisher,
+                    link,
+                    url: status.url,
+                    errors: Vec::new(),
+                    last_error: status.last_error,
+                };
+                platform.validate(post, db);
+
+                platform
+            })
+            .collect();
+
+        let schedule = ScheduleEditor::new(post, db, &platforms);
+
         Self {
             id,
             entries,
+            platforms,
+            options: PublishOptions::new(credentials),
+            schedule,
+            template: TemplateEditor::new(db),
+            version: (
+                db.current_version.caption_templates,
+                db.current_version.hashtag_policies,
+            ),
             queue: MessageQueue::new(),
             keyboard_mapping: Self::create_mapping(),
         }
@@ -127,9 +322,25 @@ impl ModalPublish {
             .key(Key::Num9, msg(Message::Copy9))
             .ctrl(Key::S, msg(Message::Publish))
             .ctrl(Key::P, msg(Message::Publish))
+            .ctrl(
+                Key::F,
+                msg(Message::PublishOnSocialMedia(SocialMediaLink::Facebook)),
+            )
+            .ctrl(
+                Key::I,
+                msg(Message::PublishOnSocialMedia(SocialMediaLink::Instagram)),
+            )
+            .ctrl(
+                Key::M,
+                msg(Message::PublishOnSocialMedia(SocialMediaLink::Mastodon)),
+            )
+            .ctrl(
+                Key::B,
+                msg(Message::PublishOnSocialMedia(SocialMediaLink::Bluesky)),
+            )
     }
 
-    fn handle_message(&mut self, msg: Message, tab_queue: &mut TabMessageQueue) {
+    fn handle_message(&mut self, msg: Message, db: &Database, tab_queue: &mut TabMessageQueue) {
         match msg {
             Message::Cancel => {
                 tab_queue.push_back(TabMessage::CloseModal);
@@ -203,10 +414,49 @@ impl ModalPublish {
                 );
                 tab_queue.push_back(TabMessage::CloseModal);
             }
-            Message::PublishOnSocialMedia => {
-                tab_queue.push_back(TabMessage::StartPublishing(self.id));
+            Message::PublishOnSocialMedia(link) => {
+                let enabled = self
+                    .platforms
+                    .iter()
+                    .any(|platform| platform.link == link && platform.can_publish());
+                if !enabled {
+                    return;
+                }
+
+                tab_queue.push_back(TabMessage::StartPublishing(
+                    self.id,
+                    link,
+                    self.options.clone(),
+                ));
+                tab_queue.push_back(TabMessage::CloseModal);
+            }
+            Message::Schedule => {
+                if let Ok(schedule) = self.schedule.schedule() {
+                    tab_queue.push_back(EditDetails::SetSchedule(self.id, Some(schedule)).into());
+                    tab_queue.push_back(TabMessage::CloseModal);
+                }
+            }
+            Message::Unschedule => {
+                tab_queue.push_back(EditDetails::SetSchedule(self.id, None).into());
                 tab_queue.push_back(TabMessage::CloseModal);
             }
+            Message::SelectTemplate(name) => {
+                self.template.template = db.caption_templates.get_or_default(&name).into_owned();
+                tab_queue.push_back(TabMessage::SelectCaptionTemplate(name));
+            }
+            Message::SetHashtagPolicy(link, policy) => {
+                tab_queue.push_back(TabMessage::SetHashtagPolicy(link, policy));
+            }
+            Message::SaveTemplate => {
+                if let Err(err) = self.template.template.validate() {
+                    self.template.error = Some(err);
+                    return;
+                }
+
+                tab_queue.push_back(TabMessage::SaveCaptionTemplate(
+                    self.template.template.clone(),
+                ));
+            }
         }
     }
 
@@ -219,10 +469,23 @@ impl ModalPublish {
         tab_queue: &mut TabMessageQueue,
     ) {
         while let Some(msg) = self.queue.pop_front() {
-            self.handle_message(msg, tab_queue);
+            self.handle_message(msg, db, tab_queue);
         }
 
         let post = db.post(&self.id);
+        let version = (
+            db.current_version.caption_templates,
+            db.current_version.hashtag_policies,
+        );
+        if self.version != version {
+            self.version = version;
+            self.entries[0].text = render_caption(post, db, None);
+            for platform in self.platforms.iter_mut() {
+                platform.validate(post, db);
+            }
+        }
+
+        self.template.render(post, db);
 
         SidePanel::left(fmt!("{ID_PREFIX}-left"))
             .resizable(false)
@@ -251,6 +514,23 @@ impl ModalPublish {
                     self.queue.push_back(Message::Publish);
                 }
 
+                for platform in self.platforms.iter().rev() {
+                    let label = format!("{ICON_SHARE} Publish on {}", platform.link.name());
+                    let button = Button::new(label).fill(style.button.publish);
+
+                    let resp = ui.add_enabled(platform.can_publish(), button);
+                    let resp = if !platform.url.is_empty() {
+                        resp.on_disabled_hover_text(format!("Already published: {}", platform.url))
+                    } else {
+                        resp.on_disabled_hover_text(platform.errors.join("\n"))
+                    };
+
+                    if resp.clicked() {
+                        self.queue
+                            .push_back(Message::PublishOnSocialMedia(platform.link));
+                    }
+                }
+
                 if button::cancel(ui) {
                     self.queue.push_back(Message::Cancel);
                 }
@@ -259,8 +539,111 @@ impl ModalPublish {
 
         CentralPanel::default().show(ctx, |ui| {
             ui.vertical_centered_justified(|ui| {
-                let mut read_only: &str = &self.entries[0].text;
+                self.template_view(ui, db);
+
+                let mut read_only: &str = &self.template.preview;
                 ui.text_edit_multiline(&mut read_only);
+                if self.template.platform.is_none() && self.template.preview != self.entries[0].text
+                {
+                    ui.weak("preview of the unsaved template, the copied text differs");
+                }
+
+                if let Some(err) = &self.template.error {
+                    ui.colored_label(
+                        ui.visuals().error_fg_color,
+                        format!("{ICON_WARNING} template: {err}"),
+                    );
+                }
+
+                for platform in &self.platforms {
+                    for err in &platform.errors {
+                        ui.colored_label(
+                            ui.visuals().error_fg_color,
+                            format!("{ICON_WARNING} {}: {err}", platform.link.name()),
+                        );
+                    }
+
+                    if !platform.last_error.is_empty() {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!(
+                                "{ICON_WARNING} {}: the last attempt failed: {}",
+                                platform.link.name(),
+                                platform.last_error
+                            ),
+                        );
+                    }
+                }
+
+                let mastodon = self
+                    .platforms
+                    .iter()
+                    .any(|platform| platform.link == SocialMediaLink::Mastodon);
+                if mastodon {
+                    ui.horizontal(|ui| {
+                        ui.label("Mastodon");
+                        ComboBox::from_id_salt(fmt!("{ID_PREFIX}-visibility"))
+                            .selected_text(self.options.visibility.to_string())
+                            .show_ui(ui, |ui| {
+                                for visibility in Visibility::ALL {
+                                    ui.selectable_value(
+                                        &mut self.options.visibility,
+                                        visibility,
+                                        visibility.to_string(),
+                                    );
+                                }
+                            });
+
+                        let edit = TextEdit::singleline(&mut self.options.content_warning)
+                            .hint_text("content warning")
+                            .desired_width(f32::INFINITY);
+                        ui.add(edit);
+                    });
+                }
+
+                if !self.schedule.platforms.is_empty() {
+                    ui.horizontal(|ui| {
+                        ui.label(fmt!("{ICON_SCHEDULE} Schedule"));
+                        let edit = TextEdit::singleline(&mut self.schedule.at)
+                            .hint_text("YYYY-MM-DD HH:MM")
+                            .desired_width(120.0);
+                        ui.add(edit);
+
+                        for (link, checked) in self.schedule.platforms.iter_mut() {
+                            ui.checkbox(checked, link.name());
+                        }
+
+                        let schedule = self.schedule.schedule();
+                        let label = if self.schedule.scheduled {
+                            "Reschedule"
+                        } else {
+                            "Schedule"
+                        };
+                        let resp = ui.add_enabled(schedule.is_ok(), Button::new(label));
+                        if let Err(err) = schedule {
+                            resp.on_disabled_hover_text(err);
+                        } else if resp.clicked() {
+                            self.queue.push_back(Message::Schedule);
+                        }
+
+                        if self.schedule.scheduled && ui.button("Unschedule").clicked() {
+                            self.queue.push_back(Message::Unschedule);
+                        }
+                    });
+                }
+
+                ui.separator();
+
+                for (ok, text) in checklist(post, db) {
+                    if ok {
+                        ui.label(format!("{ICON_CHECK} {text}"));
+                    } else {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!("{ICON_WARNING} {text}"),
+                        );
+                    }
+                }
 
                 ui.separator();
 
@@ -289,121 +672,175 @@ impl ModalPublish {
         });
     }
 
-    pub fn try_close(&mut self) {
-        self.queue.push_back(Message::Cancel);
-    }
-}
-
-// --------------------------------------------------
-
-const PL_EMOJI: &str = "🇵🇱";
-const EN_EMOJI: &str = "🇬🇧";
-
-pub fn render_text(post: &Post, db: &Database) -> String {
-    let mut f = Builder::default();
-
-    if !post.pl.is_empty() {
-        f.writeln(format!("{PL_EMOJI} {}", post.pl));
-    }
-
-    if !post.en.is_empty() {
-        f.writeln(format!("{EN_EMOJI} {}", post.en));
-    }
-
-    if post.species.is_some() && !f.is_empty() {
-        f.newline();
-
-        let latin = post.species.as_ref().unwrap();
-        let species = db.species_by_latin(latin).unwrap();
-        let latin = latin.as_str();
-
-        let pl = format_species(PL_EMOJI, &species.pl);
-        let en = format_species(EN_EMOJI, &species.en);
-        f.writeln(match (pl, en) {
-            (None, None) => latin.to_owned(),
-            (Some(pl), None) => format!("{latin} ({pl})"),
-            (None, Some(en)) => format!("{latin} ({en})"),
-            (Some(pl), Some(en)) => format!("{latin} ({pl} {en})"),
+    fn template_view(&mut self, ui: &mut Ui, db: &Database) {
+        ui.horizontal(|ui| {
+            ComboBox::from_id_salt(fmt!("{ID_PREFIX}-template"))
+                .selected_text(format!(
+                    "{ICON_DESCRIPTION} {}",
+                    self.template.template.name
+                ))
+                .show_ui(ui, |ui| {
+                    for name in db.caption_templates.names() {
+                        let selected = name == self.template.template.name;
+                        if ui.selectable_label(selected, name).clicked() && !selected {
+                            self.queue
+                                .push_back(Message::SelectTemplate(name.to_owned()));
+                        }
+                    }
+                });
+
+            let platform_name = |platform: Option<SocialMediaLink>| {
+                platform.map_or("copied text".to_owned(), |link| link.name().to_owned())
+            };
+            ComboBox::from_id_salt(fmt!("{ID_PREFIX}-template-platform"))
+                .selected_text(format!(
+                    "preview: {}",
+                    platform_name(self.template.platform)
+                ))
+                .show_ui(ui, |ui| {
+                    let options = std::iter::once(None)
+                        .chain(self.platforms.iter().map(|platform| Some(platform.link)));
+                    for option in options {
+                        ui.selectable_value(
+                            &mut self.template.platform,
+                            option,
+                            platform_name(option),
+                        );
+                    }
+                });
+
+            ui.toggle_value(&mut self.template.show, fmt!("{ICON_EDIT} Edit template"));
         });
-    }
 
-    if f.is_empty() && post.species.is_some() {
-        let latin = post.species.as_ref().unwrap();
-        let species = db.species_by_latin(latin).unwrap();
-
-        if !species.pl.is_empty() {
-            f.writeln(format!(
-                "{PL_EMOJI} {} ({})",
-                species.pl,
-                &species.latin.as_str()
-            ));
-            if !species.en.is_empty() {
-                f.writeln(format!("{EN_EMOJI} {}", species.en));
-            }
-        } else if !species.en.is_empty() {
-            f.writeln(format!(
-                "{EN_EMOJI} {} ({})",
-                species.en,
-                species.latin.as_str()
-            ));
-        } else {
-            f.writeln(species.latin.as_str().to_string());
+        if !self.template.show {
+            return;
         }
-    }
-
-    if !f.is_empty() {
-        f.newline()
-    }
 
-    let mut first = true;
-    for tag in post.tags.iter() {
-        if db.ignored_tags.contains(tag) {
-            continue;
+        if let Some(link) = self.template.platform {
+            self.hashtag_policy_view(ui, db, link);
         }
 
-        if !first {
-            f.put_char(' ');
+        let template = &mut self.template.template;
+        if let Some(link) = self.template.platform {
+            let mut own = template.overrides.contains_key(&link);
+            if ui
+                .checkbox(&mut own, format!("separate template for {}", link.name()))
+                .changed()
+            {
+                if own {
+                    template.overrides.insert(link, template.text.clone());
+                } else {
+                    template.overrides.remove(&link);
+                }
+            }
         }
 
-        first = false;
-        f.write(format!("#{tag}"));
-    }
-
-    f.buf
-}
+        let text = match self.template.platform {
+            Some(link) if template.overrides.contains_key(&link) => {
+                template.overrides.get_mut(&link).unwrap()
+            }
+            _ => &mut template.text,
+        };
+        let edit = TextEdit::multiline(text)
+            .code_editor()
+            .desired_rows(8)
+            .desired_width(f32::INFINITY);
+        ui.add(edit);
+
+        let variables: Vec<String> = Variable::ALL
+            .iter()
+            .map(|var| format!("{{{{{}}}}} {}", var.name(), var.description()))
+            .collect();
+
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.add(TextEdit::singleline(&mut template.name).desired_width(150.0));
+
+            let enabled = !template.name.trim().is_empty();
+            if ui
+                .add_enabled(enabled, Button::new(fmt!("{ICON_SAVE} Save template")))
+                .clicked()
+            {
+                self.queue.push_back(Message::SaveTemplate);
+            }
 
-fn format_species(emoji: &str, name: &str) -> Option<String> {
-    if name.is_empty() {
-        return None;
+            ui.weak("variables").on_hover_text(variables.join("\n"));
+        });
     }
 
-    Some(format!("{emoji} {name}"))
-}
-
-#[derive(Default)]
-struct Builder {
-    buf: String,
-}
+    fn hashtag_policy_view(&mut self, ui: &mut Ui, db: &Database, link: SocialMediaLink) {
+        let original = db.hashtag_policy(Some(link));
+        let mut policy = original.clone();
+
+        ui.horizontal(|ui| {
+            ui.label(fmt!("{ICON_TAG} Hashtags"));
+
+            let mut limited = policy.max_count.is_some();
+            ui.checkbox(&mut limited, "at most");
+            let mut max_count = policy.max_count.unwrap_or(INSTAGRAM_MAX_HASHTAGS);
+            ui.add_enabled(limited, DragValue::new(&mut max_count).range(0..=100));
+            policy.max_count = limited.then_some(max_count);
+
+            ComboBox::from_id_salt(fmt!("{ID_PREFIX}-hashtag-order"))
+                .selected_text(policy.order.name())
+                .show_ui(ui, |ui| {
+                    for order in HashtagOrder::ALL {
+                        ui.selectable_value(&mut policy.order, order, order.name());
+                    }
+                });
+
+            ComboBox::from_id_salt(fmt!("{ID_PREFIX}-hashtag-language"))
+                .selected_text(policy.language.name())
+                .show_ui(ui, |ui| {
+                    for language in HashtagLanguage::ALL {
+                        ui.selectable_value(&mut policy.language, language, language.name());
+                    }
+                });
+        });
 
-impl Builder {
-    fn writeln(&mut self, s: String) {
-        self.buf.push_str(&s);
-        self.buf.push('\n');
+        if policy != original {
+            self.queue
+                .push_back(Message::SetHashtagPolicy(link, policy));
+        }
     }
 
-    fn write(&mut self, s: String) {
-        self.buf.push_str(&s);
+    pub fn try_close(&mut self) {
+        self.queue.push_back(Message::Cancel);
     }
+}
 
-    fn put_char(&mut self, c: char) {
-        self.buf.push(c);
+/// Things worth checking before publishing; `false` marks a problem.
+fn checklist(post: &Post, db: &Database) -> Vec<(bool, String)> {
+    let mut result = Vec::new();
+
+    let default = db.default_alt_text(post);
+    for (i, file) in post.files.iter().enumerate() {
+        let n = i + 1;
+        let item = if !file.alt_pl.is_empty() || !file.alt_en.is_empty() {
+            (true, format!("photo #{n} has alt text"))
+        } else if default.en.is_empty() && default.pl.is_empty() {
+            (false, format!("photo #{n} has no alt text"))
+        } else {
+            let text = db.alt_text(post, i);
+            (
+                false,
+                format!("photo #{n} has no alt text, generated: {text}"),
+            )
+        };
+        result.push(item);
     }
 
-    fn newline(&mut self) {
-        self.buf.push('\n');
-    }
+    let description = match (post.pl.is_empty(), post.en.is_empty()) {
+        (false, false) => (true, "description in both languages".to_owned()),
+        (true, true) => (false, "no description".to_owned()),
+        (true, false) => (false, "no Polish description".to_owned()),
+        (false, true) => (false, "no English description".to_owned()),
+    };
+    result.push(description);
 
-    fn is_empty(&self) -> bool {
-        self.buf.is_empty()
+    if post.species.is_none() {
+        result.push((false, "species not set".to_owned()));
     }
+
+    result
 }
@@ -35,16 +35,19 @@ use egui::Ui;
 use species::RecentSpecies;
 use std::collections::VecDeque;
 
+use egui_material_icons::icons::ICON_CLOSE;
 use egui_material_icons::icons::ICON_MAGNIFICATION_LARGE;
 use egui_material_icons::icons::ICON_MAGNIFICATION_SMALL;
+use egui_material_icons::icons::ICON_STAR;
 use egui_material_icons::icons::ICON_WARNING;
 
 const ID_PREFIX: &str = "modal-post-species";
 
 pub struct ModalSpecies {
     id: PostId,
-    new: Option<Latin>,
-    original: Option<Latin>,
+    /// Selected species, the primary one first
+    new: Vec<Latin>,
+    original: Vec<Latin>,
     recent_species: RecentSpecies,
     recent_species_version: u64,
     search_box: SearchBox,
@@ -86,6 +89,10 @@ pub enum Message {
     SpeciesHovered(Option<SpeciesId>),
     SpeciesViewAction(SpeciesViewAction, SpeciesId),
     SetSpecies(SpeciesId),
+    /// Adds or removes the species, keeping the others
+    ToggleSpecies(SpeciesId),
+    MakePrimary(usize),
+    RemoveSpecies(usize),
     FilterByName(String),
     UnsetSpecies,
     SoftClose,
@@ -102,6 +109,9 @@ impl Message {
             Self::SpeciesHovered(_) => unreachable!(),
             Self::SpeciesViewAction { .. } => unreachable!(),
             Self::SetSpecies(_) => unreachable!(),
+            Self::ToggleSpecies(_) => unreachable!(),
+            Self::MakePrimary(_) => unreachable!(),
+            Self::RemoveSpecies(_) => unreachable!(),
             Self::FilterByName(_) => unreachable!(),
             Self::UnsetSpecies => unreachable!(),
             Self::SoftClose => help::SOFT_CLOSE,
@@ -122,7 +132,7 @@ impl From<Message> for TabMessage {
 impl ModalSpecies {
     pub fn new(id: PostId, db: &Database) -> Self {
         let post = db.post(&id);
-        let original = post.species.clone();
+        let original = post.species_list();
         let new = original.clone();
         let recent_species = RecentSpecies::new(id, db);
         let recent_species_version = db.current_version.species;
@@ -242,11 +252,32 @@ impl ModalSpecies {
             }
             Message::SetSpecies(id) => {
                 if let Some(species) = db.species_by_id(&id) {
-                    self.new = Some(species.latin.clone());
+                    self.new = vec![species.latin.clone()];
+                }
+            }
+            Message::ToggleSpecies(id) => {
+                if let Some(species) = db.species_by_id(&id) {
+                    match self.new.iter().position(|latin| *latin == species.latin) {
+                        Some(index) => {
+                            self.new.remove(index);
+                        }
+                        None => self.new.push(species.latin.clone()),
+                    }
+                }
+            }
+            Message::MakePrimary(index) => {
+                if index < self.new.len() {
+                    let latin = self.new.remove(index);
+                    self.new.insert(0, latin);
+                }
+            }
+            Message::RemoveSpecies(index) => {
+                if index < self.new.len() {
+                    self.new.remove(index);
                 }
             }
             Message::UnsetSpecies => {
-                self.new = None;
+                self.new.clear();
             }
             Message::SoftClose => {
                 if self.is_modified() {
@@ -275,7 +306,7 @@ impl ModalSpecies {
             Message::SaveAndExit => {
                 tab_queue.push_back(TabMessage::CloseModal);
                 if self.is_modified() {
-                    let msg = EditDetails::SetSpecies(self.id, self.new.clone());
+                    let msg = EditDetails::SetSpeciesList(self.id, self.new.clone());
                     tab_queue.push_back(msg.into());
                 }
             }
@@ -403,19 +434,24 @@ impl ModalSpecies {
         tab_queue: &mut TabMessageQueue,
         queue: &mut MessageQueue,
     ) {
-        let Some(selected_species) = &self.new else {
+        if self.new.is_empty() {
             ui.label("no species selected");
+            ui.weak("Ctrl+click selects several species");
             return;
-        };
+        }
 
-        if let Some(species) = db.species_by_latin(selected_species) {
-            ui.horizontal(|ui| {
-                if ui.button("🗙 Clear").clicked() {
-                    queue.push_back(Message::UnsetSpecies);
-                }
+        ui.horizontal(|ui| {
+            if ui.button("🗙 Clear").clicked() {
+                queue.push_back(Message::UnsetSpecies);
+            }
 
-                ui.heading("Selected species")
-            });
+            ui.heading("Selected species")
+        });
+
+        for (index, latin) in self.new.iter().enumerate() {
+            let Some(species) = db.species_by_latin(latin) else {
+                continue;
+            };
 
             Frame::new()
                 .inner_margin(5.0)
@@ -425,6 +461,23 @@ impl ModalSpecies {
                     ui.set_min_width(ui.available_width());
                     ui.horizontal(|ui| {
                         crate::species_view::format_latin(ui, species);
+
+                        ui.with_layout(Layout::right_to_left(egui::Align::Min), |ui| {
+                            if ui.small_button(ICON_CLOSE).clicked() {
+                                queue.push_back(Message::RemoveSpecies(index));
+                            }
+
+                            if index == 0 {
+                                ui.label(fmt!("{ICON_STAR} primary"))
+                                    .on_hover_text("used in captions");
+                            } else if ui
+                                .small_button(ICON_STAR)
+                                .on_hover_text("make primary, used in captions")
+                                .clicked()
+                            {
+                                queue.push_back(Message::MakePrimary(index));
+                            }
+                        });
                     });
 
                     ui.horizontal(|ui| {
@@ -435,17 +488,19 @@ impl ModalSpecies {
                         crate::species_view::format_en(ui, species);
                     });
                 });
+        }
 
-            if post.is_example {
-                if ui.button("🗙 Not a good example").clicked() {
-                    let msg = EditDetails::Example(self.id, false);
-                    tab_queue.push_back(msg.into());
-                }
-            } else {
-                if ui.button("✔ Set as an example").clicked() {
-                    let msg = EditDetails::Example(self.id, true);
-                    tab_queue.push_back(msg.into());
-                }
+        ui.weak("Ctrl+click adds or removes a species");
+
+        if post.is_example {
+            if ui.button("🗙 Not a good example").clicked() {
+                let msg = EditDetails::Example(self.id, false);
+                tab_queue.push_back(msg.into());
+            }
+        } else {
+            if ui.button("✔ Set as an example").clicked() {
+                let msg = EditDetails::Example(self.id, true);
+                tab_queue.push_back(msg.into());
             }
         }
     }
@@ -498,7 +553,11 @@ impl ModalSpecies {
                 }
             }
             if let Some(clicked) = resp.clicked {
-                queue.push_back(Message::SetSpecies(clicked));
+                if ui.input(|input| input.modifiers.command) {
+                    queue.push_back(Message::ToggleSpecies(clicked));
+                } else {
+                    queue.push_back(Message::SetSpecies(clicked));
+                }
             }
             if let Some((action, id)) = resp.species_view_action {
                 queue.push_back(Message::SpeciesViewAction(action, id));
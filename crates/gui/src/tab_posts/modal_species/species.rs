@@ -26,18 +26,18 @@ impl RecentSpecies {
         }
 
         for item in db.posts.iter() {
-            let Some(latin) = &item.species else {
-                continue;
-            };
+            for latin in item.all_species() {
+                let Some(species) = db.species_by_latin(latin) else {
+                    continue;
+                };
 
-            if match_day(&this.date, &item.date) {
-                let species = db.species_by_latin(latin).unwrap();
-                day.insert(species.latin.clone(), species.id);
-            }
+                if match_day(&this.date, &item.date) {
+                    day.insert(species.latin.clone(), species.id);
+                }
 
-            if match_month(&this.date, &item.date) {
-                let species = db.species_by_latin(latin).unwrap();
-                month.insert(species.latin.clone(), species.id);
+                if match_month(&this.date, &item.date) {
+                    month.insert(species.latin.clone(), species.id);
+                }
             }
         }
 
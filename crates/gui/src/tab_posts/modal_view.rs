@@ -3,24 +3,32 @@ mod cursor;
 use cursor::Cursor;
 
 use crate::application::Message as MainMessage;
+use crate::gui::icon_en;
+use crate::gui::icon_pl;
 use crate::keyboard::KeyboardMapping;
 use crate::tab_posts::ImageCache;
 use crate::tab_posts::Message as TabMessage;
 use crate::tab_posts::MessageQueue as TabMessageQueue;
+use db::edit_details::EditDetails;
 use db::Database;
 use db::PostId;
 use egui::Align;
 use egui::CentralPanel;
+use egui::Checkbox;
 use egui::Context;
 use egui::Image;
 use egui::Key;
 use egui::Layout;
+use egui::TextEdit;
+use egui::TopBottomPanel;
 use std::collections::VecDeque;
 
 pub struct ModalView {
     post_id: PostId,
     cursor: Cursor,
     initialized: bool,
+    /// Polish and English alt text of each photo, as edited
+    alt: Vec<(String, String)>,
 
     pub queue: MessageQueue,
     pub keyboard_mapping: KeyboardMapping,
@@ -76,6 +84,11 @@ impl ModalView {
             queue: MessageQueue::new(),
             post_id: id,
             cursor: Cursor::new(post.files.len()),
+            alt: post
+                .files
+                .iter()
+                .map(|file| (file.alt_pl.clone(), file.alt_en.clone()))
+                .collect(),
             keyboard_mapping: Self::create_mapping(post.files.len()),
         }
     }
@@ -201,6 +214,46 @@ impl ModalView {
 
         let n = post.files.len();
 
+        TopBottomPanel::bottom("modal-view-alt-text").show(ctx, |ui| {
+            let file = &post.files[current];
+            let default = db.default_alt_text(post);
+            let (pl, en) = &mut self.alt[current];
+
+            let mut changed = false;
+            ui.horizontal(|ui| {
+                let width = (ui.available_width() - 40.0) / 2.0;
+
+                icon_pl(ui);
+                let edit = TextEdit::singleline(pl)
+                    .hint_text(default.pl.as_str())
+                    .desired_width(width);
+                changed |= ui.add(edit).lost_focus();
+
+                icon_en(ui);
+                let edit = TextEdit::singleline(en)
+                    .hint_text(default.en.as_str())
+                    .desired_width(width);
+                changed |= ui.add(edit).lost_focus();
+            });
+
+            if changed && (*pl != file.alt_pl || *en != file.alt_en) {
+                let action = EditDetails::SetAltText(self.post_id, current, pl.clone(), en.clone());
+                tab_queue.push_back(action.into());
+            }
+
+            if post.species.is_some() {
+                let mut flag = post.is_example || file.is_example;
+                let checkbox = Checkbox::new(&mut flag, "example of the species");
+                let resp = ui
+                    .add_enabled(!post.is_example, checkbox)
+                    .on_disabled_hover_text("all photos of the post are examples");
+                if resp.changed() {
+                    let action = EditDetails::FileExample(self.post_id, current, flag);
+                    tab_queue.push_back(action.into());
+                }
+            }
+        });
+
         CentralPanel::default().show(ctx, |ui| {
             if n == 1 {
                 ui.centered_and_justified(|ui| {
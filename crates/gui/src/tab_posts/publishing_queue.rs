@@ -0,0 +1,76 @@
+use super::Message;
+use super::MessageQueue;
+use crate::file_stem;
+use db::schedule::format_date_time;
+use db::Database;
+use egui::Id;
+use egui::RichText;
+use egui::ScrollArea;
+use egui::Ui;
+
+use egui_material_icons::icons::ICON_DRAG_INDICATOR;
+use egui_material_icons::icons::ICON_SCHEDULE;
+
+const OVERDUE: egui::Color32 = crate::colors::DARK_ORANGE;
+
+/// Side panel listing scheduled posts ordered by their slots; dragging
+/// a post onto another one moves it into that slot.
+pub fn view(ui: &mut Ui, db: &Database, queue: &mut MessageQueue) {
+    ui.label(RichText::new("Publishing queue").heading());
+
+    let scheduled = db.scheduled();
+    if scheduled.is_empty() {
+        ui.weak("nothing scheduled");
+        return;
+    }
+
+    let now = chrono::Local::now();
+    ScrollArea::vertical()
+        .id_salt("tab-posts-publishing-queue-scroll")
+        .show(ui, |ui| {
+            for (index, (id, schedule)) in scheduled.iter().enumerate() {
+                let post = db.post(id);
+                let name = post
+                    .files
+                    .first()
+                    .map(|file| file_stem(&file.rel_path))
+                    .unwrap_or_default();
+                let platforms: Vec<&str> =
+                    schedule.platforms.iter().map(|link| link.name()).collect();
+
+                let mut at = RichText::new(format!(
+                    "{ICON_SCHEDULE} {}",
+                    format_date_time(&schedule.at)
+                ));
+                if schedule.is_due(&now) {
+                    at = at.color(OVERDUE);
+                }
+
+                let drag_id = Id::new(("tab-posts-publishing-queue", index));
+                let resp = ui
+                    .dnd_drag_source(drag_id, index, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(ICON_DRAG_INDICATOR);
+                            ui.label(at);
+                            ui.label(name);
+                        });
+                        ui.weak(platforms.join(", "));
+                    })
+                    .response;
+
+                if let Some(from) = resp.dnd_release_payload::<usize>() {
+                    queue.push_back(Message::Reschedule(*from, index));
+                }
+
+                if resp.clicked() {
+                    queue.push_back(Message::Select(*id));
+                }
+
+                if resp.double_clicked() {
+                    queue.push_back(Message::Publish(*id));
+                }
+
+                ui.separator();
+            }
+        });
+}
@@ -0,0 +1,109 @@
+use super::filter::Filter;
+use super::Message;
+use super::MessageQueue;
+use db::Database;
+use db::Source;
+use egui::Button;
+use egui::RichText;
+use egui::ScrollArea;
+use egui::TextEdit;
+use egui::Ui;
+
+use egui_material_icons::icons::ICON_BOOKMARK;
+use egui_material_icons::icons::ICON_BOOKMARK_ADD;
+use egui_material_icons::icons::ICON_DELETE;
+use egui_material_icons::icons::ICON_EDIT;
+use egui_material_icons::icons::ICON_SEARCH;
+
+/// Side panel listing saved searches along with the number of matching posts.
+#[derive(Default)]
+pub struct SavedSearches {
+    name: String,
+}
+
+impl SavedSearches {
+    pub fn clear(&mut self) {
+        self.name.clear();
+    }
+
+    pub fn view(&mut self, ui: &mut Ui, db: &Database, filter: &Filter, queue: &mut MessageQueue) {
+        ui.label(RichText::new("Saved searches").heading());
+
+        let current = filter.source();
+        ScrollArea::vertical()
+            .id_salt("tab-posts-saved-searches-scroll")
+            .max_height(ui.available_height() - 4.0 * ui.spacing().interact_size.y)
+            .show(ui, |ui| {
+                if db.saved_searches.is_empty() {
+                    ui.weak("no saved searches");
+                }
+
+                for search in db.saved_searches.iter() {
+                    let source = Source::SavedSearch(search.name.clone());
+                    let selected = source == current;
+                    let label = format!(
+                        "{ICON_BOOKMARK} {} ({})",
+                        search.name,
+                        filter.count(&source, db)
+                    );
+
+                    let resp = ui.selectable_label(selected, label);
+                    if resp.clicked() {
+                        let name = (!selected).then(|| search.name.clone());
+                        queue.push_back(Message::SelectSavedSearch(name));
+                    }
+
+                    let resp = resp.on_hover_text(&search.query);
+                    resp.context_menu(|ui| {
+                        if ui.button(format!("{ICON_SEARCH} Edit query")).clicked() {
+                            queue.push_back(Message::LoadSavedSearch(search.name.clone()));
+                        }
+
+                        let enabled = filter.is_phrase_valid() && !filter.phrase().is_empty();
+                        let button = Button::new(format!("{ICON_EDIT} Replace with current query"));
+                        if ui.add_enabled(enabled, button).clicked() {
+                            queue.push_back(Message::UpdateSavedSearch(search.name.clone()));
+                        }
+
+                        if ui.button(format!("{ICON_DELETE} Delete")).clicked() {
+                            queue.push_back(Message::DeleteSavedSearch(search.name.clone()));
+                        }
+                    });
+                }
+            });
+
+        ui.separator();
+
+        ui.add(TextEdit::singleline(&mut self.name).hint_text("name"));
+
+        let validation = self.validate(db, filter);
+        let button = Button::new(format!("{ICON_BOOKMARK_ADD} Save current search"));
+        let resp = ui.add_enabled(validation.is_ok(), button);
+        if let Err(msg) = validation {
+            resp.on_disabled_hover_text(msg);
+        } else if resp.clicked() {
+            queue.push_back(Message::SaveSearch(self.name.trim().to_owned()));
+        }
+    }
+
+    fn validate(&self, db: &Database, filter: &Filter) -> Result<(), &'static str> {
+        if filter.phrase().is_empty() {
+            return Err("search phrase is empty");
+        }
+
+        if !filter.is_phrase_valid() {
+            return Err("search phrase is not a valid query");
+        }
+
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err("name cannot be empty");
+        }
+
+        if db.saved_searches.get(name).is_some() {
+            return Err("saved search with this name already exists");
+        }
+
+        Ok(())
+    }
+}
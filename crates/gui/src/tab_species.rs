@@ -1,7 +1,13 @@
 mod modal_edit;
+mod modal_import;
+mod modal_stats;
 
 use modal_edit::Message as ModalEditMessage;
 use modal_edit::ModalEdit;
+use modal_import::Message as ModalImportMessage;
+use modal_import::ModalImport;
+use modal_stats::Message as ModalStatsMessage;
+use modal_stats::ModalStats;
 
 use crate::application::Message as MainMessage;
 use crate::application::MessageQueue as MainMessageQueue;
@@ -14,25 +20,41 @@ use crate::species_view::SortOrder;
 use crate::species_view::SpeciesList;
 use crate::species_view::SpeciesViewAction;
 use crate::style::Style;
+use crate::tab_posts::Message as TabPostsMessage;
 use const_format::formatcp as fmt;
 use db::Database;
+use db::Rank;
 use db::SpeciesId;
+use db::TaxonNode;
+use egui::collapsing_header::CollapsingState;
 use egui::CentralPanel;
 use egui::Context;
+use egui::Id;
 use egui::Key;
 use egui::ScrollArea;
+use egui::SidePanel;
 use egui::Ui;
 use std::collections::VecDeque;
 
 use egui_material_icons::icons::ICON_ARROW_DOWNWARD;
 use egui_material_icons::icons::ICON_ARROW_UPWARD;
 use egui_material_icons::icons::ICON_FORMAT_LIST_NUMBERED;
+use egui_material_icons::icons::ICON_PHOTO_LIBRARY;
+use egui_material_icons::icons::ICON_QUERY_STATS;
+use egui_material_icons::icons::ICON_SORT;
 use egui_material_icons::icons::ICON_SORT_BY_ALPHA;
+use egui_material_icons::icons::ICON_UNDO;
+use egui_material_icons::icons::ICON_UPLOAD_FILE;
+
+const ID_PREFIX: &str = "tab-species";
 
 pub struct TabSpecies {
     list: SpeciesList,
     search_box: SearchBox,
     modal_window: ModalWindow,
+    taxa: Vec<TaxonNode>,
+    /// Versions of species and posts the taxa were counted for
+    taxa_version: Option<(u64, u64)>,
 
     pub queue: MessageQueue,
     pub keyboard_mapping: KeyboardMapping,
@@ -43,6 +65,8 @@ pub type MessageQueue = VecDeque<Message>;
 pub enum ModalWindow {
     None,
     ModalEdit(Box<ModalEdit>),
+    ModalImport(Box<ModalImport>),
+    ModalStats(Box<ModalStats>),
 }
 
 impl ModalWindow {
@@ -54,11 +78,18 @@ impl ModalWindow {
 #[derive(Clone)]
 pub enum Message {
     ModalEdit(ModalEditMessage),
+    ModalImport(ModalImportMessage),
+    ModalStats(ModalStatsMessage),
     OpenModalEdit(SpeciesId),
     AddNew,
+    Import,
+    Statistics,
+    UndoRename,
     EditCurrent,
     Edit(SpeciesId),
     FilterByName(String),
+    SelectTaxon(Option<(Rank, String)>),
+    ShowPosts(Rank, String),
     SortBy(SortOrder),
     RefreshView,
     Hovered(Option<SpeciesId>),
@@ -74,11 +105,18 @@ impl Message {
     pub const fn name(&self) -> &str {
         match self {
             Self::ModalEdit(msg) => msg.name(),
+            Self::ModalImport(msg) => msg.name(),
+            Self::ModalStats(msg) => msg.name(),
             Self::OpenModalEdit(_) => unreachable!(),
             Self::AddNew => "add new species",
+            Self::Import => "import species checklist",
+            Self::Statistics => "show the life list and statistics",
+            Self::UndoRename => "undo the last rename of a species",
             Self::EditCurrent => "edit highlighted species",
             Self::Edit(_) => unreachable!(),
             Self::FilterByName(_) => unreachable!(),
+            Self::SelectTaxon(_) => unreachable!(),
+            Self::ShowPosts(..) => unreachable!(),
             Self::SortBy(_) => unreachable!(),
             Self::RefreshView => unreachable!(),
             Self::Hovered(_) => unreachable!(),
@@ -103,9 +141,11 @@ impl Default for TabSpecies {
         let mut res = Self {
             list: SpeciesList::default().with_width(300.0),
             queue: MessageQueue::new(),
-            search_box: SearchBox::new("tab-species-search"),
+            search_box: SearchBox::new(fmt!("{ID_PREFIX}-search")),
             keyboard_mapping: Self::create_mapping(),
             modal_window: ModalWindow::None,
+            taxa: Vec::new(),
+            taxa_version: None,
         };
 
         res.queue.push_back(Message::RefreshView);
@@ -125,6 +165,12 @@ impl TabSpecies {
     ) {
         self.list.image_width = style.image.preview_width;
 
+        let version = (db.current_version.species, db.current_version.posts);
+        if self.taxa_version != Some(version) {
+            self.taxa = db.taxonomy_tree();
+            self.taxa_version = Some(version);
+        }
+
         while let Some(msg) = self.queue.pop_front() {
             self.handle_message(ctx, db, msg, main_queue);
         }
@@ -135,6 +181,12 @@ impl TabSpecies {
             ModalWindow::ModalEdit(window) => {
                 window.update(ctx, image_cache, style, db, &mut queue);
             }
+            ModalWindow::ModalImport(window) => {
+                window.update(ctx, style, db, &mut queue);
+            }
+            ModalWindow::ModalStats(window) => {
+                window.update(ctx, style, db, &mut queue);
+            }
         }
 
         while let Some(msg) = queue.pop_front() {
@@ -150,6 +202,9 @@ impl TabSpecies {
             .key(Key::E, Message::EditCurrent.into())
             .ctrl(Key::E, Message::EditCurrent.into())
             .ctrl(Key::N, Message::AddNew.into())
+            .ctrl(Key::I, Message::Import.into())
+            .ctrl(Key::L, Message::Statistics.into())
+            .ctrl(Key::Z, Message::UndoRename.into())
             .key(Key::ArrowRight, Message::SelectPrevExample.into())
             .key(Key::ArrowLeft, Message::SelectNextExample.into())
     }
@@ -162,6 +217,8 @@ impl TabSpecies {
         match &mut self.modal_window {
             ModalWindow::None => (),
             ModalWindow::ModalEdit(window) => window.try_close(),
+            ModalWindow::ModalImport(window) => window.try_close(),
+            ModalWindow::ModalStats(window) => window.try_close(),
         }
     }
 
@@ -169,6 +226,8 @@ impl TabSpecies {
         match &self.modal_window {
             ModalWindow::None => &self.keyboard_mapping,
             ModalWindow::ModalEdit(window) => &window.keyboard_mapping,
+            ModalWindow::ModalImport(window) => &window.keyboard_mapping,
+            ModalWindow::ModalStats(window) => &window.keyboard_mapping,
         }
     }
 
@@ -186,6 +245,14 @@ impl TabSpecies {
             Message::FilterByName(phrase) => {
                 self.list.set_filter(phrase, db);
             }
+            Message::SelectTaxon(taxon) => {
+                self.list.set_taxon(taxon, db);
+            }
+            Message::ShowPosts(rank, name) => {
+                let query = format!("{}:\"{name}\"", rank.name());
+                main_queue.push_back(TabPostsMessage::Search(query).into());
+                main_queue.push_back(MainMessage::SelectTabPosts);
+            }
             Message::SortBy(sort_order) => {
                 self.list.set_sort_order(sort_order, db);
             }
@@ -194,6 +261,22 @@ impl TabSpecies {
                 let window = ModalEdit::new();
                 self.modal_window = ModalWindow::ModalEdit(Box::new(window));
             }
+            Message::Import => {
+                assert!(self.modal_window.is_none());
+                let window = ModalImport::new();
+                self.modal_window = ModalWindow::ModalImport(Box::new(window));
+            }
+            Message::Statistics => {
+                assert!(self.modal_window.is_none());
+                let window = ModalStats::new(db);
+                self.modal_window = ModalWindow::ModalStats(Box::new(window));
+            }
+            Message::UndoRename => {
+                if let Some(rename) = db.undo_rename() {
+                    log::info!("renamed '{}' back to '{}'", rename.to, rename.from);
+                    self.queue.push_back(Message::RefreshView);
+                }
+            }
             Message::EditCurrent => {
                 if let Some(id) = self.list.hovered {
                     self.queue.push_back(Message::OpenModalEdit(id));
@@ -210,6 +293,16 @@ impl TabSpecies {
                     window.queue.push_back(msg);
                 }
             }
+            Message::ModalImport(msg) => {
+                if let ModalWindow::ModalImport(window) = &mut self.modal_window {
+                    window.queue.push_back(msg);
+                }
+            }
+            Message::ModalStats(msg) => {
+                if let ModalWindow::ModalStats(window) = &mut self.modal_window {
+                    window.queue.push_back(msg);
+                }
+            }
             Message::OpenModalEdit(id) => {
                 assert!(self.modal_window.is_none());
                 let window = ModalEdit::edit(id, db);
@@ -258,11 +351,74 @@ impl TabSpecies {
         db: &Database,
         queue: &mut MessageQueue,
     ) {
+        if !self.taxa.is_empty() {
+            SidePanel::left(fmt!("{ID_PREFIX}-taxa"))
+                .resizable(true)
+                .show(ctx, |ui| {
+                    ScrollArea::vertical()
+                        .id_salt(fmt!("{ID_PREFIX}-taxa-scroll"))
+                        .show(ui, |ui| {
+                            self.draw_taxa(ui, db, queue);
+                        });
+                });
+        }
+
         CentralPanel::default().show(ctx, |ui| {
             self.draw_main(ui, image_cache, style, db, queue);
         });
     }
 
+    fn draw_taxa(&self, ui: &mut Ui, db: &Database, queue: &mut MessageQueue) {
+        let selected = self.list.taxon().is_none();
+        let label = format!("All species ({})", db.species.len());
+        if ui.selectable_label(selected, label).clicked() {
+            queue.push_back(Message::SelectTaxon(None));
+        }
+
+        for node in &self.taxa {
+            self.draw_taxon(ui, node, queue);
+        }
+    }
+
+    fn draw_taxon(&self, ui: &mut Ui, node: &TaxonNode, queue: &mut MessageQueue) {
+        let selected = self
+            .list
+            .taxon()
+            .is_some_and(|(rank, name)| *rank == node.rank && *name == node.name);
+
+        let mut header = |ui: &mut Ui| {
+            let label = format!("{} ({}, {} posts)", node.name, node.species, node.posts);
+            let resp = ui
+                .selectable_label(selected, label)
+                .on_hover_text(node.rank.name());
+            if resp.clicked() {
+                let taxon = (!selected).then(|| (node.rank, node.name.clone()));
+                queue.push_back(Message::SelectTaxon(taxon));
+            }
+
+            if node.posts > 0 && ui.small_button(ICON_PHOTO_LIBRARY).clicked() {
+                queue.push_back(Message::ShowPosts(node.rank, node.name.clone()));
+            }
+        };
+
+        if node.children.is_empty() {
+            ui.horizontal(|ui| {
+                ui.add_space(ui.spacing().indent);
+                header(ui);
+            });
+            return;
+        }
+
+        let id = Id::new(ID_PREFIX).with(node.rank).with(&node.name);
+        CollapsingState::load_with_default_open(ui.ctx(), id, false)
+            .show_header(ui, header)
+            .body(|ui| {
+                for child in &node.children {
+                    self.draw_taxon(ui, child, queue);
+                }
+            });
+    }
+
     pub fn draw_main(
         &self,
         ui: &mut Ui,
@@ -282,6 +438,22 @@ impl TabSpecies {
                 queue.push_back(Message::AddNew);
             }
 
+            if ui.button(fmt!("{ICON_UPLOAD_FILE} Import")).clicked() {
+                queue.push_back(Message::Import);
+            }
+
+            if ui.button(fmt!("{ICON_QUERY_STATS} Statistics")).clicked() {
+                queue.push_back(Message::Statistics);
+            }
+
+            if db.can_undo_rename() {
+                ui.separator();
+
+                if ui.button(fmt!("{ICON_UNDO} Undo rename")).clicked() {
+                    queue.push_back(Message::UndoRename);
+                }
+            }
+
             ui.separator();
 
             if ui.button(sort_order_label(&self.list.sort_order)).clicked() {
@@ -293,7 +465,7 @@ impl TabSpecies {
 
         ScrollArea::vertical()
             .auto_shrink(false)
-            .id_salt("tab-species-scroll")
+            .id_salt(fmt!("{ID_PREFIX}-scroll"))
             .show(ui, |ui| {
                 ui.vertical(|ui| {
                     let resp = self.list.render(ui, image_cache, style, db);
@@ -320,7 +492,8 @@ const fn next_sort_order(sort_order: &SortOrder) -> SortOrder {
         SortOrder::PolishAsc => SortOrder::PolishDesc,
         SortOrder::PolishDesc => SortOrder::EnglishAsc,
         SortOrder::EnglishAsc => SortOrder::EnglishDesc,
-        SortOrder::EnglishDesc => SortOrder::DateAddedAsc,
+        SortOrder::EnglishDesc => SortOrder::Relevance,
+        SortOrder::Relevance => SortOrder::DateAddedAsc,
     }
 }
 
@@ -336,5 +509,6 @@ const fn sort_order_label(sort_order: &SortOrder) -> &str {
         SortOrder::PolishDesc => fmt!("{ICON_SORT_BY_ALPHA}{ICON_ARROW_UPWARD} Polish"),
         SortOrder::EnglishAsc => fmt!("{ICON_SORT_BY_ALPHA}{ICON_ARROW_DOWNWARD} English"),
         SortOrder::EnglishDesc => fmt!("{ICON_SORT_BY_ALPHA}{ICON_ARROW_UPWARD} English"),
+        SortOrder::Relevance => fmt!("{ICON_SORT} relevance"),
     }
 }
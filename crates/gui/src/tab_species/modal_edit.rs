@@ -13,11 +13,16 @@ use crate::tab_species::Message as TabMessage;
 use crate::tab_species::MessageQueue as TabMessageQueue;
 use const_format::formatcp as fmt;
 use db::Database;
+use db::FileMetadata;
 use db::Latin;
+use db::Rank;
+use db::Rename;
 use db::Species;
 use db::SpeciesId;
 use egui::Align;
+use egui::Button;
 use egui::CentralPanel;
+use egui::ComboBox;
 use egui::Context;
 use egui::Grid;
 use egui::Key;
@@ -28,17 +33,35 @@ use egui::TopBottomPanel;
 use egui::Ui;
 use std::collections::BTreeSet;
 use std::collections::VecDeque;
+use std::path::PathBuf;
 
+use egui_material_icons::icons::ICON_ARROW_DOWNWARD;
+use egui_material_icons::icons::ICON_ARROW_UPWARD;
 use egui_material_icons::icons::ICON_CONTENT_PASTE;
+use egui_material_icons::icons::ICON_DELETE;
+use egui_material_icons::icons::ICON_MERGE;
+use egui_material_icons::icons::ICON_PUSH_PIN;
 use egui_material_icons::icons::ICON_WARNING;
 
 const ID_PREFIX: &str = "modal-edit-species";
+const NO_TARGET: &str = "(other species)";
 
 pub struct ModalEdit {
     original: Option<Species>,
     new: Species,
     can_save: Result<bool, String>,
+    /// Set when the Latin name of an existing species got changed
+    rename: Option<Rename>,
+    /// Synonyms as typed, separated with commas
+    synonyms: String,
+    /// Posts referring to the edited species
+    usage: usize,
+    /// Other species to merge with or to move the posts to
+    targets: Vec<(SpeciesId, String)>,
+    target: Option<SpeciesId>,
     categories: BTreeSet<String>,
+    /// Examples in the order being edited
+    examples: Vec<FileMetadata>,
 
     pub queue: MessageQueue,
     pub keyboard_mapping: KeyboardMapping,
@@ -50,14 +73,24 @@ type MessageQueue = VecDeque<Message>;
 pub enum Message {
     SoftClose,
     SaveAndExit,
+    RenameAndExit,
     CancelAndExit,
+    Delete,
+    DeleteAndExit,
+    Merge,
+    MergeAndExit,
+    SelectTarget(Option<SpeciesId>),
     ChangeLatin(Latin),
+    ChangeSynonyms(String),
     ChangePolish(String),
     ChangeEnglish(String),
     ChangeInsektariumPl(String),
     ChangeWikipediaPl(String),
     ChangeWikipediaEn(String),
     ChangeCategory(Option<String>),
+    ChangeRank(Rank, String),
+    SetCover(Option<PathBuf>),
+    MoveExample(usize, isize),
 }
 
 impl Message {
@@ -65,14 +98,24 @@ impl Message {
         match self {
             Self::SoftClose => help::SOFT_CLOSE,
             Self::SaveAndExit => help::SAVE_AND_EXIT,
+            Self::RenameAndExit => unreachable!(),
             Self::CancelAndExit => unreachable!(),
+            Self::Delete => unreachable!(),
+            Self::DeleteAndExit => unreachable!(),
+            Self::Merge => unreachable!(),
+            Self::MergeAndExit => unreachable!(),
+            Self::SelectTarget(_) => unreachable!(),
             Self::ChangeLatin(_) => unreachable!(),
+            Self::ChangeSynonyms(_) => unreachable!(),
             Self::ChangePolish(_) => unreachable!(),
             Self::ChangeEnglish(_) => unreachable!(),
             Self::ChangeWikipediaPl(_) => unreachable!(),
             Self::ChangeInsektariumPl(_) => unreachable!(),
             Self::ChangeWikipediaEn(_) => unreachable!(),
             Self::ChangeCategory(_) => unreachable!(),
+            Self::ChangeRank(..) => unreachable!(),
+            Self::SetCover(_) => unreachable!(),
+            Self::MoveExample(..) => unreachable!(),
         }
     }
 }
@@ -87,10 +130,16 @@ impl ModalEdit {
     pub fn new() -> Self {
         Self {
             can_save: Ok(false),
+            rename: None,
+            synonyms: String::new(),
+            usage: 0,
+            targets: Vec::new(),
+            target: None,
             original: None,
             new: Species::default(),
             queue: MessageQueue::new(),
             categories: BTreeSet::new(),
+            examples: Vec::new(),
             keyboard_mapping: Self::create_mapping(),
         }
     }
@@ -99,8 +148,23 @@ impl ModalEdit {
         let original = db.species_by_id(&id).unwrap().clone();
         let new = original.clone();
 
+        let synonyms: Vec<&str> = original.synonyms.iter().map(Latin::as_str).collect();
+        let mut targets: Vec<(SpeciesId, String)> = db
+            .species
+            .iter()
+            .filter(|species| species.id != id)
+            .map(|species| (species.id, format!("{} ({})", species.latin, species.pl)))
+            .collect();
+        targets.sort_by(|a, b| a.1.cmp(&b.1));
+
         Self {
             can_save: Ok(false),
+            rename: None,
+            synonyms: synonyms.join(", "),
+            usage: db.posts_of_species(&original.latin).len(),
+            targets,
+            target: None,
+            examples: original.examples.clone(),
             original: Some(original),
             new,
             queue: MessageQueue::new(),
@@ -179,14 +243,98 @@ impl ModalEdit {
                 tab_queue.push_back(TabMessage::CloseModal);
             }
             Message::SaveAndExit => {
-                if self.original.is_some() {
+                if let Some(rename) = &self.rename {
+                    let confirm = confirm_rename(rename, db, style);
+                    tab_queue.push_back(TabMessage::Confirm(confirm));
+                    return;
+                }
+
+                if let Some(original) = &self.original {
                     db.update_species(&self.new);
+
+                    // a new cover is shown right away
+                    let cover = &self.new.example_order.cover;
+                    if cover.is_some() && *cover != original.example_order.cover {
+                        if let Some(species) = db.species_mut_by_id(&original.id) {
+                            species.selected_example.clone_from(cover);
+                        }
+                    }
                 } else {
                     db.add_species(&self.new);
                 }
                 tab_queue.push_back(TabMessage::RefreshView);
                 tab_queue.push_back(TabMessage::CloseModal);
             }
+            Message::RenameAndExit => {
+                let Some(rename) = self.rename.take() else {
+                    return;
+                };
+
+                if let Err(err) = db.rename_species(&rename) {
+                    self.can_save = Err(err);
+                    return;
+                }
+
+                // the old name became a synonym
+                if !self.new.synonyms.contains(&rename.from) {
+                    self.new.synonyms.push(rename.from.clone());
+                }
+                self.new.synonyms.retain(|synonym| *synonym != rename.to);
+
+                db.update_species(&self.new);
+                tab_queue.push_back(TabMessage::RefreshView);
+                tab_queue.push_back(TabMessage::CloseModal);
+            }
+            Message::SelectTarget(target) => {
+                self.target = target;
+            }
+            Message::Delete => {
+                if let Some(confirm) = self.confirm_delete(style) {
+                    tab_queue.push_back(TabMessage::Confirm(confirm));
+                }
+            }
+            Message::Merge => {
+                if let Some(confirm) = self.confirm_merge(style) {
+                    tab_queue.push_back(TabMessage::Confirm(confirm));
+                }
+            }
+            Message::DeleteAndExit => {
+                let Some(original) = &self.original else {
+                    return;
+                };
+
+                let reassign = self.target.filter(|_| self.usage > 0);
+                match db.delete_species(original.id, reassign) {
+                    Ok(_) => {
+                        tab_queue.push_back(TabMessage::RefreshView);
+                        tab_queue.push_back(TabMessage::CloseModal);
+                    }
+                    Err(err) => self.can_save = Err(err),
+                }
+            }
+            Message::MergeAndExit => {
+                let (Some(original), Some(target)) = (&self.original, self.target) else {
+                    return;
+                };
+
+                match db.merge_species(original.id, target) {
+                    Ok(_) => {
+                        tab_queue.push_back(TabMessage::RefreshView);
+                        tab_queue.push_back(TabMessage::CloseModal);
+                    }
+                    Err(err) => self.can_save = Err(err),
+                }
+            }
+            Message::ChangeSynonyms(text) => {
+                self.new.synonyms = text
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(|name| Latin::from(name.to_owned()))
+                    .collect();
+                self.synonyms = text;
+                self.validate(db);
+            }
             Message::ChangeLatin(text) => {
                 self.new.latin = text;
                 self.validate(db);
@@ -215,10 +363,28 @@ impl ModalEdit {
                 self.new.category = maybe_text;
                 self.validate(db);
             }
+            Message::ChangeRank(rank, text) => {
+                self.new.taxonomy.set(rank, &text);
+                self.validate(db);
+            }
+            Message::SetCover(cover) => {
+                self.new.example_order.cover = cover;
+                self.new.example_order.arrange(&mut self.examples);
+                self.validate(db);
+            }
+            Message::MoveExample(index, offset) => {
+                self.new
+                    .example_order
+                    .move_file(&self.examples, index, offset);
+                self.new.example_order.arrange(&mut self.examples);
+                self.validate(db);
+            }
         }
     }
 
     fn validate(&mut self, db: &Database) {
+        self.rename = None;
+
         if self.new.latin.is_empty() {
             self.can_save = Err("name cannot be empty".to_string());
             return;
@@ -239,6 +405,36 @@ impl ModalEdit {
             return;
         }
 
+        let id = self.original.as_ref().map(|species| species.id);
+        for synonym in &self.new.synonyms {
+            if *synonym == self.new.latin {
+                self.can_save = Err(format!("'{synonym}' is the name of this species"));
+                return;
+            }
+
+            let other = db
+                .species
+                .iter()
+                .filter(|species| Some(species.id) != id)
+                .find(|species| species.latin == *synonym || species.synonyms.contains(synonym));
+            if let Some(other) = other {
+                self.can_save = Err(format!("'{synonym}' is used by {}", other.latin));
+                return;
+            }
+        }
+
+        if let Some(original) = &self.original {
+            if original.latin != self.new.latin {
+                match db.plan_rename(original.id, self.new.latin.clone()) {
+                    Ok(rename) => self.rename = Some(rename),
+                    Err(err) => {
+                        self.can_save = Err(err);
+                        return;
+                    }
+                }
+            }
+        }
+
         self.can_save = Ok(self.is_modified());
     }
 
@@ -270,11 +466,11 @@ impl ModalEdit {
             .resizable(false)
             .min_width(style.image.preview_width)
             .show(ctx, |ui| {
-                if let Some(species) = &self.original {
+                if self.original.is_some() {
                     ScrollArea::vertical()
                         .id_salt(fmt!("{ID_PREFIX}-pictures-scroll"))
                         .show(ui, |ui| {
-                            for meta in &species.examples {
+                            for (index, meta) in self.examples.iter().enumerate() {
                                 add_image(
                                     ui,
                                     meta,
@@ -282,6 +478,7 @@ impl ModalEdit {
                                     style.image.preview_width,
                                     style.image.radius,
                                 );
+                                self.draw_example_order(ui, index, meta, queue);
                             }
                         });
                 } else {
@@ -305,6 +502,42 @@ impl ModalEdit {
         });
     }
 
+    fn draw_example_order(
+        &self,
+        ui: &mut Ui,
+        index: usize,
+        meta: &FileMetadata,
+        queue: &mut MessageQueue,
+    ) {
+        let is_cover = self.new.example_order.is_cover(&meta.rel_path);
+        ui.horizontal(|ui| {
+            let resp = ui
+                .selectable_label(is_cover, fmt!("{ICON_PUSH_PIN} cover"))
+                .on_hover_text("show this photo first");
+            if resp.clicked() {
+                let cover = (!is_cover).then(|| meta.rel_path.clone());
+                queue.push_back(Message::SetCover(cover));
+            }
+
+            let first = if self.new.example_order.cover.is_some() {
+                1
+            } else {
+                0
+            };
+            let up = Button::new(ICON_ARROW_UPWARD);
+            if ui.add_enabled(!is_cover && index > first, up).clicked() {
+                queue.push_back(Message::MoveExample(index, -1));
+            }
+            let down = Button::new(ICON_ARROW_DOWNWARD);
+            if ui
+                .add_enabled(!is_cover && index + 1 < self.examples.len(), down)
+                .clicked()
+            {
+                queue.push_back(Message::MoveExample(index, 1));
+            }
+        });
+    }
+
     fn draw_details(&self, ui: &mut Ui, queue: &mut MessageQueue) {
         Grid::new("species-details").num_columns(2).show(ui, |ui| {
             ui.label("Latin");
@@ -316,11 +549,22 @@ impl ModalEdit {
                 if let Err(msg) = &self.can_save {
                     let color = ui.visuals().error_fg_color;
                     ui.colored_label(color, msg);
+                } else if let Some(rename) = &self.rename {
+                    ui.label(format!(
+                        "{ICON_WARNING} renaming updates {} post(s)",
+                        rename.posts.len()
+                    ));
                 }
             });
 
             ui.end_row();
 
+            ui.label("synonyms");
+            if let Some(val) = edit(ui, &self.synonyms) {
+                queue.push_back(Message::ChangeSynonyms(val));
+            }
+            ui.end_row();
+
             ui.separator();
             ui.end_row();
 
@@ -412,9 +656,128 @@ impl ModalEdit {
                 }
             });
             ui.end_row();
+
+            for rank in Rank::ALL {
+                ui.label(rank.name());
+                let curr = self.new.taxonomy.get(rank).unwrap_or_default().to_owned();
+                if let Some(val) = edit(ui, &curr) {
+                    queue.push_back(Message::ChangeRank(rank, val));
+                }
+                ui.end_row();
+            }
+
+            if self.original.is_some() {
+                ui.separator();
+                ui.end_row();
+
+                self.draw_removal(ui, queue);
+                ui.end_row();
+            }
+        });
+    }
+
+    fn draw_removal(&self, ui: &mut Ui, queue: &mut MessageQueue) {
+        ui.label("used by");
+        ui.horizontal(|ui| {
+            ui.label(format!("{} post(s)", self.usage));
+            ui.separator();
+
+            let mut selected = self.target;
+            let text = match self.target {
+                Some(_) => self.target_label(),
+                None => NO_TARGET,
+            };
+            ComboBox::from_id_salt(fmt!("{ID_PREFIX}-target"))
+                .selected_text(text)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut selected, None, NO_TARGET);
+                    for (id, label) in &self.targets {
+                        ui.selectable_value(&mut selected, Some(*id), label);
+                    }
+                });
+            if selected != self.target {
+                queue.push_back(Message::SelectTarget(selected));
+            }
+
+            let can_merge = self.target.is_some();
+            if ui
+                .add_enabled(can_merge, Button::new(fmt!("{ICON_MERGE} Merge into")))
+                .on_hover_text("move posts, fill in empty fields and keep the names as synonyms")
+                .clicked()
+            {
+                queue.push_back(Message::Merge);
+            }
+
+            let can_delete = self.usage == 0 || self.target.is_some();
+            let hint = if self.usage == 0 {
+                "delete the species"
+            } else {
+                "move posts to the other species and delete this one"
+            };
+            if ui
+                .add_enabled(can_delete, Button::new(fmt!("{ICON_DELETE} Delete")))
+                .on_hover_text(hint)
+                .clicked()
+            {
+                queue.push_back(Message::Delete);
+            }
         });
     }
 
+    fn target_label(&self) -> &str {
+        self.targets
+            .iter()
+            .find(|(id, _)| Some(*id) == self.target)
+            .map_or("", |(_, label)| label.as_str())
+    }
+
+    fn confirm_delete(&self, style: &Style) -> Option<Confirm> {
+        let original = self.original.as_ref()?;
+        let text = if self.usage == 0 {
+            format!("Delete '{}'?", original.latin)
+        } else {
+            self.target?;
+            format!(
+                "Delete '{}' and move its {} post(s) to {}?",
+                original.latin,
+                self.usage,
+                self.target_label()
+            )
+        };
+
+        let msg: TabMessage = Message::DeleteAndExit.into();
+        let delete = ConfirmOption::new(fmt!("{ICON_DELETE} Delete"))
+            .with_message(msg.into())
+            .with_color(style.button.remove);
+
+        let cont = ConfirmOption::new("Continue editing").with_key(Key::Escape);
+
+        Some(Confirm::new(text, vec![delete, cont]))
+    }
+
+    fn confirm_merge(&self, style: &Style) -> Option<Confirm> {
+        let original = self.original.as_ref()?;
+        self.target?;
+
+        let text = format!(
+            "Merge '{}' into {}?\n{} post(s) will be moved, empty fields filled in \
+             and '{}' kept as a synonym. Unsaved changes of this form are dropped.",
+            original.latin,
+            self.target_label(),
+            self.usage,
+            original.latin
+        );
+
+        let msg: TabMessage = Message::MergeAndExit.into();
+        let merge = ConfirmOption::new(fmt!("{ICON_MERGE} Merge"))
+            .with_message(msg.into())
+            .with_color(style.button.save);
+
+        let cont = ConfirmOption::new("Continue editing").with_key(Key::Escape);
+
+        Some(Confirm::new(text, vec![merge, cont]))
+    }
+
     fn is_modified(&self) -> bool {
         if let Some(original) = &self.original {
             self.new != *original
@@ -436,6 +799,37 @@ impl ModalEdit {
     }
 }
 
+/// Lists posts which would get the new name before renaming.
+fn confirm_rename(rename: &Rename, db: &Database, style: &Style) -> Confirm {
+    const MAX_LISTED: usize = 10;
+
+    let mut text = format!(
+        "Renaming '{}' to '{}' updates {} post(s); the old name is kept as a synonym.",
+        rename.from,
+        rename.to,
+        rename.posts.len()
+    );
+    for id in rename.posts.iter().take(MAX_LISTED) {
+        let post = db.post(id);
+        match post.files.first() {
+            Some(file) => text.push_str(&format!("\n{} {}", post.date, file.rel_path.display())),
+            None => text.push_str(&format!("\n{}", post.date)),
+        }
+    }
+    if rename.posts.len() > MAX_LISTED {
+        text.push_str(&format!("\n… and {} more", rename.posts.len() - MAX_LISTED));
+    }
+
+    let msg: TabMessage = Message::RenameAndExit.into();
+    let rename = ConfirmOption::new("Rename and save")
+        .with_message(msg.into())
+        .with_color(style.button.save);
+
+    let cont = ConfirmOption::new("Continue editing").with_key(Key::Escape);
+
+    Confirm::new(text, vec![rename, cont])
+}
+
 fn edit(ui: &mut Ui, curr: &String) -> Option<String> {
     let mut val = curr.clone();
     let resp = ui.text_edit_singleline(&mut val);
@@ -0,0 +1,425 @@
+use crate::application::Message as MainMessage;
+use crate::confirm::Confirm;
+use crate::confirm::ConfirmOption;
+use crate::gui::button;
+use crate::help;
+use crate::keyboard::KeyboardMapping;
+use crate::style::Style;
+use crate::tab_species::Message as TabMessage;
+use crate::tab_species::MessageQueue as TabMessageQueue;
+use const_format::formatcp as fmt;
+use db::Database;
+use dwc::checklist;
+use dwc::checklist::Field;
+use dwc::checklist::Mapping;
+use dwc::checklist::Resolution;
+use dwc::checklist::Row;
+use dwc::checklist::RowKind;
+use dwc::checklist::Table;
+use egui::Align;
+use egui::CentralPanel;
+use egui::ComboBox;
+use egui::Context;
+use egui::Grid;
+use egui::Key;
+use egui::Layout;
+use egui::ScrollArea;
+use egui::TextEdit;
+use egui::TopBottomPanel;
+use egui::Ui;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+use egui_material_icons::icons::ICON_ERROR;
+use egui_material_icons::icons::ICON_UPLOAD_FILE;
+use egui_material_icons::icons::ICON_WARNING;
+
+const ID_PREFIX: &str = "modal-import-species";
+
+pub struct ModalImport {
+    path: String,
+    table: Option<Table>,
+    mapping: Mapping,
+    rows: Result<Vec<Row>, String>,
+
+    pub queue: MessageQueue,
+    pub keyboard_mapping: KeyboardMapping,
+}
+
+type MessageQueue = VecDeque<Message>;
+
+#[derive(Clone)]
+pub enum Message {
+    ChangePath(String),
+    Load,
+    SetMapping(usize, Option<Field>),
+    SetResolution(usize, Resolution),
+    SetConflictResolution(Resolution),
+    SoftClose,
+    ImportAndExit,
+    CancelAndExit,
+}
+
+impl Message {
+    pub const fn name(&self) -> &str {
+        match self {
+            Self::ChangePath(_) => unreachable!(),
+            Self::Load => unreachable!(),
+            Self::SetMapping(..) => unreachable!(),
+            Self::SetResolution(..) => unreachable!(),
+            Self::SetConflictResolution(_) => unreachable!(),
+            Self::SoftClose => help::SOFT_CLOSE,
+            Self::ImportAndExit => "import species and close",
+            Self::CancelAndExit => unreachable!(),
+        }
+    }
+}
+
+impl From<Message> for TabMessage {
+    fn from(val: Message) -> Self {
+        Self::ModalImport(val)
+    }
+}
+
+impl ModalImport {
+    pub fn new() -> Self {
+        Self {
+            path: String::new(),
+            table: None,
+            mapping: Mapping(Vec::new()),
+            rows: Ok(Vec::new()),
+            queue: MessageQueue::new(),
+            keyboard_mapping: Self::create_mapping(),
+        }
+    }
+
+    fn create_mapping() -> KeyboardMapping {
+        KeyboardMapping::default()
+            .key(
+                Key::Escape,
+                MainMessage::TabSpecies(Message::SoftClose.into()),
+            )
+            .ctrl(
+                Key::S,
+                MainMessage::TabSpecies(Message::ImportAndExit.into()),
+            )
+    }
+
+    pub fn update(
+        &mut self,
+        ctx: &Context,
+        style: &Style,
+        db: &mut Database,
+        tab_queue: &mut TabMessageQueue,
+    ) {
+        let dropped: Option<PathBuf> = ctx.input(|input| {
+            input
+                .raw
+                .dropped_files
+                .first()
+                .and_then(|file| file.path.clone())
+        });
+        if let Some(path) = dropped {
+            self.path = path.display().to_string();
+            self.queue.push_back(Message::Load);
+        }
+
+        while let Some(msg) = self.queue.pop_front() {
+            self.handle_message(style, db, msg, tab_queue);
+        }
+
+        let mut queue = MessageQueue::new();
+        self.draw(ctx, style, &mut queue);
+
+        while let Some(msg) = queue.pop_front() {
+            self.queue.push_back(msg);
+        }
+    }
+
+    fn handle_message(
+        &mut self,
+        style: &Style,
+        db: &mut Database,
+        message: Message,
+        tab_queue: &mut TabMessageQueue,
+    ) {
+        match message {
+            Message::ChangePath(path) => {
+                self.path = path;
+            }
+            Message::Load => match checklist::read_table(&PathBuf::from(self.path.trim())) {
+                Ok(table) => {
+                    self.mapping = Mapping::guess(&table.header);
+                    self.table = Some(table);
+                    self.refresh_preview(db);
+                }
+                Err(err) => {
+                    self.table = None;
+                    self.rows = Err(err);
+                }
+            },
+            Message::SetMapping(column, field) => {
+                self.mapping.0[column] = field;
+                self.refresh_preview(db);
+            }
+            Message::SetResolution(index, resolution) => {
+                if let Ok(rows) = &mut self.rows {
+                    rows[index].resolution = resolution;
+                }
+            }
+            Message::SetConflictResolution(resolution) => {
+                if let Ok(rows) = &mut self.rows {
+                    for row in rows.iter_mut().filter(|row| row.has_conflicts()) {
+                        row.resolution = resolution;
+                    }
+                }
+            }
+            Message::SoftClose => {
+                if self.to_import() > 0 {
+                    let msg: TabMessage = Message::ImportAndExit.into();
+                    let import = ConfirmOption::new("Import and exit")
+                        .with_message(msg.into())
+                        .with_color(style.button.save);
+
+                    let msg: TabMessage = Message::CancelAndExit.into();
+                    let abort = ConfirmOption::new(fmt!("{ICON_WARNING} Abandon import"))
+                        .with_message(msg.into())
+                        .with_color(style.button.discard);
+
+                    let cont = ConfirmOption::new("Continue").with_key(Key::Escape);
+
+                    let confirm =
+                        Confirm::new("The checklist was not imported.", vec![abort, import, cont]);
+
+                    tab_queue.push_back(TabMessage::Confirm(confirm));
+                } else {
+                    tab_queue.push_back(TabMessage::CloseModal);
+                }
+            }
+            Message::CancelAndExit => {
+                tab_queue.push_back(TabMessage::CloseModal);
+            }
+            Message::ImportAndExit => {
+                if let Ok(rows) = &self.rows {
+                    let summary = checklist::apply(db, rows);
+                    log::info!(
+                        "{}: added {} species, updated {}",
+                        self.path,
+                        summary.added,
+                        summary.updated
+                    );
+                }
+                tab_queue.push_back(TabMessage::RefreshView);
+                tab_queue.push_back(TabMessage::CloseModal);
+            }
+        }
+    }
+
+    fn refresh_preview(&mut self, db: &Database) {
+        if let Some(table) = &self.table {
+            self.rows = checklist::preview(db, table, &self.mapping);
+        }
+    }
+
+    /// Number of rows which would change the species list.
+    fn to_import(&self) -> usize {
+        match &self.rows {
+            Ok(rows) => rows
+                .iter()
+                .filter(|row| row.resolution != Resolution::Skip)
+                .filter(|row| matches!(row.kind, RowKind::New | RowKind::Update(..)))
+                .count(),
+            Err(_) => 0,
+        }
+    }
+
+    fn draw(&self, ctx: &Context, style: &Style, queue: &mut MessageQueue) {
+        TopBottomPanel::top(fmt!("{ID_PREFIX}-top")).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("checklist");
+
+                let mut path = self.path.clone();
+                let edit = TextEdit::singleline(&mut path)
+                    .hint_text("CSV, taxon.txt or Darwin Core Archive; drop a file here")
+                    .desired_width(ui.available_width() - 100.0);
+                let resp = ui.add(edit);
+                if resp.lost_focus() && ui.input(|input| input.key_pressed(Key::Enter)) {
+                    queue.push_back(Message::Load);
+                }
+                if path != self.path {
+                    queue.push_back(Message::ChangePath(path));
+                }
+
+                if ui.button(fmt!("{ICON_UPLOAD_FILE} Load")).clicked() {
+                    queue.push_back(Message::Load);
+                }
+            });
+
+            if let Some(table) = &self.table {
+                ui.separator();
+                self.draw_mapping(ui, table, queue);
+            }
+        });
+
+        TopBottomPanel::bottom(fmt!("{ID_PREFIX}-bottom")).show(ctx, |ui| {
+            ui.with_layout(Layout::right_to_left(Align::Min), |ui| {
+                let count = self.to_import();
+                let label = format!("Import {count} species");
+                let button = egui::Button::new(label).fill(style.button.save);
+                if ui.add_enabled(count > 0, button).clicked() {
+                    queue.push_back(Message::ImportAndExit);
+                }
+                if button::cancel(ui) {
+                    queue.push_back(Message::SoftClose);
+                }
+
+                if self
+                    .rows
+                    .as_ref()
+                    .is_ok_and(|rows| rows.iter().any(Row::has_conflicts))
+                {
+                    ui.separator();
+                    if ui.button("keep all existing values").clicked() {
+                        queue.push_back(Message::SetConflictResolution(Resolution::FillEmpty));
+                    }
+                    if ui.button("overwrite all conflicts").clicked() {
+                        queue.push_back(Message::SetConflictResolution(Resolution::Overwrite));
+                    }
+                }
+            });
+        });
+
+        CentralPanel::default().show(ctx, |ui| match &self.rows {
+            Ok(rows) => self.draw_preview(ui, style, rows, queue),
+            Err(err) => {
+                ui.colored_label(style.error, format!("{ICON_ERROR} {err}"));
+            }
+        });
+    }
+
+    fn draw_mapping(&self, ui: &mut Ui, table: &Table, queue: &mut MessageQueue) {
+        ScrollArea::horizontal()
+            .id_salt(fmt!("{ID_PREFIX}-mapping-scroll"))
+            .show(ui, |ui| {
+                Grid::new(fmt!("{ID_PREFIX}-mapping"))
+                    .num_columns(table.header.len())
+                    .show(ui, |ui| {
+                        for column in &table.header {
+                            ui.strong(column);
+                        }
+                        ui.end_row();
+
+                        for (column, current) in self.mapping.0.iter().enumerate() {
+                            let mut selected = *current;
+                            ComboBox::from_id_salt((ID_PREFIX, "mapping", column))
+                                .selected_text(field_name(selected))
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut selected, None, field_name(None));
+                                    for field in Field::ALL {
+                                        ui.selectable_value(
+                                            &mut selected,
+                                            Some(field),
+                                            field.name(),
+                                        );
+                                    }
+                                });
+
+                            if selected != *current {
+                                queue.push_back(Message::SetMapping(column, selected));
+                            }
+                        }
+                        ui.end_row();
+                    });
+            });
+    }
+
+    fn draw_preview(&self, ui: &mut Ui, style: &Style, rows: &[Row], queue: &mut MessageQueue) {
+        ScrollArea::vertical()
+            .auto_shrink(false)
+            .id_salt(fmt!("{ID_PREFIX}-preview-scroll"))
+            .show(ui, |ui| {
+                Grid::new(fmt!("{ID_PREFIX}-preview"))
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (index, row) in rows.iter().enumerate() {
+                            ui.label(format!("{}", row.line));
+                            ui.label(row.imported.latin.as_str());
+
+                            match &row.kind {
+                                RowKind::New => {
+                                    ui.label("new species");
+                                    select_resolution(ui, index, row, false, queue);
+                                }
+                                RowKind::Update(_, changes) => {
+                                    ui.vertical(|ui| {
+                                        for change in changes {
+                                            let text = if change.is_conflict() {
+                                                format!(
+                                                    "{ICON_WARNING} {}: {} → {}",
+                                                    change.field.name(),
+                                                    change.old,
+                                                    change.new
+                                                )
+                                            } else {
+                                                format!("{}: {}", change.field.name(), change.new)
+                                            };
+                                            ui.label(text);
+                                        }
+                                    });
+                                    select_resolution(ui, index, row, true, queue);
+                                }
+                                RowKind::Unchanged(_) => {
+                                    ui.weak("no changes");
+                                    ui.label("");
+                                }
+                                RowKind::Invalid(err) => {
+                                    ui.colored_label(style.error, format!("{ICON_ERROR} {err}"));
+                                    ui.label("");
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    pub fn try_close(&mut self) {
+        self.queue.push_back(Message::SoftClose);
+    }
+}
+
+fn select_resolution(ui: &mut Ui, index: usize, row: &Row, update: bool, queue: &mut MessageQueue) {
+    let options: &[(Resolution, &str)] = if !update {
+        &[(Resolution::Overwrite, "add"), (Resolution::Skip, "skip")]
+    } else if row.has_conflicts() {
+        &[
+            (Resolution::FillEmpty, "keep existing"),
+            (Resolution::Overwrite, "overwrite"),
+            (Resolution::Skip, "skip"),
+        ]
+    } else {
+        &[
+            (Resolution::FillEmpty, "update"),
+            (Resolution::Skip, "skip"),
+        ]
+    };
+
+    ui.horizontal(|ui| {
+        for (resolution, label) in options {
+            if ui
+                .selectable_label(row.resolution == *resolution, *label)
+                .clicked()
+            {
+                queue.push_back(Message::SetResolution(index, *resolution));
+            }
+        }
+    });
+}
+
+const fn field_name(field: Option<Field>) -> &'static str {
+    match field {
+        Some(field) => field.name(),
+        None => "(ignore)",
+    }
+}
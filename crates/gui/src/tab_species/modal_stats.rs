@@ -0,0 +1,263 @@
+use crate::application::Message as MainMessage;
+use crate::gui::button;
+use crate::help;
+use crate::keyboard::KeyboardMapping;
+use crate::style::Style;
+use crate::tab_species::Message as TabMessage;
+use crate::tab_species::MessageQueue as TabMessageQueue;
+use const_format::formatcp as fmt;
+use db::Database;
+use db::Latin;
+use db::LifeRecord;
+use db::Year;
+use egui::vec2;
+use egui::Align;
+use egui::CentralPanel;
+use egui::Context;
+use egui::CornerRadius;
+use egui::Grid;
+use egui::Key;
+use egui::Layout;
+use egui::Rect;
+use egui::ScrollArea;
+use egui::Sense;
+use egui::TextEdit;
+use egui::TopBottomPanel;
+use egui::Ui;
+use std::collections::BTreeMap;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+use egui_material_icons::icons::ICON_DOWNLOAD;
+use egui_material_icons::icons::ICON_ERROR;
+
+const ID_PREFIX: &str = "modal-species-stats";
+
+const MONTHS: [&str; 12] = ["J", "F", "M", "A", "M", "J", "J", "A", "S", "O", "N", "D"];
+
+pub struct ModalStats {
+    life_list: Vec<LifeRecord>,
+    new_species: BTreeMap<Year, Vec<Latin>>,
+    path: String,
+    /// Result of the last export
+    status: Option<Result<String, String>>,
+
+    pub queue: MessageQueue,
+    pub keyboard_mapping: KeyboardMapping,
+}
+
+type MessageQueue = VecDeque<Message>;
+
+#[derive(Clone)]
+pub enum Message {
+    ChangePath(String),
+    Export,
+    Close,
+}
+
+impl Message {
+    pub const fn name(&self) -> &str {
+        match self {
+            Self::ChangePath(_) => unreachable!(),
+            Self::Export => "export the life list as CSV",
+            Self::Close => help::SOFT_CLOSE,
+        }
+    }
+}
+
+impl From<Message> for TabMessage {
+    fn from(val: Message) -> Self {
+        Self::ModalStats(val)
+    }
+}
+
+impl ModalStats {
+    pub fn new(db: &Database) -> Self {
+        Self {
+            life_list: db.life_list(),
+            new_species: db.new_species_by_year(),
+            path: String::from("life-list.csv"),
+            status: None,
+            queue: MessageQueue::new(),
+            keyboard_mapping: Self::create_mapping(),
+        }
+    }
+
+    fn create_mapping() -> KeyboardMapping {
+        KeyboardMapping::default()
+            .key(Key::Escape, MainMessage::TabSpecies(Message::Close.into()))
+            .ctrl(Key::S, MainMessage::TabSpecies(Message::Export.into()))
+    }
+
+    pub fn update(
+        &mut self,
+        ctx: &Context,
+        style: &Style,
+        db: &Database,
+        tab_queue: &mut TabMessageQueue,
+    ) {
+        while let Some(msg) = self.queue.pop_front() {
+            self.handle_message(db, msg, tab_queue);
+        }
+
+        let mut queue = MessageQueue::new();
+        self.draw(ctx, style, db, &mut queue);
+
+        while let Some(msg) = queue.pop_front() {
+            self.queue.push_back(msg);
+        }
+    }
+
+    fn handle_message(&mut self, db: &Database, message: Message, tab_queue: &mut TabMessageQueue) {
+        match message {
+            Message::ChangePath(path) => {
+                self.path = path;
+            }
+            Message::Export => {
+                let path = PathBuf::from(self.path.trim());
+                self.status = Some(
+                    dwc::export_life_list(db, None, &path)
+                        .map(|count| format!("{}: exported {count} species", path.display())),
+                );
+            }
+            Message::Close => {
+                tab_queue.push_back(TabMessage::CloseModal);
+            }
+        }
+    }
+
+    fn draw(&self, ctx: &Context, style: &Style, db: &Database, queue: &mut MessageQueue) {
+        TopBottomPanel::top(fmt!("{ID_PREFIX}-top")).show(ctx, |ui| {
+            let photos: usize = self.life_list.iter().map(|record| record.photos).sum();
+            ui.heading(format!(
+                "{} species, {} photos",
+                self.life_list.len(),
+                photos
+            ));
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label("new species:");
+                for (year, species) in &self.new_species {
+                    ui.label(format!("{year}: {}", species.len()));
+                }
+            });
+        });
+
+        TopBottomPanel::bottom(fmt!("{ID_PREFIX}-bottom")).show(ctx, |ui| {
+            ui.with_layout(Layout::right_to_left(Align::Min), |ui| {
+                if button::close(ui) {
+                    queue.push_back(Message::Close);
+                }
+
+                ui.separator();
+
+                if ui.button(fmt!("{ICON_DOWNLOAD} Export")).clicked() {
+                    queue.push_back(Message::Export);
+                }
+
+                let mut path = self.path.clone();
+                let edit = TextEdit::singleline(&mut path)
+                    .hint_text("CSV file")
+                    .desired_width(300.0);
+                ui.add(edit);
+                if path != self.path {
+                    queue.push_back(Message::ChangePath(path));
+                }
+
+                match &self.status {
+                    Some(Ok(status)) => {
+                        ui.label(status);
+                    }
+                    Some(Err(err)) => {
+                        ui.colored_label(style.error, format!("{ICON_ERROR} {err}"));
+                    }
+                    None => (),
+                }
+            });
+        });
+
+        CentralPanel::default().show(ctx, |ui| {
+            ScrollArea::vertical()
+                .auto_shrink(false)
+                .id_salt(fmt!("{ID_PREFIX}-scroll"))
+                .show(ui, |ui| {
+                    Grid::new(fmt!("{ID_PREFIX}-grid"))
+                        .num_columns(6)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("species");
+                            ui.strong("first seen");
+                            ui.strong("last seen");
+                            ui.strong("posts");
+                            ui.strong("photos");
+                            ui.strong(MONTHS.join(" "));
+                            ui.end_row();
+
+                            for record in &self.life_list {
+                                self.draw_record(ui, db, record);
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+    }
+
+    fn draw_record(&self, ui: &mut Ui, db: &Database, record: &LifeRecord) {
+        ui.horizontal(|ui| {
+            ui.label(record.latin.as_str());
+            if let Some(species) = db.species_by_latin(&record.latin) {
+                ui.weak(&species.pl);
+            }
+        });
+        ui.label(record.first_seen.to_string());
+        ui.label(record.last_seen.to_string());
+        ui.label(record.posts.to_string());
+        ui.label(record.photos.to_string());
+        draw_phenology(ui, &record.months);
+    }
+
+    pub fn try_close(&mut self) {
+        self.queue.push_back(Message::Close);
+    }
+}
+
+/// Bar per month, scaled to the busiest month of the species.
+fn draw_phenology(ui: &mut Ui, months: &[usize; 12]) {
+    let bar = vec2(10.0, ui.spacing().interact_size.y);
+    let (rect, resp) = ui.allocate_exact_size(vec2(bar.x * 12.0, bar.y), Sense::hover());
+
+    let max = months.iter().copied().max().unwrap_or_default().max(1);
+    let painter = ui.painter_at(rect);
+    let fill = ui.visuals().selection.bg_fill;
+    let empty = ui.visuals().faint_bg_color;
+    for (index, count) in months.iter().enumerate() {
+        let height = bar.y * (*count as f32 / max as f32);
+        let left = rect.left() + bar.x * index as f32;
+        painter.rect_filled(
+            Rect::from_min_max(
+                egui::pos2(left + 1.0, rect.top()),
+                egui::pos2(left + bar.x - 1.0, rect.bottom()),
+            ),
+            CornerRadius::ZERO,
+            empty,
+        );
+        painter.rect_filled(
+            Rect::from_min_max(
+                egui::pos2(left + 1.0, rect.bottom() - height),
+                egui::pos2(left + bar.x - 1.0, rect.bottom()),
+            ),
+            CornerRadius::ZERO,
+            fill,
+        );
+    }
+
+    resp.on_hover_text(
+        months
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(index, count)| format!("{:02}: {count} posts", index + 1))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    );
+}
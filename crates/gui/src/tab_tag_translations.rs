@@ -3,6 +3,7 @@ use crate::gui::icon_en;
 use crate::gui::icon_pl;
 use crate::keyboard::KeyboardMapping;
 use crate::search_box::SearchBox;
+use db::text::fold;
 use db::Database;
 use db::Translation;
 use egui::Align;
@@ -132,7 +133,7 @@ impl TabTagTranslations {
             .id_salt("scroll-area-tags")
             .auto_shrink(false)
             .show(ui, |ui| {
-                let phrase = self.search_box.phrase(ui.ctx());
+                let phrase = fold(&self.search_box.phrase(ui.ctx()));
                 for (id, trans) in db.tag_translations.0.iter().enumerate() {
                     if self.filter(&phrase, trans) {
                         self.draw_translation(ui, id, trans, queue);
@@ -142,7 +143,7 @@ impl TabTagTranslations {
     }
 
     fn filter(&self, phrase: &str, trans: &Translation) -> bool {
-        phrase.is_empty() | trans.pl.contains(phrase) | trans.en.contains(phrase)
+        phrase.is_empty() | fold(&trans.pl).contains(phrase) | fold(&trans.en).contains(phrase)
     }
 
     fn draw_translation(
@@ -0,0 +1,172 @@
+//! Publishing posts on social media platforms.
+//!
+//! Each platform is a [`Publisher`]; the set of available publishers
+//! depends on the credentials, see [`publishers`]. Adding a platform
+//! means implementing the trait and registering it in [`publisher`].
+
+mod platforms;
+pub mod scheduler;
+
+use db::Database;
+use db::Post;
+use db::edit_details::SocialMediaLink;
+use socmedia::Credentials;
+use socmedia::Media;
+use socmedia::Published;
+use socmedia::bluesky::Bluesky;
+use socmedia::facebook::Facebook;
+use socmedia::instagram::Instagram;
+use socmedia::mastodon::Mastodon;
+use socmedia::mastodon::Visibility;
+
+pub trait Publisher: Send {
+    fn platform(&self) -> SocialMediaLink;
+
+    /// Checks limits of the platform; returns the list of problems.
+    fn validate(&self, draft: &Draft) -> Vec<String>;
+
+    /// Creates a post; blocks until the platform has accepted it.
+    fn publish(&self, draft: &Draft) -> socmedia::Result<Published>;
+}
+
+/// Post as sent to platforms.
+#[derive(Debug, Clone)]
+pub struct Draft {
+    pub media: Vec<Media>,
+    pub caption: String,
+    pub options: PublishOptions,
+}
+
+/// Settings chosen while publishing; not all platforms support them.
+#[derive(Debug, Default, Clone)]
+pub struct PublishOptions {
+    pub content_warning: String,
+    pub visibility: Visibility,
+}
+
+impl PublishOptions {
+    pub fn new(credentials: &Credentials) -> Self {
+        match &credentials.mastodon {
+            Some(config) => Self {
+                content_warning: config.content_warning.clone(),
+                visibility: config.visibility,
+            },
+            None => Self::default(),
+        }
+    }
+}
+
+impl Draft {
+    pub fn new(post: &Post, db: &Database, caption: String, options: PublishOptions) -> Self {
+        let media = post
+            .files
+            .iter()
+            .enumerate()
+            .map(|(index, file)| Media {
+                path: file.full_path.clone(),
+                rel_path: file.rel_path.clone(),
+                alt_text: db.alt_text(post, index),
+                size: file.image_size.map(|size| (size.width, size.height)),
+            })
+            .collect();
+
+        Self {
+            media,
+            caption,
+            options,
+        }
+    }
+}
+
+/// Publishers of all platforms having credentials.
+pub fn publishers(credentials: &Credentials) -> Vec<Box<dyn Publisher>> {
+    SocialMediaLink::ALL
+        .into_iter()
+        .filter_map(|link| publisher(credentials, link))
+        .collect()
+}
+
+/// Returns `None` if there are no credentials for the platform.
+pub fn publisher(credentials: &Credentials, link: SocialMediaLink) -> Option<Box<dyn Publisher>> {
+    let publisher: Box<dyn Publisher> = match link {
+        SocialMediaLink::Facebook => Box::new(Facebook::new(credentials.facebook.as_ref()?)),
+        SocialMediaLink::Instagram => Box::new(Instagram::new(credentials.instagram.as_ref()?)),
+        SocialMediaLink::Mastodon => Box::new(Mastodon::new(credentials.mastodon.as_ref()?)),
+        SocialMediaLink::Bluesky => Box::new(Bluesky::new(credentials.bluesky.as_ref()?)),
+    };
+
+    Some(publisher)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use db::FileMetadata;
+    use db::test_util::DatabaseBuilder;
+    use db::test_util::latin;
+    use db::test_util::red_fox;
+
+    #[test]
+    fn test_publishers() {
+        let credentials = Credentials::parse(
+            r#"
+            [instagram]
+            user_id = "17"
+            access_token = "secret"
+            media_url = "https://example.com/photos"
+
+            [bluesky]
+            identifier = "photos.bsky.social"
+            app_password = "secret"
+            "#,
+        )
+        .unwrap();
+
+        let platforms: Vec<_> = publishers(&credentials)
+            .iter()
+            .map(|publisher| publisher.platform())
+            .collect();
+
+        assert_eq!(
+            platforms,
+            vec![SocialMediaLink::Instagram, SocialMediaLink::Bluesky]
+        );
+        assert!(publisher(&credentials, SocialMediaLink::Facebook).is_none());
+    }
+
+    #[test]
+    fn test_draft() {
+        let db = DatabaseBuilder::new().species(red_fox()).build();
+
+        let post = Post {
+            pl: "Lis w lesie".to_owned(),
+            species: Some(latin("Vulpes vulpes")),
+            files: vec![
+                Default::default(),
+                FileMetadata {
+                    alt_en: "Fox in the snow".to_owned(),
+                    ..FileMetadata::default()
+                },
+            ],
+            ..Post::default()
+        };
+
+        let draft = Draft::new(&post, &db, "caption".to_owned(), PublishOptions::default());
+        assert_eq!(draft.media.len(), 2);
+        assert_eq!(draft.media[0].alt_text, "Red fox (Vulpes vulpes)");
+        assert_eq!(draft.media[1].alt_text, "Fox in the snow");
+
+        let credentials = Credentials::parse(
+            r#"
+            [mastodon]
+            instance_url = "https://mastodon.social"
+            access_token = "secret"
+            max_media = 1
+            "#,
+        )
+        .unwrap();
+
+        let mastodon = publisher(&credentials, SocialMediaLink::Mastodon).unwrap();
+        assert_eq!(mastodon.validate(&draft).len(), 1);
+    }
+}
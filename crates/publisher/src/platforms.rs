@@ -0,0 +1,73 @@
+use crate::Draft;
+use crate::Publisher;
+use db::edit_details::SocialMediaLink;
+use socmedia::Published;
+use socmedia::bluesky;
+use socmedia::bluesky::Bluesky;
+use socmedia::facebook;
+use socmedia::facebook::Facebook;
+use socmedia::instagram;
+use socmedia::instagram::Instagram;
+use socmedia::mastodon::Mastodon;
+use socmedia::mastodon::StatusOptions;
+
+impl Publisher for Facebook {
+    fn platform(&self) -> SocialMediaLink {
+        SocialMediaLink::Facebook
+    }
+
+    fn validate(&self, draft: &Draft) -> Vec<String> {
+        facebook::validate(draft.media.len(), &draft.caption)
+    }
+
+    fn publish(&self, draft: &Draft) -> socmedia::Result<Published> {
+        self.publish(&draft.media, &draft.caption)
+    }
+}
+
+impl Publisher for Instagram {
+    fn platform(&self) -> SocialMediaLink {
+        SocialMediaLink::Instagram
+    }
+
+    fn validate(&self, draft: &Draft) -> Vec<String> {
+        instagram::validate(draft.media.len(), &draft.caption)
+    }
+
+    fn publish(&self, draft: &Draft) -> socmedia::Result<Published> {
+        self.publish(&draft.media, &draft.caption)
+    }
+}
+
+impl Publisher for Mastodon {
+    fn platform(&self) -> SocialMediaLink {
+        SocialMediaLink::Mastodon
+    }
+
+    fn validate(&self, draft: &Draft) -> Vec<String> {
+        self.config().validate(draft.media.len(), &draft.caption)
+    }
+
+    fn publish(&self, draft: &Draft) -> socmedia::Result<Published> {
+        let options = StatusOptions {
+            content_warning: draft.options.content_warning.clone(),
+            visibility: draft.options.visibility,
+        };
+
+        self.publish(&draft.media, &draft.caption, &options)
+    }
+}
+
+impl Publisher for Bluesky {
+    fn platform(&self) -> SocialMediaLink {
+        SocialMediaLink::Bluesky
+    }
+
+    fn validate(&self, draft: &Draft) -> Vec<String> {
+        bluesky::validate(draft.media.len(), &draft.caption)
+    }
+
+    fn publish(&self, draft: &Draft) -> socmedia::Result<Published> {
+        self.publish(&draft.media, &draft.caption)
+    }
+}
@@ -0,0 +1,362 @@
+//! Publishing posts whose scheduled time has come.
+
+use crate::Draft;
+use crate::PublishOptions;
+use crate::Publisher;
+use db::Database;
+use db::LocalDateTime;
+use db::PlatformStatus;
+use db::PostId;
+use db::PublishedState;
+use db::Schedule;
+use db::edit_details;
+use db::edit_details::EditDetails;
+use db::edit_details::SocialMediaLink;
+use db::render_caption;
+use db::schedule::format_date_time;
+use std::io::Write;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub published: usize,
+    pub failed: usize,
+}
+
+/// Publishes due posts on their scheduled platforms and records results
+/// in the database. In the dry-run mode only prints what would be sent.
+///
+/// A platform which failed stays in the schedule, thus the next run
+/// repeats the attempt; once all platforms succeed the schedule is
+/// removed and the post is marked as published.
+///
+/// The database is passed to `save` after each recorded result, so a
+/// post already sent is not sent again when a later one aborts the run.
+pub fn publish_due(
+    db: &mut Database,
+    now: LocalDateTime,
+    publisher: &dyn Fn(SocialMediaLink) -> Option<Box<dyn Publisher>>,
+    options: &PublishOptions,
+    dry_run: bool,
+    out: &mut dyn Write,
+    save: &mut dyn FnMut(&mut Database) -> std::io::Result<()>,
+) -> std::io::Result<Summary> {
+    let due: Vec<(PostId, Schedule)> = db
+        .scheduled()
+        .into_iter()
+        .filter(|(_, schedule)| schedule.is_due(&now))
+        .map(|(id, schedule)| (id, schedule.clone()))
+        .collect();
+
+    let mut summary = Summary::default();
+    for (id, schedule) in due {
+        let at = format_date_time(&schedule.at);
+        let subject = match db.post(&id).files.first() {
+            Some(file) if !file.rel_path.as_os_str().is_empty() => {
+                format!("{at} {}", file.rel_path.display())
+            }
+            _ => at,
+        };
+
+        let mut remaining = Vec::new();
+        for link in schedule.platforms.iter().copied() {
+            if db.post(&id).platforms.is_published_on(link) {
+                continue;
+            }
+
+            let post = db.post(&id);
+            let caption = render_caption(post, db, Some(link));
+            let draft = Draft::new(post, db, caption, options.clone());
+
+            let header = format!("{subject} on {}", link.name());
+
+            let result = match publisher(link) {
+                None => Err(format!("no credentials for {}", link.name())),
+                Some(publisher) => {
+                    let errors = publisher.validate(&draft);
+                    if !errors.is_empty() {
+                        Err(errors.join("; "))
+                    } else if dry_run {
+                        writeln!(out, "{header}: would publish")?;
+                        describe(&draft, link, out)?;
+                        continue;
+                    } else {
+                        publisher.publish(&draft).map_err(|err| err.to_string())
+                    }
+                }
+            };
+
+            match result {
+                Ok(published) => {
+                    writeln!(out, "{header}: published {}", published.url)?;
+                    summary.published += 1;
+                    if !dry_run {
+                        let status = PlatformStatus::published(published.id, published.url, now);
+                        let action = EditDetails::SetPlatformStatus(id, link, status);
+                        edit_details::apply(action, db);
+                        save(db)?;
+                    }
+                }
+                Err(err) => {
+                    writeln!(out, "{header}: failed: {err}")?;
+                    summary.failed += 1;
+                    remaining.push(link);
+                    if !dry_run {
+                        let status = db
+                            .post(&id)
+                            .platforms
+                            .get(link)
+                            .cloned()
+                            .unwrap_or_default()
+                            .failed(err, now);
+                        let action = EditDetails::SetPlatformStatus(id, link, status);
+                        edit_details::apply(action, db);
+                        save(db)?;
+                    }
+                }
+            }
+        }
+
+        if dry_run {
+            continue;
+        }
+
+        if remaining.is_empty() {
+            edit_details::apply(EditDetails::SetSchedule(id, None), db);
+            let action = EditDetails::SetPublished(id, PublishedState::Timestamp(now));
+            edit_details::apply(action, db);
+        } else {
+            let schedule = Schedule {
+                at: schedule.at,
+                platforms: remaining,
+            };
+            edit_details::apply(EditDetails::SetSchedule(id, Some(schedule)), db);
+        }
+        save(db)?;
+    }
+
+    Ok(summary)
+}
+
+fn describe(draft: &Draft, link: SocialMediaLink, out: &mut dyn Write) -> std::io::Result<()> {
+    for line in draft.caption.lines() {
+        writeln!(out, "    | {line}")?;
+    }
+
+    for media in &draft.media {
+        writeln!(out, "    photo {}", media.path.display())?;
+        if !media.alt_text.is_empty() {
+            writeln!(out, "      alt: {}", media.alt_text)?;
+        }
+    }
+
+    if link == SocialMediaLink::Mastodon {
+        writeln!(out, "    visibility: {}", draft.options.visibility)?;
+        if !draft.options.content_warning.is_empty() {
+            writeln!(
+                out,
+                "    content warning: {}",
+                draft.options.content_warning
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use db::FileMetadata;
+    use db::Post;
+    use db::schedule::parse_date_time;
+    use db::test_util::DatabaseBuilder;
+    use socmedia::Error;
+    use socmedia::Published;
+
+    struct Fake {
+        link: SocialMediaLink,
+        fail: bool,
+    }
+
+    impl Publisher for Fake {
+        fn platform(&self) -> SocialMediaLink {
+            self.link
+        }
+
+        fn validate(&self, _draft: &Draft) -> Vec<String> {
+            Vec::new()
+        }
+
+        fn publish(&self, draft: &Draft) -> socmedia::Result<Published> {
+            if self.fail {
+                return Err(Error::Transport("connection reset".to_owned()));
+            }
+
+            Ok(Published {
+                id: "42".to_owned(),
+                url: format!("https://{}.example/{}", self.link.name(), draft.media.len()),
+            })
+        }
+    }
+
+    fn lookup(link: SocialMediaLink) -> Option<Box<dyn Publisher>> {
+        match link {
+            SocialMediaLink::Facebook | SocialMediaLink::Instagram => Some(Box::new(Fake {
+                link,
+                fail: link == SocialMediaLink::Instagram,
+            })),
+            _ => None,
+        }
+    }
+
+    /// Posts of a fox scheduled on three days.
+    fn database() -> Database {
+        let mut builder = DatabaseBuilder::new();
+        for (at, platforms) in [
+            (
+                "2025-05-01 18:00",
+                vec![SocialMediaLink::Facebook, SocialMediaLink::Instagram],
+            ),
+            ("2025-05-02 18:00", vec![SocialMediaLink::Facebook]),
+            ("2025-05-09 18:00", vec![SocialMediaLink::Facebook]),
+        ] {
+            builder = builder.post(Post {
+                en: "Red fox".to_owned(),
+                files: vec![FileMetadata {
+                    alt_en: "Red fox at dusk".to_owned(),
+                    ..FileMetadata::default()
+                }],
+                schedule: Some(Schedule {
+                    at: parse_date_time(at).unwrap(),
+                    platforms,
+                }),
+                ..Post::default()
+            });
+        }
+
+        builder.build()
+    }
+
+    #[test]
+    fn test_publish_due() {
+        let mut db = database();
+        let now = parse_date_time("2025-05-02 18:30").unwrap();
+        let mut out = Vec::new();
+
+        let summary = publish_due(
+            &mut db,
+            now,
+            &lookup,
+            &PublishOptions::default(),
+            false,
+            &mut out,
+            &mut |_| Ok(()),
+        )
+        .unwrap();
+
+        assert_eq!(
+            summary,
+            Summary {
+                published: 2,
+                failed: 1
+            }
+        );
+
+        let first = db.post(&PostId(0));
+        assert!(first.platforms.is_published_on(SocialMediaLink::Facebook));
+        assert_eq!(
+            first.platforms.url(SocialMediaLink::Facebook),
+            "https://Facebook.example/1"
+        );
+        let instagram = first.platforms.get(SocialMediaLink::Instagram).unwrap();
+        assert_eq!(instagram.last_error, "connection error: connection reset");
+        assert_eq!(
+            first.schedule.as_ref().unwrap().platforms,
+            vec![SocialMediaLink::Instagram]
+        );
+        assert!(!first.is_published());
+
+        let second = db.post(&PostId(1));
+        assert!(second.schedule.is_none());
+        assert!(second.is_published());
+
+        assert!(db.post(&PostId(2)).schedule.is_some());
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("on Instagram: failed: connection error: connection reset"));
+    }
+
+    /// Output which breaks once publishing reaches Instagram.
+    struct Broken;
+
+    impl Write for Broken {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if String::from_utf8_lossy(buf).contains("Instagram") {
+                return Err(std::io::Error::other("broken pipe"));
+            }
+
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_saved_before_abort() {
+        let mut db = database();
+        let now = parse_date_time("2025-05-01 18:30").unwrap();
+        let mut saved = Vec::new();
+
+        let result = publish_due(
+            &mut db,
+            now,
+            &lookup,
+            &PublishOptions::default(),
+            false,
+            &mut Broken,
+            &mut |db| {
+                let post = db.post(&PostId(0));
+                saved.push(post.platforms.is_published_on(SocialMediaLink::Facebook));
+                Ok(())
+            },
+        );
+
+        assert!(result.is_err());
+        assert_eq!(saved, vec![true]);
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let mut db = database();
+        let now = parse_date_time("2025-05-01 18:00").unwrap();
+        let mut out = Vec::new();
+
+        let summary = publish_due(
+            &mut db,
+            now,
+            &lookup,
+            &PublishOptions::default(),
+            true,
+            &mut out,
+            &mut |_| Ok(()),
+        )
+        .unwrap();
+
+        assert_eq!(
+            summary,
+            Summary {
+                published: 0,
+                failed: 0
+            }
+        );
+        assert!(!db.is_dirty());
+        assert!(db.post(&PostId(0)).platforms.iter().next().is_none());
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("2025-05-01 18:00 on Facebook: would publish"));
+        assert!(out.contains("    | 🇬🇧 Red fox"));
+        assert!(out.contains("      alt: Red fox at dusk"));
+    }
+}
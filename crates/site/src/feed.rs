@@ -0,0 +1,474 @@
+//! Atom and JSON Feed documents of published posts.
+//!
+//! Feeds are written to the `feeds` directory of the gallery:
+//!
+//! * `all` — all posts, with descriptions in both languages;
+//! * `pl`, `en` — all posts, described in one language;
+//! * `tags/<tag>` — posts with the tag;
+//! * `categories/<category>` — posts showing species of the category.
+//!
+//! Each feed is available as `.xml` (Atom) and `.json` (JSON Feed 1.1).
+//! A post is dated with the time it was published; posts marked as
+//! published without the time use the midnight (UTC) of their date.
+
+use crate::Output;
+use crate::Site;
+use crate::Slugs;
+use crate::html::escape;
+use crate::html::escape_lines;
+use crate::html::slug;
+use crate::non_empty;
+use crate::tag_title;
+use crate::title;
+use chrono::DateTime;
+use chrono::SecondsFormat;
+use chrono::Utc;
+use db::Post;
+use db::PublishedState;
+use db::TranslatedTag;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+pub const DIR: &str = "feeds";
+
+const MIME_TYPE: &str = "image/jpeg";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Language {
+    Both,
+    Pl,
+    En,
+}
+
+impl Language {
+    const fn code(&self) -> Option<&'static str> {
+        match self {
+            Self::Both => None,
+            Self::Pl => Some("pl"),
+            Self::En => Some("en"),
+        }
+    }
+}
+
+/// Feed entry, all links are absolute.
+struct Entry {
+    url: String,
+    title: String,
+    content: String,
+    published: String,
+    categories: Vec<String>,
+    /// URLs and sizes of photos
+    enclosures: Vec<(String, Option<u64>)>,
+}
+
+/// Feed metadata; `path` is relative to the gallery and has no extension.
+struct Feed<'a> {
+    path: &'a str,
+    title: &'a str,
+    /// Page of the gallery showing the same posts
+    home: &'a str,
+    language: Language,
+}
+
+/// HTML `<link>` elements announcing the main feed.
+pub fn links(root: &str, title: &str) -> String {
+    let title = escape(title);
+    format!(
+        "<link rel=\"alternate\" type=\"application/atom+xml\" title=\"{title}\" href=\"{root}{DIR}/all.xml\">\n\
+         <link rel=\"alternate\" type=\"application/feed+json\" title=\"{title}\" href=\"{root}{DIR}/all.json\">"
+    )
+}
+
+/// Time the post was published.
+pub fn published(post: &Post) -> Option<DateTime<Utc>> {
+    match &post.published {
+        PublishedState::Timestamp(time) => Some(time.with_timezone(&Utc)),
+        PublishedState::Published => Some(post.date.to_naive()?.and_hms_opt(0, 0, 0)?.and_utc()),
+        PublishedState::None => None,
+    }
+}
+
+impl Site<'_> {
+    pub(crate) fn feeds(&self, url: &str, out: &mut Output) -> Result<(), String> {
+        let url = if url.ends_with('/') {
+            url.to_owned()
+        } else {
+            format!("{url}/")
+        };
+
+        let site_title = self.options.title.as_str();
+        let all: Vec<usize> = (0..self.posts.len()).collect();
+        for (name, language) in [
+            ("all", Language::Both),
+            ("pl", Language::Pl),
+            ("en", Language::En),
+        ] {
+            let feed = Feed {
+                path: &format!("{DIR}/{name}"),
+                title: site_title,
+                home: "index.html",
+                language,
+            };
+            self.feed(&feed, &all, &url, out)?;
+        }
+
+        for (tag, (name, posts)) in &self.tags {
+            let feed = Feed {
+                path: &format!("{DIR}/tags/{name}"),
+                title: &format!("{site_title}: #{}", tag_title(tag)),
+                home: &format!("tags/{name}.html"),
+                language: Language::Both,
+            };
+            self.feed(&feed, posts, &url, out)?;
+        }
+
+        let mut categories = BTreeMap::<&str, Vec<usize>>::new();
+        for (index, (post, _)) in self.posts.iter().enumerate() {
+            let species = post
+                .all_species()
+                .filter_map(|latin| self.db.species_by_latin(latin));
+            for category in species.filter_map(|species| species.category.as_deref()) {
+                let list = categories.entry(category).or_default();
+                if list.last() != Some(&index) {
+                    list.push(index);
+                }
+            }
+        }
+
+        let mut slugs = Slugs::default();
+        for (category, posts) in categories {
+            let name = slugs.unique(non_empty(slug(category), "category"));
+            let feed = Feed {
+                path: &format!("{DIR}/categories/{name}"),
+                title: &format!("{site_title}: {category}"),
+                home: "species/index.html",
+                language: Language::Both,
+            };
+            self.feed(&feed, &posts, &url, out)?;
+        }
+
+        Ok(())
+    }
+
+    fn feed(
+        &self,
+        feed: &Feed,
+        posts: &[usize],
+        url: &str,
+        out: &mut Output,
+    ) -> Result<(), String> {
+        let mut posts: Vec<(usize, DateTime<Utc>)> = posts
+            .iter()
+            .filter_map(|index| Some((*index, published(self.posts[*index].0)?)))
+            .collect();
+        posts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        posts.truncate(self.options.feed_size);
+
+        let entries: Vec<Entry> = posts
+            .iter()
+            .map(|(index, time)| self.entry(*index, time, feed.language, url, out))
+            .collect();
+
+        let atom = atom(feed, &entries, url);
+        out.write(&format!("{}.xml", feed.path), atom.as_bytes())?;
+
+        let json = json_feed(feed, &entries, url);
+        out.write(&format!("{}.json", feed.path), json.as_bytes())
+    }
+
+    fn entry(
+        &self,
+        index: usize,
+        time: &DateTime<Utc>,
+        language: Language,
+        url: &str,
+        out: &Output,
+    ) -> Entry {
+        let (post, name) = &self.posts[index];
+        let species = post
+            .species
+            .as_ref()
+            .and_then(|latin| self.db.species_by_latin(latin));
+
+        let descriptions = match language {
+            Language::Both => vec![("pl", &post.pl), ("en", &post.en)],
+            Language::Pl => vec![("pl", &post.pl)],
+            Language::En => vec![("en", &post.en)],
+        };
+
+        let mut content = String::new();
+        for (lang, text) in &descriptions {
+            if !text.is_empty() {
+                content.push_str(&format!("<p lang=\"{lang}\">{}</p>\n", escape_lines(text)));
+            }
+        }
+
+        for latin in post.all_species() {
+            let mut names = format!("<i>{}</i>", escape(latin.as_str()));
+            if let Some(species) = self.db.species_by_latin(latin) {
+                for (lang, name) in [("pl", &species.pl), ("en", &species.en)] {
+                    if !name.is_empty() && language.code().is_none_or(|code| code == lang) {
+                        names.push_str(&format!(" <span lang=\"{lang}\">{}</span>", escape(name)));
+                    }
+                }
+            }
+            content.push_str(&format!("<p>{names}</p>\n"));
+        }
+
+        let mut enclosures = Vec::with_capacity(post.files.len());
+        for n in 0..post.files.len() {
+            let path = format!("images/{name}-{}.jpg", n + 1);
+            let length = std::fs::metadata(out.dir.join(&path))
+                .ok()
+                .map(|metadata| metadata.len());
+            let href = format!("{url}{path}");
+
+            content.push_str(&format!(
+                "<p><img src=\"{href}\" alt=\"{}\"></p>\n",
+                escape(&self.db.alt_text(post, n))
+            ));
+            enclosures.push((href, length));
+        }
+
+        let title = match language {
+            Language::Both => title(post),
+            Language::Pl => line_or(&post.pl, species.map(|s| s.pl.as_str()), post),
+            Language::En => line_or(&post.en, species.map(|s| s.en.as_str()), post),
+        };
+
+        let mut categories = Vec::new();
+        for tag in post.tags.iter() {
+            if self.db.ignored_tags.contains(tag) {
+                continue;
+            }
+
+            let category = match (language, self.db.tag_translations.as_tag(tag)) {
+                (Language::Pl, TranslatedTag::Translation(trans)) if !trans.pl.is_empty() => {
+                    trans.pl
+                }
+                (Language::En, TranslatedTag::Translation(trans)) if !trans.en.is_empty() => {
+                    trans.en
+                }
+                _ => tag.clone(),
+            };
+            if !categories.contains(&category) {
+                categories.push(category);
+            }
+        }
+
+        Entry {
+            url: format!("{url}posts/{name}.html"),
+            title,
+            content,
+            published: time.to_rfc3339_opts(SecondsFormat::Secs, true),
+            categories,
+            enclosures,
+        }
+    }
+}
+
+/// The first line of the description, the name of species or the date.
+fn line_or(description: &str, species: Option<&str>, post: &Post) -> String {
+    match description.lines().next().map(str::trim) {
+        Some(line) if !line.is_empty() => line.to_owned(),
+        _ => match species.filter(|name| !name.is_empty()) {
+            Some(name) => name.to_owned(),
+            None => post.date.to_string(),
+        },
+    }
+}
+
+fn atom(feed: &Feed, entries: &[Entry], url: &str) -> String {
+    let self_url = format!("{url}{}.xml", feed.path);
+    let updated = entries
+        .iter()
+        .map(|entry| entry.published.as_str())
+        .max()
+        .unwrap_or("1970-01-01T00:00:00Z");
+
+    let mut result = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    match feed.language.code() {
+        Some(code) => result.push_str(&format!(
+            "<feed xmlns=\"http://www.w3.org/2005/Atom\" xml:lang=\"{code}\">\n"
+        )),
+        None => result.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n"),
+    }
+    result.push_str(&format!("<title>{}</title>\n", escape(feed.title)));
+    result.push_str(&format!("<id>{}</id>\n", escape(&self_url)));
+    result.push_str(&format!(
+        "<link rel=\"self\" href=\"{}\"/>\n",
+        escape(&self_url)
+    ));
+    result.push_str(&format!(
+        "<link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>\n",
+        escape(&format!("{url}{}", feed.home))
+    ));
+    result.push_str(&format!("<updated>{updated}</updated>\n"));
+    result.push_str(&format!(
+        "<author><name>{}</name></author>\n",
+        escape(feed.title)
+    ));
+
+    for entry in entries {
+        result.push_str("<entry>\n");
+        result.push_str(&format!("<title>{}</title>\n", escape(&entry.title)));
+        result.push_str(&format!("<id>{}</id>\n", escape(&entry.url)));
+        result.push_str(&format!(
+            "<link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>\n",
+            escape(&entry.url)
+        ));
+        result.push_str(&format!("<published>{}</published>\n", entry.published));
+        result.push_str(&format!("<updated>{}</updated>\n", entry.published));
+        for category in &entry.categories {
+            result.push_str(&format!("<category term=\"{}\"/>\n", escape(category)));
+        }
+        for (href, length) in &entry.enclosures {
+            let length = match length {
+                Some(length) => format!(" length=\"{length}\""),
+                None => String::new(),
+            };
+            result.push_str(&format!(
+                "<link rel=\"enclosure\" type=\"{MIME_TYPE}\" href=\"{}\"{length}/>\n",
+                escape(href)
+            ));
+        }
+        result.push_str(&format!(
+            "<content type=\"html\">{}</content>\n",
+            escape(&entry.content)
+        ));
+        result.push_str("</entry>\n");
+    }
+
+    result.push_str("</feed>\n");
+
+    result
+}
+
+fn json_feed(feed: &Feed, entries: &[Entry], url: &str) -> String {
+    let items: Vec<_> = entries
+        .iter()
+        .map(|entry| {
+            let attachments: Vec<_> = entry
+                .enclosures
+                .iter()
+                .map(|(href, length)| match length {
+                    Some(length) => json!({
+                        "url": href,
+                        "mime_type": MIME_TYPE,
+                        "size_in_bytes": length,
+                    }),
+                    None => json!({
+                        "url": href,
+                        "mime_type": MIME_TYPE,
+                    }),
+                })
+                .collect();
+
+            let mut item = json!({
+                "id": entry.url,
+                "url": entry.url,
+                "title": entry.title,
+                "content_html": entry.content,
+                "date_published": entry.published,
+                "tags": entry.categories,
+                "attachments": attachments,
+            });
+            if let Some((href, _)) = entry.enclosures.first() {
+                item["image"] = json!(href);
+            }
+
+            item
+        })
+        .collect();
+
+    let mut result = json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": feed.title,
+        "home_page_url": format!("{url}{}", feed.home),
+        "feed_url": format!("{url}{}.json", feed.path),
+        "authors": [{"name": feed.title}],
+        "items": items,
+    });
+    if let Some(code) = feed.language.code() {
+        result["language"] = json!(code);
+    }
+
+    serde_json::to_string_pretty(&result).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Options;
+    use crate::generate;
+    use crate::generate_feeds;
+    use crate::test::database;
+    use db::PostId;
+    use serde_json::Value;
+
+    #[test]
+    fn test_feeds() {
+        let dir = std::env::temp_dir().join(format!("site-feeds-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut db = database(&dir);
+        let time = DateTime::parse_from_rfc3339("2025-06-05T18:30:00+02:00").unwrap();
+        db.post_mut(&PostId(1)).published = PublishedState::Timestamp(time.into());
+        db.post_mut(&PostId(1)).en = "Red fox & cubs".to_owned();
+        let mut species = db.species[0].clone();
+        species.category = Some("Ssaki".to_owned());
+        db.update_species(&species);
+        db.refresh_caches();
+
+        let mut options = Options::new(&dir.join("www"));
+        options.url = Some("https://example.com/photos".to_owned());
+        options.thumbnail_size = 16;
+        generate(&db, &options).unwrap();
+
+        let read = |path: &str| std::fs::read_to_string(options.output.join(path)).unwrap();
+
+        let atom = read("feeds/all.xml");
+        assert!(atom.contains("<title>Red fox &amp; cubs</title>"));
+        assert!(atom.contains("<id>https://example.com/photos/posts/2025-06-02-lis-2.html</id>"));
+        assert!(atom.contains("<published>2025-06-05T16:30:00Z</published>"));
+        assert!(atom.contains("<published>2025-05-01T00:00:00Z</published>"));
+        assert!(atom.contains("<category term=\"las\"/>"));
+        assert!(atom.contains(
+            "<link rel=\"enclosure\" type=\"image/jpeg\" href=\"https://example.com/photos/images/2025-06-02-lis-2-1.jpg\" length=\""
+        ));
+        assert!(atom.contains("&lt;p lang=&quot;pl&quot;&gt;Lis &amp;lt;lis 2&amp;gt;&lt;/p&gt;"));
+        assert!(atom.find("lis-2.html").unwrap() < atom.find("lis-1.html").unwrap());
+        assert!(!atom.contains("draft"));
+
+        let json: Value = serde_json::from_str(&read("feeds/en.json")).unwrap();
+        assert_eq!(json["language"], "en");
+        assert_eq!(json["items"].as_array().unwrap().len(), 2);
+        assert_eq!(json["items"][0]["title"], "Red fox & cubs");
+        assert_eq!(json["items"][0]["tags"][0], "forest");
+        assert_eq!(json["items"][1]["title"], "red fox");
+        assert_eq!(
+            json["items"][0]["attachments"][0]["url"],
+            "https://example.com/photos/images/2025-06-02-lis-2-1.jpg"
+        );
+
+        assert!(read("feeds/tags/forest.xml").contains("<title>Photos: #forest / las</title>"));
+        assert!(read("feeds/categories/ssaki.json").contains("\"title\": \"Photos: Ssaki\""));
+        assert!(read("index.html").contains("href=\"feeds/all.xml\""));
+
+        db.post_mut(&PostId(1)).en.clear();
+        let summary = generate_feeds(&db, &options).unwrap();
+        assert!(summary.written > 0);
+        assert_eq!(summary.removed, 0);
+        assert_eq!(summary.resized, 0);
+        assert!(options.output.join("index.html").exists());
+
+        let summary = generate(&db, &options).unwrap();
+        assert_eq!(summary.removed, 0);
+        assert_eq!(summary.resized, 0);
+
+        options.url = None;
+        assert!(generate_feeds(&db, &options).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
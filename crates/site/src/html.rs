@@ -0,0 +1,61 @@
+use db::text::fold;
+
+pub fn escape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// Escapes the text, keeping line breaks.
+pub fn escape_lines(s: &str) -> String {
+    s.lines().map(escape).collect::<Vec<_>>().join("<br>\n")
+}
+
+/// Converts the text into a part of a file name, like "zolw-blotny".
+pub fn slug(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in fold(s).chars() {
+        if c.is_ascii_alphanumeric() {
+            result.push(c);
+        } else if !result.is_empty() && !result.ends_with('-') {
+            result.push('-');
+        }
+    }
+
+    while result.ends_with('-') {
+        result.pop();
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+        assert_eq!(escape_lines("Lis\n<rudy>"), "Lis<br>\n&lt;rudy&gt;");
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("Żółw błotny"), "zolw-blotny");
+        assert_eq!(slug("  IMG_0012 (1) "), "img-0012-1");
+        assert_eq!(slug("???"), "");
+    }
+}
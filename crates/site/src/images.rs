@@ -0,0 +1,38 @@
+use image::ImageReader;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+const QUALITY: u8 = 85;
+
+/// Writes a JPEG copy of the photo which fits into a square of the given
+/// size; smaller photos are not enlarged.
+pub fn resize(source: &Path, target: &Path, size: u32) -> Result<(), String> {
+    let error = |err: &dyn std::fmt::Display| format!("{}: {err}", source.display());
+
+    let img = ImageReader::open(source)
+        .map_err(|err| error(&err))?
+        .with_guessed_format()
+        .map_err(|err| error(&err))?
+        .decode()
+        .map_err(|err| error(&err))?;
+
+    let img = if img.width() > size || img.height() > size {
+        img.resize(size, size, FilterType::Lanczos3)
+    } else {
+        img
+    };
+
+    if let Some(dir) = target.parent() {
+        std::fs::create_dir_all(dir).map_err(|err| error(&err))?;
+    }
+
+    let file = File::create(target).map_err(|err| format!("{}: {err}", target.display()))?;
+    let encoder = JpegEncoder::new_with_quality(BufWriter::new(file), QUALITY);
+
+    img.to_rgb8()
+        .write_with_encoder(encoder)
+        .map_err(|err| format!("{}: {err}", target.display()))
+}
@@ -0,0 +1,797 @@
+//! Static web gallery of published posts.
+//!
+//! The output directory gets pages of posts, species, tags and months,
+//! paginated indexes and resized photos. Generated files are recorded in
+//! a manifest, thus the next run writes only files whose contents changed,
+//! resizes only photos which changed and removes files of posts which are
+//! no longer published.
+//!
+//! When the public address of the gallery is known, Atom and JSON Feed
+//! documents are generated as well, see [`feed`].
+
+pub mod feed;
+mod html;
+mod images;
+mod manifest;
+pub mod theme;
+
+pub use theme::Theme;
+
+use db::Database;
+use db::Latin;
+use db::Month;
+use db::Post;
+use db::PostId;
+use db::TranslatedTag;
+use db::Year;
+use html::escape;
+use html::escape_lines;
+use html::slug;
+use manifest::Manifest;
+use manifest::fingerprint;
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+use theme::render;
+
+pub struct Options {
+    pub output: PathBuf,
+    /// Title of the main page and feeds
+    pub title: String,
+    /// Public address of the gallery, like `https://example.com/photos/`;
+    /// feeds need absolute links, thus are generated only when it is known
+    pub url: Option<String>,
+    /// Directory with templates overriding the built-in ones
+    pub theme: Option<PathBuf>,
+    /// Number of posts on an index page
+    pub page_size: usize,
+    /// The longer side of photos shown on post pages
+    pub image_size: u32,
+    /// The longer side of photos shown on index pages
+    pub thumbnail_size: u32,
+    /// Number of the latest posts in a feed
+    pub feed_size: usize,
+    /// Posts to render, like those of a saved search; unpublished ones
+    /// are skipped anyway. All posts when `None`.
+    pub posts: Option<Vec<PostId>>,
+}
+
+impl Options {
+    pub fn new(output: &Path) -> Self {
+        Self {
+            output: output.to_path_buf(),
+            title: "Photos".to_owned(),
+            url: None,
+            theme: None,
+            page_size: 24,
+            image_size: 1600,
+            thumbnail_size: 400,
+            feed_size: 50,
+            posts: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub written: usize,
+    pub unchanged: usize,
+    pub resized: usize,
+    pub removed: usize,
+}
+
+/// Renders published posts of the database into the output directory.
+pub fn generate(db: &Database, options: &Options) -> Result<Summary, String> {
+    let theme = Theme::load(options.theme.as_deref())?;
+    let site = Site::new(db, &theme, options);
+
+    std::fs::create_dir_all(&options.output)
+        .map_err(|err| format!("{}: {err}", options.output.display()))?;
+
+    let mut out = Output::new(&options.output);
+    site.render(&mut out)?;
+    if let Some(url) = &options.url {
+        site.feeds(url, &mut out)?;
+    }
+
+    out.finish()
+}
+
+/// Renders only the feeds, the remaining files of the gallery are kept.
+pub fn generate_feeds(db: &Database, options: &Options) -> Result<Summary, String> {
+    let Some(url) = &options.url else {
+        return Err("feeds require the public address of the gallery".to_owned());
+    };
+
+    let theme = Theme::default();
+    let site = Site::new(db, &theme, options);
+
+    std::fs::create_dir_all(&options.output)
+        .map_err(|err| format!("{}: {err}", options.output.display()))?;
+
+    let mut out = Output::partial(&options.output, feed::DIR);
+    site.feeds(url, &mut out)?;
+
+    out.finish()
+}
+
+// --------------------------------------------------
+
+struct Site<'a> {
+    db: &'a Database,
+    theme: &'a Theme,
+    options: &'a Options,
+    /// Published posts, the newest first, with their slugs
+    posts: Vec<(&'a Post, String)>,
+    /// Slugs and posts of tags; translations of a tag share the page
+    tags: BTreeMap<TranslatedTag, (String, Vec<usize>)>,
+    species: BTreeMap<&'a Latin, (String, Vec<usize>)>,
+    months: BTreeMap<(Year, Month), Vec<usize>>,
+}
+
+impl<'a> Site<'a> {
+    fn new(db: &'a Database, theme: &'a Theme, options: &'a Options) -> Self {
+        let selected: Option<HashSet<PostId>> = options
+            .posts
+            .as_ref()
+            .map(|ids| ids.iter().copied().collect());
+        let mut posts: Vec<&Post> = db
+            .posts
+            .iter()
+            .filter(|post| post.is_published())
+            .filter(|post| selected.as_ref().is_none_or(|ids| ids.contains(&post.id)))
+            .collect();
+        posts.sort_by(|a, b| b.date.cmp(&a.date).then(b.id.cmp(&a.id)));
+
+        let mut slugs = Slugs::default();
+        let posts: Vec<(&Post, String)> = posts
+            .into_iter()
+            .map(|post| {
+                let stem = post
+                    .files
+                    .first()
+                    .and_then(|file| file.rel_path.file_stem())
+                    .map(|stem| slug(&stem.to_string_lossy()))
+                    .unwrap_or_default();
+                let name = if stem.is_empty() {
+                    post.date.to_string()
+                } else {
+                    format!("{}-{stem}", post.date)
+                };
+
+                (post, slugs.unique(name))
+            })
+            .collect();
+
+        let mut tags = BTreeMap::<TranslatedTag, (String, Vec<usize>)>::new();
+        let mut species = BTreeMap::<&Latin, (String, Vec<usize>)>::new();
+        let mut months = BTreeMap::<(Year, Month), Vec<usize>>::new();
+        for (index, (post, _)) in posts.iter().enumerate() {
+            for tag in post.tags.iter() {
+                if db.ignored_tags.contains(tag) {
+                    continue;
+                }
+
+                let (_, list) = tags.entry(db.tag_translations.as_tag(tag)).or_default();
+                if list.last() != Some(&index) {
+                    list.push(index);
+                }
+            }
+
+            for latin in post.all_species() {
+                species.entry(latin).or_default().1.push(index);
+            }
+
+            months
+                .entry((post.date.year, post.date.month))
+                .or_default()
+                .push(index);
+        }
+
+        let mut slugs = Slugs::reserved();
+        for (tag, (name, _)) in tags.iter_mut() {
+            *name = slugs.unique(non_empty(slug(tag.base()), "tag"));
+        }
+
+        let mut slugs = Slugs::reserved();
+        for (latin, (name, _)) in species.iter_mut() {
+            *name = slugs.unique(non_empty(slug(latin.as_str()), "species"));
+        }
+
+        Self {
+            db,
+            theme,
+            options,
+            posts,
+            tags,
+            species,
+            months,
+        }
+    }
+
+    fn render(&self, out: &mut Output) -> Result<(), String> {
+        out.write(theme::STYLE, self.theme.style.as_bytes())?;
+
+        for index in 0..self.posts.len() {
+            self.post(index, out)?;
+        }
+
+        let all: Vec<usize> = (0..self.posts.len()).collect();
+        self.list("index", &self.options.title, &all, out)?;
+
+        self.tags(out)?;
+        self.months(out)?;
+        self.species(out)
+    }
+
+    fn post(&self, index: usize, out: &mut Output) -> Result<(), String> {
+        let (post, name) = &self.posts[index];
+
+        let mut photos = String::new();
+        for (n, file) in post.files.iter().enumerate() {
+            let image = format!("images/{name}-{}.jpg", n + 1);
+            out.image(&file.full_path, &image, self.options.image_size)?;
+            if n == 0 {
+                let thumbnail = format!("thumbs/{name}.jpg");
+                out.image(&file.full_path, &thumbnail, self.options.thumbnail_size)?;
+            }
+
+            photos.push_str(&format!(
+                "<figure><img src=\"../{image}\" alt=\"{}\"></figure>\n",
+                escape(&self.db.alt_text(post, n))
+            ));
+        }
+
+        let mut description = String::new();
+        for (lang, text) in [("pl", &post.pl), ("en", &post.en)] {
+            if !text.is_empty() {
+                description.push_str(&format!("<p lang=\"{lang}\">{}</p>\n", escape_lines(text)));
+            }
+        }
+
+        let mut species = String::new();
+        for latin in post.all_species() {
+            if let Some((name, _)) = self.species.get(latin) {
+                species.push_str(&format!(
+                    "<p class=\"species\"><a href=\"../species/{name}.html\">{}</a></p>\n",
+                    self.species_names(latin)
+                ));
+            }
+        }
+
+        let mut tags = String::new();
+        for tag in post.tags.iter() {
+            let Some((name, _)) = self.tags.get(&self.db.tag_translations.as_tag(tag)) else {
+                continue;
+            };
+
+            tags.push_str(&format!(
+                "<li><a href=\"../tags/{name}.html\">#{}</a></li>\n",
+                escape(tag)
+            ));
+        }
+        if !tags.is_empty() {
+            tags = format!("<ul class=\"tags\">\n{tags}</ul>");
+        }
+
+        let content = render(
+            &self.theme.post,
+            &[
+                ("date", &post.date.to_string()),
+                ("month_url", &format!("../months/{}.html", month_slug(post))),
+                ("photos", &photos),
+                ("description", &description),
+                ("species", &species),
+                ("tags", &tags),
+            ],
+        )?;
+
+        self.page(&format!("posts/{name}.html"), &title(post), &content, out)
+    }
+
+    /// Paginated list of posts, the first page is `base.html`, the next
+    /// ones `base-2.html`, `base-3.html` and so on.
+    fn list(
+        &self,
+        base: &str,
+        title: &str,
+        posts: &[usize],
+        out: &mut Output,
+    ) -> Result<(), String> {
+        let root = "../".repeat(base.matches('/').count());
+        let file_name = base.rsplit('/').next().unwrap_or(base);
+
+        let chunks: Vec<&[usize]> = posts.chunks(self.options.page_size.max(1)).collect();
+        let count = chunks.len().max(1);
+        for page in 1..=count {
+            let chunk = chunks.get(page - 1).copied().unwrap_or_default();
+            let content = render(
+                &self.theme.list,
+                &[
+                    ("content", &self.grid(chunk, &root)),
+                    ("pagination", &pagination(file_name, page, count)),
+                ],
+            )?;
+
+            let title = if page == 1 {
+                title.to_owned()
+            } else {
+                format!("{title} ({page}/{count})")
+            };
+
+            self.page(&page_path(base, page), &title, &content, out)?;
+        }
+
+        Ok(())
+    }
+
+    fn tags(&self, out: &mut Output) -> Result<(), String> {
+        let mut index = String::new();
+        for (tag, (name, posts)) in &self.tags {
+            let title = tag_title(tag);
+            self.list(&format!("tags/{name}"), &format!("#{title}"), posts, out)?;
+
+            index.push_str(&format!(
+                "<li><a href=\"{name}.html\">{}</a> ({})</li>\n",
+                escape(&title),
+                posts.len()
+            ));
+        }
+
+        self.index("tags/index.html", "Tags", &index, out)
+    }
+
+    fn months(&self, out: &mut Output) -> Result<(), String> {
+        let mut index = String::new();
+        for ((year, month), posts) in self.months.iter().rev() {
+            let name = format!("{year:04}-{:02}", month.as_u8());
+            let title = format!("{month} {year}");
+            self.list(&format!("months/{name}"), &title, posts, out)?;
+
+            index.push_str(&format!(
+                "<li><a href=\"{name}.html\">{title}</a> ({})</li>\n",
+                posts.len()
+            ));
+        }
+
+        self.index("months/index.html", "Months", &index, out)
+    }
+
+    fn species(&self, out: &mut Output) -> Result<(), String> {
+        let mut index = String::new();
+        for (latin, (name, posts)) in &self.species {
+            let mut links = String::new();
+            if let Some(species) = self.db.species_by_latin(latin) {
+                for (url, label) in [
+                    (&species.wikipedia_pl, "Wikipedia (pl)"),
+                    (&species.wikipedia_en, "Wikipedia (en)"),
+                    (&species.insektarium_pl, "Insektarium"),
+                ] {
+                    if !url.is_empty() {
+                        links.push_str(&format!(
+                            "<li><a href=\"{}\">{label}</a></li>\n",
+                            escape(url)
+                        ));
+                    }
+                }
+            }
+            if !links.is_empty() {
+                links = format!("<ul class=\"links\">\n{links}</ul>");
+            }
+
+            let names = self.species_names(latin);
+            let content = render(
+                &self.theme.species,
+                &[
+                    ("names", &format!("<p class=\"names\">{names}</p>")),
+                    ("links", &links),
+                    ("posts", &self.grid(posts, "../")),
+                ],
+            )?;
+
+            self.page(
+                &format!("species/{name}.html"),
+                latin.as_str(),
+                &content,
+                out,
+            )?;
+
+            index.push_str(&format!(
+                "<li><a href=\"{name}.html\">{names}</a> ({})</li>\n",
+                posts.len()
+            ));
+        }
+
+        self.index("species/index.html", "Species", &index, out)
+    }
+
+    /// Unpaginated list of links.
+    fn index(&self, path: &str, title: &str, items: &str, out: &mut Output) -> Result<(), String> {
+        let content = render(
+            &self.theme.list,
+            &[
+                ("content", &format!("<ul class=\"index\">\n{items}</ul>")),
+                ("pagination", ""),
+            ],
+        )?;
+
+        self.page(path, title, &content, out)
+    }
+
+    fn page(&self, path: &str, title: &str, content: &str, out: &mut Output) -> Result<(), String> {
+        let root = "../".repeat(path.matches('/').count());
+        let feeds = match self.options.url {
+            Some(_) => feed::links(&root, &self.options.title),
+            None => String::new(),
+        };
+
+        let html = render(
+            &self.theme.layout,
+            &[
+                ("title", &escape(title)),
+                ("root", &root),
+                ("feeds", &feeds),
+                ("content", content),
+            ],
+        )?;
+
+        out.write(path, html.as_bytes())
+    }
+
+    fn grid(&self, posts: &[usize], root: &str) -> String {
+        let mut result = String::from("<ul class=\"grid\">\n");
+        for index in posts {
+            let (post, name) = &self.posts[*index];
+            let title = escape(&title(post));
+            let img = if post.files.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    "<img src=\"{root}thumbs/{name}.jpg\" alt=\"{}\" loading=\"lazy\">",
+                    escape(&self.db.alt_text(post, 0))
+                )
+            };
+
+            result.push_str(&format!(
+                "<li><a href=\"{root}posts/{name}.html\">{img}<span>{title}</span></a></li>\n"
+            ));
+        }
+        result.push_str("</ul>");
+
+        result
+    }
+
+    /// Latin name followed by the Polish and English ones.
+    fn species_names(&self, latin: &Latin) -> String {
+        let mut result = format!("<i>{}</i>", escape(latin.as_str()));
+        if let Some(species) = self.db.species_by_latin(latin) {
+            for (lang, name) in [("pl", &species.pl), ("en", &species.en)] {
+                if !name.is_empty() {
+                    result.push_str(&format!(" <span lang=\"{lang}\">{}</span>", escape(name)));
+                }
+            }
+        }
+
+        result
+    }
+}
+
+fn title(post: &Post) -> String {
+    let description = if post.en.is_empty() {
+        &post.pl
+    } else {
+        &post.en
+    };
+    match description.lines().next() {
+        Some(line) if !line.trim().is_empty() => line.trim().to_owned(),
+        _ => post.date.to_string(),
+    }
+}
+
+fn tag_title(tag: &TranslatedTag) -> String {
+    match tag {
+        TranslatedTag::Untranslated(tag) => tag.clone(),
+        TranslatedTag::Translation(trans) if trans.pl.is_empty() || trans.pl == trans.en => {
+            trans.en.clone()
+        }
+        TranslatedTag::Translation(trans) if trans.en.is_empty() => trans.pl.clone(),
+        TranslatedTag::Translation(trans) => format!("{} / {}", trans.en, trans.pl),
+    }
+}
+
+fn month_slug(post: &Post) -> String {
+    format!("{:04}-{:02}", post.date.year, post.date.month.as_u8())
+}
+
+fn page_path(base: &str, page: usize) -> String {
+    if page == 1 {
+        format!("{base}.html")
+    } else {
+        format!("{base}-{page}.html")
+    }
+}
+
+fn pagination(file_name: &str, page: usize, count: usize) -> String {
+    if count < 2 {
+        return String::new();
+    }
+
+    let link = |n: usize, label: &str| {
+        let href = page_path(file_name, n);
+        format!("<a href=\"{href}\">{label}</a>\n")
+    };
+
+    let mut result = String::from("<nav class=\"pagination\">\n");
+    if page > 1 {
+        result.push_str(&link(page - 1, "&laquo;"));
+    }
+    for n in 1..=count {
+        if n == page {
+            result.push_str(&format!("<span>{n}</span>\n"));
+        } else {
+            result.push_str(&link(n, &n.to_string()));
+        }
+    }
+    if page < count {
+        result.push_str(&link(page + 1, "&raquo;"));
+    }
+    result.push_str("</nav>");
+
+    result
+}
+
+fn non_empty(s: String, default: &str) -> String {
+    if s.is_empty() { default.to_owned() } else { s }
+}
+
+/// Names of files in a directory; repeated names get a numeric suffix.
+#[derive(Default)]
+struct Slugs(HashSet<String>);
+
+impl Slugs {
+    /// The index page shares the directory.
+    fn reserved() -> Self {
+        Self(HashSet::from(["index".to_owned()]))
+    }
+
+    fn unique(&mut self, base: String) -> String {
+        let mut name = base.clone();
+        let mut n = 1;
+        while !self.0.insert(name.clone()) {
+            n += 1;
+            name = format!("{base}-{n}");
+        }
+
+        name
+    }
+}
+
+// --------------------------------------------------
+
+struct Output<'a> {
+    dir: &'a Path,
+    previous: Manifest,
+    current: Manifest,
+    summary: Summary,
+}
+
+impl<'a> Output<'a> {
+    fn new(dir: &'a Path) -> Self {
+        Self {
+            dir,
+            previous: Manifest::load(dir),
+            current: Manifest::default(),
+            summary: Summary::default(),
+        }
+    }
+
+    /// Output which replaces only files in the directory, other files
+    /// recorded in the manifest are kept.
+    fn partial(dir: &'a Path, subdir: &str) -> Self {
+        let mut result = Self::new(dir);
+        let prefix = format!("{subdir}/");
+        for path in result.previous.paths() {
+            if !path.starts_with(&prefix) {
+                result
+                    .current
+                    .insert(path, result.previous.get(path).unwrap());
+            }
+        }
+
+        result
+    }
+
+    /// Writes the file unless it has not changed since the previous run.
+    fn write(&mut self, path: &str, contents: &[u8]) -> Result<(), String> {
+        let hash = fingerprint(contents);
+        let target = self.dir.join(path);
+        if self.previous.get(path) == Some(hash) && target.is_file() {
+            self.summary.unchanged += 1;
+        } else {
+            let error = |err: std::io::Error| format!("{}: {err}", target.display());
+            if let Some(dir) = target.parent() {
+                std::fs::create_dir_all(dir).map_err(error)?;
+            }
+            std::fs::write(&target, contents).map_err(error)?;
+            self.summary.written += 1;
+        }
+
+        self.current.insert(path, hash);
+
+        Ok(())
+    }
+
+    /// Resizes the photo unless it, or the requested size, has not
+    /// changed since the previous run.
+    fn image(&mut self, source: &Path, path: &str, size: u32) -> Result<(), String> {
+        let metadata =
+            std::fs::metadata(source).map_err(|err| format!("{}: {err}", source.display()))?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default();
+
+        let key = format!(
+            "{}\n{}\n{modified}\n{size}",
+            source.display(),
+            metadata.len()
+        );
+        let hash = fingerprint(key.as_bytes());
+
+        let target = self.dir.join(path);
+        if self.previous.get(path) != Some(hash) || !target.is_file() {
+            images::resize(source, &target, size)?;
+            self.summary.resized += 1;
+        }
+
+        self.current.insert(path, hash);
+
+        Ok(())
+    }
+
+    /// Removes files which were not generated in this run and saves
+    /// the manifest.
+    fn finish(self) -> Result<Summary, String> {
+        let mut summary = self.summary;
+        for path in self.previous.paths() {
+            if self.current.get(path).is_some() {
+                continue;
+            }
+
+            let target = self.dir.join(path);
+            match std::fs::remove_file(&target) {
+                Ok(()) => summary.removed += 1,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(format!("{}: {err}", target.display())),
+            }
+        }
+
+        self.current
+            .save(self.dir)
+            .map_err(|err| format!("{}: {err}", self.dir.display()))?;
+
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use db::Date;
+    use db::FileMetadata;
+    use db::PublishedState;
+    use db::Species;
+    use db::TagList;
+    use db::test_util::DatabaseBuilder;
+    use db::test_util::latin;
+    use db::test_util::red_fox;
+    use image::RgbImage;
+
+    /// Two published posts of foxes and a draft, with photos in the
+    /// directory.
+    pub(crate) fn database(dir: &Path) -> Database {
+        let mut builder = DatabaseBuilder::in_dir(dir)
+            .species(Species {
+                wikipedia_en: "https://en.wikipedia.org/wiki/Red_fox".to_owned(),
+                ..red_fox()
+            })
+            .translation("forest", "las");
+
+        for (name, date, published) in [
+            ("lis 1", "2025-05-01", true),
+            ("lis 2", "2025-06-02", true),
+            ("draft", "2025-06-03", false),
+        ] {
+            let rel_path = PathBuf::from(format!("{name}.jpg"));
+            RgbImage::new(64, 32).save(dir.join(&rel_path)).unwrap();
+
+            builder = builder.post(Post {
+                published: if published {
+                    PublishedState::Published
+                } else {
+                    PublishedState::None
+                },
+                files: vec![FileMetadata {
+                    rel_path,
+                    ..FileMetadata::default()
+                }],
+                date: date.parse::<Date>().unwrap(),
+                pl: format!("Lis <{name}>"),
+                species: Some(latin("Vulpes vulpes")),
+                tags: TagList(vec!["las".to_owned()]),
+                ..Post::default()
+            });
+        }
+
+        builder.build()
+    }
+
+    #[test]
+    fn test_generate() {
+        let dir = std::env::temp_dir().join(format!("site-generate-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut db = database(&dir);
+        let mut options = Options::new(&dir.join("www"));
+        options.page_size = 1;
+        options.thumbnail_size = 16;
+
+        let summary = generate(&db, &options).unwrap();
+        assert_eq!(summary.resized, 4);
+        assert_eq!(summary.removed, 0);
+
+        let read = |path: &str| std::fs::read_to_string(options.output.join(path)).unwrap();
+
+        let post = read("posts/2025-05-01-lis-1.html");
+        assert!(post.contains("<p lang=\"pl\">Lis &lt;lis 1&gt;</p>"));
+        assert!(post.contains("<a href=\"../species/vulpes-vulpes.html\">"));
+        assert!(post.contains("<a href=\"../tags/forest.html\">#las</a>"));
+        assert!(post.contains("<a href=\"../months/2025-05.html\">2025-05-01</a>"));
+        assert!(post.contains("alt=\"Red fox (Vulpes vulpes): forest\""));
+        assert!(post.contains("href=\"../style.css\""));
+
+        let index = read("index.html");
+        assert!(index.contains("posts/2025-06-02-lis-2.html"));
+        assert!(index.contains("<a href=\"index-2.html\">2</a>"));
+        assert!(read("index-2.html").contains("posts/2025-05-01-lis-1.html"));
+
+        let species = read("species/vulpes-vulpes.html");
+        assert!(species.contains("https://en.wikipedia.org/wiki/Red_fox"));
+        assert!(species.contains("<span lang=\"pl\">lis</span>"));
+
+        assert!(read("tags/index.html").contains("forest / las"));
+        assert!(read("months/index.html").contains("<a href=\"2025-06.html\">June 2025</a>"));
+        assert!(!options.output.join("posts/2025-06-03-draft.html").exists());
+
+        let summary = generate(&db, &options).unwrap();
+        assert_eq!(summary.written, 0);
+        assert_eq!(summary.resized, 0);
+
+        db.post_mut(&db::PostId(1)).en = "Red fox".to_owned();
+        let summary = generate(&db, &options).unwrap();
+        assert!(summary.written > 0);
+        assert_eq!(summary.resized, 0);
+
+        db.post_mut(&db::PostId(0)).published = PublishedState::None;
+        let summary = generate(&db, &options).unwrap();
+        assert_eq!(summary.removed, 6);
+
+        options.posts = Some(vec![db::PostId(0)]);
+        generate(&db, &options).unwrap();
+        assert!(!options.output.join("posts/2025-06-02-lis-2.html").exists());
+        options.posts = None;
+        assert!(!options.output.join("posts/2025-05-01-lis-1.html").exists());
+        assert!(
+            !options
+                .output
+                .join("images/2025-05-01-lis-1-1.jpg")
+                .exists()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
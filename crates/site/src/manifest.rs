@@ -0,0 +1,63 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub const FILE_NAME: &str = ".manifest";
+
+/// Generated files with fingerprints of their contents or sources,
+/// paths are relative to the output directory.
+#[derive(Default)]
+pub struct Manifest(BTreeMap<String, u64>);
+
+impl Manifest {
+    /// Reads the manifest; a missing or broken one means a full rebuild.
+    pub fn load(dir: &Path) -> Self {
+        let Ok(contents) = std::fs::read_to_string(dir.join(FILE_NAME)) else {
+            return Self::default();
+        };
+
+        let mut result = Self::default();
+        for line in contents.lines() {
+            let Some((fingerprint, path)) = line.split_once(' ') else {
+                continue;
+            };
+
+            if let Ok(fingerprint) = u64::from_str_radix(fingerprint, 16) {
+                result.0.insert(path.to_owned(), fingerprint);
+            }
+        }
+
+        result
+    }
+
+    pub fn save(&self, dir: &Path) -> std::io::Result<()> {
+        let mut contents = String::new();
+        for (path, fingerprint) in &self.0 {
+            contents.push_str(&format!("{fingerprint:016x} {path}\n"));
+        }
+
+        std::fs::write(dir.join(FILE_NAME), contents)
+    }
+
+    pub fn get(&self, path: &str) -> Option<u64> {
+        self.0.get(path).copied()
+    }
+
+    pub fn insert(&mut self, path: &str, fingerprint: u64) {
+        self.0.insert(path.to_owned(), fingerprint);
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(|path| path.as_str())
+    }
+}
+
+/// FNV-1a hash, stable between runs and compiler versions.
+pub fn fingerprint(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf29ce484222325_u64;
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    hash
+}
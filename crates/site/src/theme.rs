@@ -0,0 +1,169 @@
+//! Templates of the generated pages.
+//!
+//! A theme is a directory with any of the files listed in [`Theme`];
+//! missing files are taken from the built-in theme. Templates contain
+//! `{{name}}` placeholders which are replaced with HTML fragments; the
+//! available names are listed in [`TemplateFile::variables`].
+
+use std::path::Path;
+
+pub struct TemplateFile {
+    pub name: &'static str,
+    pub variables: &'static [&'static str],
+    builtin: &'static str,
+}
+
+/// Frame of every page.
+pub const LAYOUT: TemplateFile = TemplateFile {
+    name: "layout.html",
+    variables: &["title", "root", "feeds", "content"],
+    builtin: include_str!("../theme/layout.html"),
+};
+
+/// Single post with its photos.
+pub const POST: TemplateFile = TemplateFile {
+    name: "post.html",
+    variables: &[
+        "date",
+        "month_url",
+        "photos",
+        "description",
+        "species",
+        "tags",
+    ],
+    builtin: include_str!("../theme/post.html"),
+};
+
+/// Index pages: all posts, posts with a tag, posts from a month.
+pub const LIST: TemplateFile = TemplateFile {
+    name: "list.html",
+    variables: &["content", "pagination"],
+    builtin: include_str!("../theme/list.html"),
+};
+
+/// Species with links to external sites and its posts.
+pub const SPECIES: TemplateFile = TemplateFile {
+    name: "species.html",
+    variables: &["names", "links", "posts"],
+    builtin: include_str!("../theme/species.html"),
+};
+
+/// Stylesheet, copied verbatim.
+pub const STYLE: &str = "style.css";
+
+const BUILTIN_STYLE: &str = include_str!("../theme/style.css");
+
+pub struct Theme {
+    pub layout: String,
+    pub post: String,
+    pub list: String,
+    pub species: String,
+    pub style: String,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            layout: LAYOUT.builtin.to_owned(),
+            post: POST.builtin.to_owned(),
+            list: LIST.builtin.to_owned(),
+            species: SPECIES.builtin.to_owned(),
+            style: BUILTIN_STYLE.to_owned(),
+        }
+    }
+}
+
+impl Theme {
+    /// Reads templates from the directory and checks that they use only
+    /// known variables.
+    pub fn load(dir: Option<&Path>) -> Result<Self, String> {
+        let read = |name: &str| -> Result<Option<String>, String> {
+            let Some(path) = dir.map(|dir| dir.join(name)).filter(|path| path.is_file()) else {
+                return Ok(None);
+            };
+
+            std::fs::read_to_string(&path)
+                .map(Some)
+                .map_err(|err| format!("{}: {err}", path.display()))
+        };
+
+        let template = |file: &TemplateFile| -> Result<String, String> {
+            let text = read(file.name)?.unwrap_or_else(|| file.builtin.to_owned());
+            let values: Vec<(&str, &str)> = file.variables.iter().map(|var| (*var, "")).collect();
+            render(&text, &values).map_err(|err| format!("{}: {err}", file.name))?;
+
+            Ok(text)
+        };
+
+        Ok(Self {
+            layout: template(&LAYOUT)?,
+            post: template(&POST)?,
+            list: template(&LIST)?,
+            species: template(&SPECIES)?,
+            style: read(STYLE)?.unwrap_or_else(|| BUILTIN_STYLE.to_owned()),
+        })
+    }
+}
+
+/// Replaces placeholders with the values; values are not escaped.
+pub fn render(template: &str, values: &[(&str, &str)]) -> Result<String, String> {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        result.push_str(&rest[..start]);
+
+        let Some(len) = rest[start..].find("}}") else {
+            return Err("unclosed {{".to_owned());
+        };
+
+        let name = rest[start + 2..start + len].trim();
+        let Some((_, value)) = values.iter().find(|(var, _)| *var == name) else {
+            return Err(format!("unknown variable '{name}'"));
+        };
+
+        result.push_str(value);
+        rest = &rest[start + len + 2..];
+    }
+
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let values = [("title", "Red fox"), ("root", "../")];
+        assert_eq!(
+            render("<a href=\"{{root}}x.html\">{{ title }}</a>", &values),
+            Ok("<a href=\"../x.html\">Red fox</a>".to_owned())
+        );
+        assert_eq!(
+            render("{{content}}", &values),
+            Err("unknown variable 'content'".to_owned())
+        );
+        assert_eq!(render("{{title", &values), Err("unclosed {{".to_owned()));
+    }
+
+    #[test]
+    fn test_load() {
+        let dir = std::env::temp_dir().join(format!("site-theme-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(LIST.name), "<div>{{content}}</div>").unwrap();
+
+        let theme = Theme::load(Some(&dir)).unwrap();
+        assert_eq!(theme.list, "<div>{{content}}</div>");
+        assert_eq!(theme.post, POST.builtin);
+
+        std::fs::write(dir.join(POST.name), "{{pl}}").unwrap();
+        assert_eq!(
+            Theme::load(Some(&dir)).err(),
+            Some("post.html: unknown variable 'pl'".to_owned())
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
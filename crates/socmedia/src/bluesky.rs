@@ -0,0 +1,532 @@
+//! Publishing on Bluesky using the AT Protocol.
+//!
+//! A session is created with an app password, then each photo is uploaded
+//! as a blob, and finally an `app.bsky.feed.post` record embedding the
+//! blobs is created in the user's repository. Hashtags and links are not
+//! recognised by the server, the client has to describe them as facets,
+//! i.e. ranges of UTF-8 bytes of the text.
+
+use crate::Error;
+use crate::Media;
+use crate::Published;
+use crate::Result;
+use crate::RetryPolicy;
+use crate::http::Client;
+use crate::http::content_type;
+use crate::http::field;
+use chrono::SecondsFormat;
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+
+pub const SERVICE_URL: &str = "https://bsky.social";
+
+pub const MAX_IMAGES: usize = 4;
+/// Limit of the text length, in graphemes
+pub const MAX_GRAPHEMES: usize = 300;
+/// Limit of a single image size, in bytes
+pub const MAX_IMAGE_SIZE: u64 = 1_000_000;
+
+const MAX_TAG_CHARS: usize = 64;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    /// Handle (like `photos.bsky.social`) or e-mail
+    pub identifier: String,
+    /// App password, created in the account settings
+    pub app_password: String,
+    /// URL of the PDS hosting the account
+    #[serde(default = "default_service_url")]
+    pub service_url: String,
+}
+
+fn default_service_url() -> String {
+    SERVICE_URL.to_owned()
+}
+
+/// Checks the Bluesky limits; returns the list of problems.
+pub fn validate(files: usize, caption: &str) -> Vec<String> {
+    let mut errors = Vec::new();
+    if files == 0 {
+        errors.push("post has no photos".to_owned());
+    }
+
+    if files > MAX_IMAGES {
+        errors.push(format!(
+            "post has {files} photos, Bluesky accepts at most {MAX_IMAGES}"
+        ));
+    }
+
+    let graphemes = count_graphemes(caption);
+    if graphemes > MAX_GRAPHEMES {
+        errors.push(format!(
+            "text has {graphemes} characters, Bluesky accepts at most {MAX_GRAPHEMES}"
+        ));
+    }
+
+    errors
+}
+
+/// Approximates the number of user-perceived characters: combining marks,
+/// joiners, variation selectors and skin tones do not start a new one,
+/// and a pair of regional indicators forms a single flag.
+fn count_graphemes(s: &str) -> usize {
+    let mut count = 0;
+    let mut joined = false;
+    let mut regional = false;
+    for c in s.chars() {
+        let extends = matches!(c,
+            '\u{0300}'..='\u{036f}'
+            | '\u{200d}'
+            | '\u{fe00}'..='\u{fe0f}'
+            | '\u{1f3fb}'..='\u{1f3ff}'
+            | '\u{e0020}'..='\u{e007f}');
+        let is_regional = ('\u{1f1e6}'..='\u{1f1ff}').contains(&c);
+
+        if !(extends || joined || (is_regional && regional)) {
+            count += 1;
+        }
+
+        joined = c == '\u{200d}';
+        regional = is_regional && !regional;
+    }
+
+    count
+}
+
+/// Rich text annotation of the byte range `start..end`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Facet {
+    pub start: usize,
+    pub end: usize,
+    pub feature: Feature,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Feature {
+    /// Hashtag, without the leading `#`
+    Tag(String),
+    Link(String),
+}
+
+impl Facet {
+    fn to_json(&self) -> Value {
+        let feature = match &self.feature {
+            Feature::Tag(tag) => json!({
+                "$type": "app.bsky.richtext.facet#tag",
+                "tag": tag,
+            }),
+            Feature::Link(uri) => json!({
+                "$type": "app.bsky.richtext.facet#link",
+                "uri": uri,
+            }),
+        };
+
+        json!({
+            "index": {"byteStart": self.start, "byteEnd": self.end},
+            "features": [feature],
+        })
+    }
+}
+
+/// Finds hashtags and links in the text.
+pub fn facets(text: &str) -> Vec<Facet> {
+    let mut result = Vec::new();
+    for (start, word) in words(text) {
+        let word = word.trim_end_matches(is_trailing_punctuation);
+        let end = start + word.len();
+
+        if let Some(tag) = word.strip_prefix('#') {
+            let valid = tag
+                .chars()
+                .any(|c| !(c.is_numeric() || c.is_ascii_punctuation()))
+                && tag.chars().count() <= MAX_TAG_CHARS;
+            if valid {
+                result.push(Facet {
+                    start,
+                    end,
+                    feature: Feature::Tag(tag.to_owned()),
+                });
+            }
+        } else if word.starts_with("https://") || word.starts_with("http://") {
+            result.push(Facet {
+                start,
+                end,
+                feature: Feature::Link(word.to_owned()),
+            });
+        }
+    }
+
+    result
+}
+
+/// Splits the text at whitespaces; yields words with their byte offsets.
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut start = None;
+    text.char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+        .filter_map(move |(i, c)| match (start, c.is_whitespace()) {
+            (None, false) => {
+                start = Some(i);
+                None
+            }
+            (Some(begin), true) => {
+                start = None;
+                Some((begin, &text[begin..i]))
+            }
+            _ => None,
+        })
+}
+
+fn is_trailing_punctuation(c: char) -> bool {
+    matches!(c, '.' | ',' | ';' | ':' | '!' | '?' | ')' | '"' | '\'')
+}
+
+/// Converts `at://did/app.bsky.feed.post/rkey` into the web app URL.
+fn post_url(uri: &str) -> Result<String> {
+    let parts: Vec<&str> = uri
+        .strip_prefix("at://")
+        .unwrap_or_default()
+        .split('/')
+        .collect();
+
+    match parts[..] {
+        [did, "app.bsky.feed.post", rkey] => {
+            Ok(format!("https://bsky.app/profile/{did}/post/{rkey}"))
+        }
+        _ => Err(Error::InvalidResponse(format!(
+            "unexpected record URI '{uri}'"
+        ))),
+    }
+}
+
+pub struct Bluesky {
+    service_url: String,
+    identifier: String,
+    password: String,
+    retry: RetryPolicy,
+}
+
+struct Session {
+    client: Client,
+    did: String,
+}
+
+impl Bluesky {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            service_url: config.service_url.clone(),
+            identifier: config.identifier.clone(),
+            password: config.app_password.clone(),
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Uploads photos and creates a post; its id is the `at://` URI
+    /// of the record.
+    pub fn publish(&self, media: &[Media], text: &str) -> Result<Published> {
+        let errors = validate(media.len(), text);
+        if !errors.is_empty() {
+            return Err(Error::Invalid(errors.join("; ")));
+        }
+
+        for item in media {
+            let size = std::fs::metadata(&item.path)?.len();
+            if size > MAX_IMAGE_SIZE {
+                return Err(Error::Invalid(format!(
+                    "{} has {size} bytes, Bluesky accepts at most {MAX_IMAGE_SIZE}",
+                    item.path.display()
+                )));
+            }
+        }
+
+        let session = self.create_session()?;
+
+        let mut images = Vec::with_capacity(media.len());
+        for item in media {
+            let blob = self.upload_blob(&session, item)?;
+            let mut image = json!({
+                "alt": item.alt_text,
+                "image": blob,
+            });
+            if let Some((width, height)) = item.size {
+                image["aspectRatio"] = json!({"width": width, "height": height});
+            }
+
+            images.push(image);
+        }
+
+        let facets: Vec<Value> = facets(text).iter().map(Facet::to_json).collect();
+        let mut record = json!({
+            "$type": "app.bsky.feed.post",
+            "text": text,
+            "createdAt": Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            "embed": {
+                "$type": "app.bsky.embed.images",
+                "images": images,
+            },
+        });
+        if !facets.is_empty() {
+            record["facets"] = Value::Array(facets);
+        }
+
+        let uri = self.create_record(&session, record)?;
+        let url = post_url(&uri)?;
+
+        Ok(Published { id: uri, url })
+    }
+
+    fn create_session(&self) -> Result<Session> {
+        let client = Client::new(&self.service_url);
+        let body = json!({
+            "identifier": self.identifier,
+            "password": self.password,
+        });
+
+        let json = self.retry.run(Error::is_transient, || {
+            client.post_json("xrpc/com.atproto.server.createSession", &body)
+        })?;
+
+        let token = field(&json, "accessJwt")?;
+
+        Ok(Session {
+            client: client.with_bearer(&token),
+            did: field(&json, "did")?,
+        })
+    }
+
+    fn upload_blob(&self, session: &Session, media: &Media) -> Result<Value> {
+        let bytes = std::fs::read(&media.path)?;
+        let mime = content_type(&media.path);
+
+        // The blob is identified by its contents, repeating is harmless
+        let json = self.retry.run(Error::is_transient, || {
+            session
+                .client
+                .post_bytes("xrpc/com.atproto.repo.uploadBlob", mime, &bytes)
+        })?;
+
+        match json.get("blob") {
+            Some(blob) if blob.is_object() => Ok(blob.clone()),
+            _ => Err(Error::InvalidResponse("missing field 'blob'".to_owned())),
+        }
+    }
+
+    fn create_record(&self, session: &Session, record: Value) -> Result<String> {
+        let body = json!({
+            "repo": session.did,
+            "collection": "app.bsky.feed.post",
+            "record": record,
+        });
+
+        // Creating a record is not repeated, see Facebook::create_post
+        let json = self.retry.run(is_rate_limited, || {
+            session
+                .client
+                .post_json("xrpc/com.atproto.repo.createRecord", &body)
+        })?;
+
+        field(&json, "uri")
+    }
+}
+
+fn is_rate_limited(err: &Error) -> bool {
+    matches!(err, Error::Api { status: 429, .. })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::MockServer;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    const SESSION: &str = r#"{"did": "did:plc:abc", "handle": "photos.bsky.social", "accessJwt": "jwt", "refreshJwt": "refresh"}"#;
+
+    fn bluesky(url: &str) -> Bluesky {
+        let config = Config {
+            identifier: "photos.bsky.social".to_owned(),
+            app_password: "app-password".to_owned(),
+            service_url: url.to_owned(),
+        };
+
+        let retry = RetryPolicy {
+            attempts: 2,
+            delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        };
+
+        Bluesky::new(&config).with_retry(retry)
+    }
+
+    fn media(dir: &str, count: usize) -> Vec<Media> {
+        let dir = std::env::temp_dir().join(format!("socmedia-{dir}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        (0..count)
+            .map(|i| {
+                let path = dir.join(format!("photo{i}.jpg"));
+                std::fs::write(&path, format!("jpeg data {i}")).unwrap();
+                Media {
+                    rel_path: PathBuf::from(format!("photo{i}.jpg")),
+                    path,
+                    alt_text: format!("Red fox {i}"),
+                    size: (i == 0).then_some((1600, 1067)),
+                }
+            })
+            .collect()
+    }
+
+    fn cleanup(media: &[Media]) {
+        std::fs::remove_dir_all(media[0].path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_facets() {
+        let text = "🇵🇱 Żuraw #żuraw, zobacz https://example.com/żuraw.\n#123 #Grus_grus";
+        let facets = facets(text);
+
+        assert_eq!(facets.len(), 3);
+
+        assert_eq!(&text[facets[0].start..facets[0].end], "#żuraw");
+        assert_eq!((facets[0].start, facets[0].end), (16, 23));
+        assert_eq!(facets[0].feature, Feature::Tag("żuraw".to_owned()));
+
+        assert_eq!(
+            facets[1].feature,
+            Feature::Link("https://example.com/żuraw".to_owned())
+        );
+        assert_eq!(
+            &text[facets[1].start..facets[1].end],
+            "https://example.com/żuraw"
+        );
+
+        assert_eq!(&text[facets[2].start..facets[2].end], "#Grus_grus");
+        assert_eq!(facets[2].end, text.len());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(4, "text").is_empty());
+        assert_eq!(validate(0, "text").len(), 1);
+        assert_eq!(validate(5, "text").len(), 1);
+
+        // flags and Polish letters are single characters
+        assert!(validate(1, &"🇵🇱ł".repeat(150)).is_empty());
+        assert_eq!(validate(1, &"🇵🇱ł".repeat(151)).len(), 1);
+        assert_eq!(count_graphemes("👍🏽 e\u{301} 👨\u{200d}👩\u{200d}👧"), 5);
+    }
+
+    #[test]
+    fn test_publish() {
+        let server = MockServer::start(vec![
+            (200, SESSION),
+            (
+                200,
+                r#"{"blob": {"$type": "blob", "ref": {"$link": "bafk1"}, "mimeType": "image/jpeg", "size": 11}}"#,
+            ),
+            (
+                200,
+                r#"{"blob": {"$type": "blob", "ref": {"$link": "bafk2"}, "mimeType": "image/jpeg", "size": 11}}"#,
+            ),
+            (
+                200,
+                r#"{"uri": "at://did:plc:abc/app.bsky.feed.post/3kxyz", "cid": "bafyrei"}"#,
+            ),
+        ]);
+
+        let media = media("bluesky", 2);
+        let published = bluesky(&server.url)
+            .publish(&media, "🇵🇱 Lis\n\n#lis #fox")
+            .unwrap();
+        cleanup(&media);
+
+        assert_eq!(published.id, "at://did:plc:abc/app.bsky.feed.post/3kxyz");
+        assert_eq!(
+            published.url,
+            "https://bsky.app/profile/did:plc:abc/post/3kxyz"
+        );
+
+        let requests = server.finish();
+        assert_eq!(requests.len(), 4);
+
+        let session = &requests[0];
+        assert_eq!(session.path, "/xrpc/com.atproto.server.createSession");
+        let body: Value = serde_json::from_slice(&session.body).unwrap();
+        assert_eq!(body["identifier"], "photos.bsky.social");
+        assert_eq!(body["password"], "app-password");
+
+        let upload = &requests[1];
+        assert_eq!(upload.path, "/xrpc/com.atproto.repo.uploadBlob");
+        assert_eq!(upload.header("authorization"), Some("Bearer jwt"));
+        assert_eq!(upload.header("content-type"), Some("image/jpeg"));
+        assert_eq!(upload.body_str(), "jpeg data 0");
+
+        let create = &requests[3];
+        assert_eq!(create.path, "/xrpc/com.atproto.repo.createRecord");
+        let body: Value = serde_json::from_slice(&create.body).unwrap();
+        assert_eq!(body["repo"], "did:plc:abc");
+        assert_eq!(body["collection"], "app.bsky.feed.post");
+
+        let record = &body["record"];
+        assert_eq!(record["$type"], "app.bsky.feed.post");
+        assert_eq!(record["text"], "🇵🇱 Lis\n\n#lis #fox");
+        assert!(record["createdAt"].as_str().unwrap().ends_with('Z'));
+
+        let images = &record["embed"]["images"];
+        assert_eq!(images[0]["alt"], "Red fox 0");
+        assert_eq!(images[0]["image"]["ref"]["$link"], "bafk1");
+        assert_eq!(images[0]["aspectRatio"]["width"], 1600);
+        assert_eq!(images[0]["aspectRatio"]["height"], 1067);
+        assert!(images[1].get("aspectRatio").is_none());
+        assert_eq!(images[1]["image"]["ref"]["$link"], "bafk2");
+
+        let facets = &record["facets"];
+        assert_eq!(facets[0]["index"]["byteStart"], 14);
+        assert_eq!(facets[0]["index"]["byteEnd"], 18);
+        assert_eq!(facets[0]["features"][0]["tag"], "lis");
+        assert_eq!(
+            facets[1]["features"][0]["$type"],
+            "app.bsky.richtext.facet#tag"
+        );
+    }
+
+    #[test]
+    fn test_invalid_password() {
+        let server = MockServer::start(vec![(
+            401,
+            r#"{"error": "AuthenticationRequired", "message": "Invalid identifier or password"}"#,
+        )]);
+
+        let media = media("bluesky-auth", 1);
+        let err = bluesky(&server.url).publish(&media, "text").unwrap_err();
+        cleanup(&media);
+
+        assert_eq!(
+            err.to_string(),
+            "HTTP 401: AuthenticationRequired: Invalid identifier or password"
+        );
+        assert_eq!(server.finish().len(), 1);
+    }
+
+    #[test]
+    fn test_record_not_repeated() {
+        let server = MockServer::start(vec![
+            (200, SESSION),
+            (200, r#"{"blob": {"ref": {"$link": "bafk1"}}}"#),
+            (502, "Bad Gateway"),
+        ]);
+
+        let media = media("bluesky-record", 1);
+        let err = bluesky(&server.url).publish(&media, "text");
+        cleanup(&media);
+
+        assert!(matches!(err, Err(Error::Api { status: 502, .. })));
+        assert_eq!(server.finish().len(), 3);
+    }
+}
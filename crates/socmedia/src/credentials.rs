@@ -0,0 +1,148 @@
+use crate::Error;
+use crate::Result;
+use crate::bluesky;
+use crate::facebook;
+use crate::instagram;
+use crate::mastodon;
+use serde::Deserialize;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Contents of the `--socmedia` file, each platform has its own section:
+///
+/// ```toml
+/// [facebook]
+/// page_id = "1234567890"
+/// page_token = "EAAB..."
+///
+/// [instagram]
+/// user_id = "17841400000000000"
+/// access_token = "EAAB..."
+/// media_url = "https://example.com/photos"
+///
+/// [mastodon]
+/// instance_url = "https://mastodon.social"
+/// access_token = "..."
+/// visibility = "unlisted"
+///
+/// [bluesky]
+/// identifier = "photos.bsky.social"
+/// app_password = "xxxx-xxxx-xxxx-xxxx"
+/// ```
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct Credentials {
+    pub facebook: Option<facebook::Config>,
+    pub instagram: Option<instagram::Config>,
+    pub mastodon: Option<mastodon::Config>,
+    pub bluesky: Option<bluesky::Config>,
+}
+
+impl Credentials {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let path = expand_tilde(path);
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|err| Error::Config(format!("cannot read {}: {err}", path.display())))?;
+
+        Self::parse(&contents).map_err(|err| Error::Config(format!("{}: {err}", path.display())))
+    }
+
+    pub fn parse(s: &str) -> std::result::Result<Self, toml::de::Error> {
+        toml::from_str(s)
+    }
+}
+
+/// Replaces the leading `~` with the home directory.
+pub fn expand_tilde(path: &Path) -> PathBuf {
+    let Ok(rest) = path.strip_prefix("~") else {
+        return path.to_path_buf();
+    };
+
+    match std::env::var_os("HOME") {
+        Some(home) => PathBuf::from(home).join(rest),
+        None => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let credentials = Credentials::parse(
+            r#"
+            [facebook]
+            page_id = "42"
+            page_token = "secret"
+            "#,
+        )
+        .unwrap();
+
+        let facebook = credentials.facebook.unwrap();
+        assert_eq!(facebook.page_id, "42");
+        assert_eq!(facebook.page_token, "secret");
+        assert_eq!(facebook.graph_url, facebook::GRAPH_URL);
+
+        let credentials = Credentials::parse(
+            r#"
+            [instagram]
+            user_id = "17"
+            access_token = "secret"
+            media_url = "https://example.com/photos"
+            "#,
+        )
+        .unwrap();
+
+        assert!(credentials.facebook.is_none());
+        assert_eq!(credentials.instagram.unwrap().user_id, "17");
+
+        let credentials = Credentials::parse(
+            r#"
+            [mastodon]
+            instance_url = "https://pixelfed.social"
+            access_token = "secret"
+            visibility = "private"
+            max_chars = 2000
+            "#,
+        )
+        .unwrap();
+
+        let mastodon = credentials.mastodon.unwrap();
+        assert_eq!(mastodon.visibility, mastodon::Visibility::Private);
+        assert_eq!(mastodon.max_chars, 2000);
+        assert_eq!(mastodon.max_media, mastodon::MAX_MEDIA);
+
+        let credentials = Credentials::parse(
+            r#"
+            [bluesky]
+            identifier = "photos.bsky.social"
+            app_password = "secret"
+            "#,
+        )
+        .unwrap();
+
+        let bluesky = credentials.bluesky.unwrap();
+        assert_eq!(bluesky.identifier, "photos.bsky.social");
+        assert_eq!(bluesky.service_url, bluesky::SERVICE_URL);
+
+        let credentials = Credentials::parse("").unwrap();
+        assert!(credentials.facebook.is_none());
+        assert!(credentials.instagram.is_none());
+    }
+
+    #[test]
+    fn test_expand_tilde() {
+        let Some(home) = std::env::var_os("HOME") else {
+            return;
+        };
+
+        assert_eq!(
+            expand_tilde(Path::new("~/.facebook")),
+            PathBuf::from(home).join(".facebook")
+        );
+        assert_eq!(
+            expand_tilde(Path::new("/etc/~facebook")),
+            PathBuf::from("/etc/~facebook")
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Missing or malformed credentials
+    Config(String),
+    /// Post cannot be published, nothing was sent
+    Invalid(String),
+    Io(std::io::Error),
+    /// Connection problems, timeouts and so on
+    Transport(String),
+    /// The server rejected a request
+    Api {
+        status: u16,
+        code: Option<i64>,
+        message: String,
+    },
+    /// The server accepted a request, but the response makes no sense
+    InvalidResponse(String),
+}
+
+impl Error {
+    /// Tells whether repeating the request might succeed.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Transport(_) => true,
+            Self::Api { status, .. } => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::Config(msg) => write!(f, "configuration error: {msg}"),
+            Self::Invalid(msg) => f.write_str(msg),
+            Self::Io(err) => write!(f, "I/O error: {err}"),
+            Self::Transport(msg) => write!(f, "connection error: {msg}"),
+            Self::Api {
+                status,
+                code: Some(code),
+                message,
+            } => write!(f, "HTTP {status}: {message} (code {code})"),
+            Self::Api {
+                status,
+                code: None,
+                message,
+            } => write!(f, "HTTP {status}: {message}"),
+            Self::InvalidResponse(msg) => write!(f, "invalid response: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ureq::Error> for Error {
+    fn from(err: ureq::Error) -> Self {
+        Self::Transport(err.to_string())
+    }
+}
@@ -0,0 +1,350 @@
+//! Publishing on a Facebook Page using the Graph API.
+//!
+//! A post with several photos is created in two steps: first each photo
+//! is uploaded as an unpublished one, then a feed post referring to all
+//! the uploaded photos is created.
+
+use crate::Error;
+use crate::Media;
+use crate::Published;
+use crate::Result;
+use crate::RetryPolicy;
+use crate::http::Client;
+use crate::http::Multipart;
+use crate::http::field;
+use serde::Deserialize;
+
+pub const GRAPH_URL: &str = "https://graph.facebook.com/v23.0";
+
+/// Maximum number of photos attached to a single post.
+pub const MAX_PHOTOS: usize = 30;
+pub const MAX_CAPTION_CHARS: usize = 63206;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    pub page_id: String,
+    /// Page access token
+    pub page_token: String,
+    #[serde(default = "default_graph_url")]
+    pub graph_url: String,
+}
+
+fn default_graph_url() -> String {
+    GRAPH_URL.to_owned()
+}
+
+/// Checks the Facebook limits; returns the list of problems.
+pub fn validate(files: usize, caption: &str) -> Vec<String> {
+    let mut errors = Vec::new();
+    if files == 0 {
+        errors.push("post has no photos".to_owned());
+    }
+
+    if files > MAX_PHOTOS {
+        errors.push(format!(
+            "post has {files} photos, Facebook accepts at most {MAX_PHOTOS}"
+        ));
+    }
+
+    let chars = caption.chars().count();
+    if chars > MAX_CAPTION_CHARS {
+        errors.push(format!(
+            "caption has {chars} characters, Facebook accepts at most {MAX_CAPTION_CHARS}"
+        ));
+    }
+
+    errors
+}
+
+pub struct Facebook {
+    client: Client,
+    page_id: String,
+    token: String,
+    retry: RetryPolicy,
+}
+
+impl Facebook {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            client: Client::new(&config.graph_url),
+            page_id: config.page_id.clone(),
+            token: config.page_token.clone(),
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Publishes all photos in a single post.
+    pub fn publish(&self, media: &[Media], caption: &str) -> Result<Published> {
+        let errors = validate(media.len(), caption);
+        if !errors.is_empty() {
+            return Err(Error::Invalid(errors.join("; ")));
+        }
+
+        let mut photo_ids = Vec::with_capacity(media.len());
+        for item in media {
+            photo_ids.push(self.upload_photo(item)?);
+        }
+
+        let post_id = self.create_post(&photo_ids, caption)?;
+        let url = self.permalink(&post_id)?;
+
+        Ok(Published { id: post_id, url })
+    }
+
+    fn upload_photo(&self, item: &Media) -> Result<String> {
+        let endpoint = format!("{}/photos", self.page_id);
+        let json = self.retry.run(is_transient, || {
+            let mut form = Multipart::default()
+                .text("access_token", &self.token)
+                .text("published", "false");
+            if !item.alt_text.is_empty() {
+                form = form.text("alt_text_custom", &item.alt_text);
+            }
+            let form = form.file("source", &item.path)?;
+
+            self.client.post_multipart(&endpoint, form)
+        })?;
+
+        field(&json, "id")
+    }
+
+    fn create_post(&self, photo_ids: &[String], caption: &str) -> Result<String> {
+        let media: Vec<(String, String)> = photo_ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let value = serde_json::json!({ "media_fbid": id });
+                (format!("attached_media[{i}]"), value.to_string())
+            })
+            .collect();
+
+        let mut fields = vec![
+            ("access_token", self.token.as_str()),
+            ("message", caption),
+            ("published", "true"),
+        ];
+        for (key, value) in &media {
+            fields.push((key, value));
+        }
+
+        // A failed request might have been executed anyway, thus repeat
+        // only when it was for sure rejected; a duplicated post is worse
+        // than a failure.
+        let endpoint = format!("{}/feed", self.page_id);
+        let json = self
+            .retry
+            .run(is_throttled, || self.client.post_form(&endpoint, &fields))?;
+
+        field(&json, "id")
+    }
+
+    fn permalink(&self, post_id: &str) -> Result<String> {
+        let query = [
+            ("access_token", self.token.as_str()),
+            ("fields", "permalink_url"),
+        ];
+        let json = self
+            .retry
+            .run(is_transient, || self.client.get(post_id, &query))?;
+
+        field(&json, "permalink_url")
+    }
+}
+
+/// Graph API error codes meaning "try again later"
+/// (<https://developers.facebook.com/docs/graph-api/guides/error-handling>).
+const TEMPORARY_ERRORS: [i64; 2] = [1, 2];
+const THROTTLING_ERRORS: [i64; 5] = [4, 17, 32, 341, 613];
+
+fn is_transient(err: &Error) -> bool {
+    match err {
+        Error::Api {
+            code: Some(code), ..
+        } if TEMPORARY_ERRORS.contains(code) => true,
+        _ => err.is_transient() || is_throttled(err),
+    }
+}
+
+fn is_throttled(err: &Error) -> bool {
+    match err {
+        Error::Api { status: 429, .. } => true,
+        Error::Api {
+            code: Some(code), ..
+        } => THROTTLING_ERRORS.contains(code),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::MockServer;
+    use std::path::Path;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn facebook(url: &str) -> Facebook {
+        let config = Config {
+            page_id: "1234".to_owned(),
+            page_token: "token".to_owned(),
+            graph_url: url.to_owned(),
+        };
+
+        let retry = RetryPolicy {
+            attempts: 3,
+            delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        };
+
+        Facebook::new(&config).with_retry(retry)
+    }
+
+    fn photos(dir: &Path, count: usize) -> Vec<Media> {
+        std::fs::create_dir_all(dir).unwrap();
+        (0..count)
+            .map(|i| {
+                let path = dir.join(format!("photo{i}.jpg"));
+                std::fs::write(&path, format!("jpeg data {i}")).unwrap();
+                Media {
+                    rel_path: PathBuf::from(format!("photo{i}.jpg")),
+                    path,
+                    alt_text: format!("Red fox {i}"),
+                    size: None,
+                }
+            })
+            .collect()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("socmedia-{name}-{}", std::process::id()))
+    }
+
+    #[test]
+    fn test_publish() {
+        let server = MockServer::start(vec![
+            (200, r#"{"id": "101"}"#),
+            (200, r#"{"id": "102"}"#),
+            (200, r#"{"id": "1234_555"}"#),
+            (
+                200,
+                r#"{"permalink_url": "https://www.facebook.com/1234/posts/555", "id": "1234_555"}"#,
+            ),
+        ]);
+
+        let dir = temp_dir("publish");
+        let files = photos(&dir, 2);
+        let published = facebook(&server.url)
+            .publish(&files, "🇵🇱 Lis\n🇬🇧 Fox\n\n#fox")
+            .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(published.id, "1234_555");
+        assert_eq!(published.url, "https://www.facebook.com/1234/posts/555");
+
+        let requests = server.finish();
+        assert_eq!(requests.len(), 4);
+
+        for (i, request) in requests[..2].iter().enumerate() {
+            assert_eq!(request.method, "POST");
+            assert_eq!(request.path, "/1234/photos");
+            assert!(
+                request
+                    .header("content-type")
+                    .unwrap()
+                    .starts_with("multipart/form-data; boundary=")
+            );
+            let body = request.body_str();
+            assert!(body.contains("name=\"published\"\r\n\r\nfalse\r\n"));
+            assert!(body.contains(&format!("filename=\"photo{i}.jpg\"")));
+            assert!(body.contains(&format!("jpeg data {i}")));
+            assert!(body.contains(&format!("name=\"alt_text_custom\"\r\n\r\nRed fox {i}\r\n")));
+        }
+
+        let feed = &requests[2];
+        assert_eq!(feed.path, "/1234/feed");
+        assert_eq!(
+            feed.form_value("message").unwrap(),
+            "🇵🇱 Lis\n🇬🇧 Fox\n\n#fox"
+        );
+        assert_eq!(feed.form_value("access_token").unwrap(), "token");
+        assert_eq!(
+            feed.form_value("attached_media[0]").unwrap(),
+            r#"{"media_fbid":"101"}"#
+        );
+        assert_eq!(
+            feed.form_value("attached_media[1]").unwrap(),
+            r#"{"media_fbid":"102"}"#
+        );
+
+        assert_eq!(requests[3].method, "GET");
+        assert!(requests[3].path.starts_with("/1234_555?"));
+        assert!(requests[3].path.contains("fields=permalink_url"));
+    }
+
+    #[test]
+    fn test_retry_upload() {
+        let server = MockServer::start(vec![
+            (500, r#"{"error": {"message": "Unknown error", "code": 1}}"#),
+            (503, "Service Unavailable"),
+            (200, r#"{"id": "101"}"#),
+            (200, r#"{"id": "1234_555"}"#),
+            (200, r#"{"permalink_url": "https://fb.com/555"}"#),
+        ]);
+
+        let dir = temp_dir("retry");
+        let files = photos(&dir, 1);
+        let published = facebook(&server.url).publish(&files, "caption").unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(published.url, "https://fb.com/555");
+        assert_eq!(server.finish().len(), 5);
+    }
+
+    #[test]
+    fn test_feed_not_repeated() {
+        let server = MockServer::start(vec![
+            (200, r#"{"id": "101"}"#),
+            (500, r#"{"error": {"message": "Internal error"}}"#),
+        ]);
+
+        let dir = temp_dir("feed");
+        let files = photos(&dir, 1);
+        let err = facebook(&server.url).publish(&files, "caption");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(err, Err(Error::Api { status: 500, .. })));
+        assert_eq!(server.finish().len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_token() {
+        let server = MockServer::start(vec![(
+            400,
+            r#"{"error": {"message": "Invalid OAuth access token.", "type": "OAuthException", "code": 190}}"#,
+        )]);
+
+        let dir = temp_dir("token");
+        let files = photos(&dir, 1);
+        let err = facebook(&server.url)
+            .publish(&files, "caption")
+            .unwrap_err();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            err.to_string(),
+            "HTTP 400: Invalid OAuth access token. (code 190)"
+        );
+        assert_eq!(server.finish().len(), 1);
+    }
+
+    #[test]
+    fn test_no_photos() {
+        let err = facebook("http://127.0.0.1:1").publish(&[], "caption");
+        assert!(matches!(err, Err(Error::Invalid(_))));
+    }
+}
@@ -0,0 +1,352 @@
+use crate::Error;
+use crate::Result;
+use serde_json::Value;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+use ureq::Agent;
+use ureq::Body;
+use ureq::RequestBuilder;
+use ureq::http::Response;
+
+const TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How many times and how often a failed request is repeated.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub attempts: u32,
+    /// Delay after the first failure; doubled after each next failure
+    pub delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 4,
+            delay: Duration::from_secs(2),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        Self {
+            attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn run<T>(
+        &self,
+        should_retry: impl Fn(&Error) -> bool,
+        mut op: impl FnMut() -> Result<T>,
+    ) -> Result<T> {
+        let mut delay = self.delay;
+        let mut attempt = 1;
+        loop {
+            match op() {
+                Err(err) if attempt < self.attempts && should_retry(&err) => {
+                    thread::sleep(delay);
+                    delay = (delay * 2).min(self.max_delay);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+// --------------------------------------------------
+
+/// Thin wrapper over HTTP agent, talking JSON.
+pub(crate) struct Client {
+    agent: Agent,
+    base_url: String,
+    authorization: Option<String>,
+}
+
+impl Client {
+    pub fn new(base_url: &str) -> Self {
+        let agent = Agent::config_builder()
+            .http_status_as_error(false)
+            .timeout_global(Some(TIMEOUT))
+            .build()
+            .into();
+
+        Self {
+            agent,
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            authorization: None,
+        }
+    }
+
+    /// Sends the token in the `Authorization` header of each request.
+    pub fn with_bearer(mut self, token: &str) -> Self {
+        self.authorization = Some(format!("Bearer {token}"));
+        self
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+    }
+
+    fn authorize<B>(&self, request: RequestBuilder<B>) -> RequestBuilder<B> {
+        match &self.authorization {
+            Some(value) => request.header("Authorization", value),
+            None => request,
+        }
+    }
+
+    pub fn get(&self, path: &str, query: &[(&str, &str)]) -> Result<Value> {
+        let request = self
+            .agent
+            .get(self.url(path))
+            .query_pairs(query.iter().copied());
+
+        response(self.authorize(request).call())
+    }
+
+    pub fn post_form(&self, path: &str, fields: &[(&str, &str)]) -> Result<Value> {
+        self.post_form_with(path, &[], fields)
+    }
+
+    pub fn post_form_with(
+        &self,
+        path: &str,
+        headers: &[(&str, &str)],
+        fields: &[(&str, &str)],
+    ) -> Result<Value> {
+        let mut request = self.authorize(self.agent.post(self.url(path)));
+        for (key, value) in headers {
+            request = request.header(*key, *value);
+        }
+
+        response(request.send_form(fields.iter().copied()))
+    }
+
+    pub fn post_json(&self, path: &str, body: &Value) -> Result<Value> {
+        let request = self
+            .agent
+            .post(self.url(path))
+            .content_type("application/json");
+
+        response(self.authorize(request).send(body.to_string()))
+    }
+
+    /// Sends raw bytes, like an image, as the request body.
+    pub fn post_bytes(&self, path: &str, content_type: &str, bytes: &[u8]) -> Result<Value> {
+        let request = self.agent.post(self.url(path)).content_type(content_type);
+
+        response(self.authorize(request).send(bytes))
+    }
+
+    pub fn post_multipart(&self, path: &str, form: Multipart) -> Result<Value> {
+        let request = self
+            .agent
+            .post(self.url(path))
+            .content_type(form.content_type());
+
+        response(self.authorize(request).send(&form.finish()[..]))
+    }
+}
+
+fn response(result: std::result::Result<Response<Body>, ureq::Error>) -> Result<Value> {
+    let mut response = result?;
+    let status = response.status().as_u16();
+    let text = response.body_mut().read_to_string()?;
+    let json = serde_json::from_str::<Value>(&text);
+
+    if (200..300).contains(&status) {
+        return json.map_err(|err| Error::InvalidResponse(err.to_string()));
+    }
+
+    let (code, message) = match json {
+        Ok(json) => api_error(&json),
+        Err(_) => (None, None),
+    };
+
+    Err(Error::Api {
+        status,
+        code,
+        message: message.unwrap_or_else(|| text.chars().take(200).collect()),
+    })
+}
+
+/// Extracts the error details; most APIs return either
+/// `{"error": {"message": ..., "code": ...}}` or
+/// `{"error": "...", "message": ...}`.
+fn api_error(json: &Value) -> (Option<i64>, Option<String>) {
+    let error = &json["error"];
+    if error.is_object() {
+        let code = error["code"].as_i64();
+        let message = error["message"].as_str().map(str::to_owned);
+
+        return (code, message);
+    }
+
+    let message = match (error.as_str(), json["message"].as_str()) {
+        (Some(error), Some(message)) => Some(format!("{error}: {message}")),
+        (Some(error), None) => Some(error.to_owned()),
+        (None, message) => message.map(str::to_owned),
+    };
+
+    (None, message)
+}
+
+/// Gets the string field of a JSON object.
+pub(crate) fn field(json: &Value, name: &str) -> Result<String> {
+    match &json[name] {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        _ => Err(Error::InvalidResponse(format!("missing field '{name}'"))),
+    }
+}
+
+// --------------------------------------------------
+
+const BOUNDARY: &str = "----publish-photos-4f6c2a1e9b";
+
+/// Body of `multipart/form-data` request.
+#[derive(Default)]
+pub(crate) struct Multipart {
+    body: Vec<u8>,
+}
+
+impl Multipart {
+    pub fn text(mut self, name: &str, value: &str) -> Self {
+        self.header(name, None, None);
+        self.body.extend_from_slice(value.as_bytes());
+        self.body.extend_from_slice(b"\r\n");
+
+        self
+    }
+
+    pub fn file(mut self, name: &str, path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path)?;
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        self.header(name, Some(&filename), Some(content_type(path)));
+        self.body.extend_from_slice(&bytes);
+        self.body.extend_from_slice(b"\r\n");
+
+        Ok(self)
+    }
+
+    fn header(&mut self, name: &str, filename: Option<&str>, content_type: Option<&str>) {
+        let mut header = format!("--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"{name}\"");
+        if let Some(filename) = filename {
+            header.push_str(&format!("; filename=\"{}\"", filename.replace('"', "_")));
+        }
+        header.push_str("\r\n");
+        if let Some(content_type) = content_type {
+            header.push_str(&format!("Content-Type: {content_type}\r\n"));
+        }
+        header.push_str("\r\n");
+
+        self.body.extend_from_slice(header.as_bytes());
+    }
+
+    fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={BOUNDARY}")
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.body
+            .extend_from_slice(format!("--{BOUNDARY}--\r\n").as_bytes());
+
+        self.body
+    }
+}
+
+pub(crate) fn content_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match ext.as_str() {
+        "png" => "image/png",
+        "webp" => "image/webp",
+        _ => "image/jpeg",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::MockServer;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_retry_policy() {
+        let policy = RetryPolicy {
+            attempts: 3,
+            delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        };
+
+        let calls = Cell::new(0);
+        let result: Result<()> = policy.run(Error::is_transient, || {
+            calls.set(calls.get() + 1);
+            Err(Error::Transport("reset".to_owned()))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 3);
+
+        calls.set(0);
+        let result: Result<()> = policy.run(Error::is_transient, || {
+            calls.set(calls.get() + 1);
+            Err(Error::Invalid("no".to_owned()))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+
+        calls.set(0);
+        let result = policy.run(Error::is_transient, || {
+            calls.set(calls.get() + 1);
+            if calls.get() < 2 {
+                Err(Error::Transport("reset".to_owned()))
+            } else {
+                Ok(42)
+            }
+        });
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn test_api_errors() {
+        let server = MockServer::start(vec![
+            (
+                400,
+                r#"{"error": {"message": "Invalid token", "code": 190}}"#,
+            ),
+            (
+                401,
+                r#"{"error": "unauthorized", "message": "bad password"}"#,
+            ),
+            (502, "Bad Gateway"),
+        ]);
+
+        let client = Client::new(&server.url);
+        let errors: Vec<String> = (0..3)
+            .map(|_| client.get("/me", &[("fields", "id")]).unwrap_err())
+            .map(|err| err.to_string())
+            .collect();
+
+        assert_eq!(errors[0], "HTTP 400: Invalid token (code 190)");
+        assert_eq!(errors[1], "HTTP 401: unauthorized: bad password");
+        assert_eq!(errors[2], "HTTP 502: Bad Gateway");
+
+        let requests = server.finish();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].method, "GET");
+        assert_eq!(requests[0].path, "/me?fields=id");
+    }
+}
@@ -0,0 +1,400 @@
+//! Publishing on Instagram using the Content Publishing API.
+//!
+//! Instagram does not accept uploads, it downloads photos from public
+//! URLs, thus the photos directory has to be mirrored on a web server;
+//! `media_url` is the URL of that mirror. A post is published in steps:
+//! each photo gets an item container, then the carousel container with
+//! the caption is created, and once Instagram finishes processing it,
+//! the carousel gets published.
+
+use crate::Error;
+use crate::Media;
+use crate::Published;
+use crate::Result;
+use crate::RetryPolicy;
+use crate::http::Client;
+use crate::http::field;
+use serde::Deserialize;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+pub const GRAPH_URL: &str = "https://graph.facebook.com/v23.0";
+
+pub const MAX_ITEMS: usize = 10;
+pub const MAX_HASHTAGS: usize = 30;
+pub const MAX_CAPTION_CHARS: usize = 2200;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    /// Instagram professional account id
+    pub user_id: String,
+    pub access_token: String,
+    /// Public URL of the photos directory
+    pub media_url: String,
+    #[serde(default = "default_graph_url")]
+    pub graph_url: String,
+}
+
+fn default_graph_url() -> String {
+    GRAPH_URL.to_owned()
+}
+
+/// Checks the Instagram limits; returns the list of problems.
+pub fn validate(files: usize, caption: &str) -> Vec<String> {
+    let mut errors = Vec::new();
+    if files == 0 {
+        errors.push("post has no photos".to_owned());
+    }
+
+    if files > MAX_ITEMS {
+        errors.push(format!(
+            "post has {files} photos, Instagram accepts at most {MAX_ITEMS}"
+        ));
+    }
+
+    let hashtags = count_hashtags(caption);
+    if hashtags > MAX_HASHTAGS {
+        errors.push(format!(
+            "caption has {hashtags} hashtags, Instagram accepts at most {MAX_HASHTAGS}"
+        ));
+    }
+
+    let chars = caption.chars().count();
+    if chars > MAX_CAPTION_CHARS {
+        errors.push(format!(
+            "caption has {chars} characters, Instagram accepts at most {MAX_CAPTION_CHARS}"
+        ));
+    }
+
+    errors
+}
+
+fn count_hashtags(caption: &str) -> usize {
+    caption
+        .split_whitespace()
+        .filter(|word| word.len() > 1 && word.starts_with('#'))
+        .count()
+}
+
+pub struct Instagram {
+    client: Client,
+    user_id: String,
+    token: String,
+    media_url: String,
+    retry: RetryPolicy,
+    poll_interval: Duration,
+    max_polls: usize,
+}
+
+impl Instagram {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            client: Client::new(&config.graph_url),
+            user_id: config.user_id.clone(),
+            token: config.access_token.clone(),
+            media_url: config.media_url.trim_end_matches('/').to_owned(),
+            retry: RetryPolicy::default(),
+            poll_interval: Duration::from_secs(3),
+            max_polls: 40,
+        }
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_polling(mut self, interval: Duration, max_polls: usize) -> Self {
+        self.poll_interval = interval;
+        self.max_polls = max_polls;
+        self
+    }
+
+    /// Publishes photos as a carousel; they are downloaded from
+    /// `media_url` joined with their relative paths. A single photo is
+    /// published as a regular post.
+    pub fn publish(&self, media: &[Media], caption: &str) -> Result<Published> {
+        let errors = validate(media.len(), caption);
+        if !errors.is_empty() {
+            return Err(Error::Invalid(errors.join("; ")));
+        }
+
+        let container_id = if let [item] = media {
+            let image_url = self.image_url(&item.rel_path);
+            let mut params = vec![("image_url", image_url.as_str()), ("caption", caption)];
+            if !item.alt_text.is_empty() {
+                params.push(("alt_text", &item.alt_text));
+            }
+
+            self.create_container(&params)?
+        } else {
+            let mut children = Vec::with_capacity(media.len());
+            for item in media {
+                let image_url = self.image_url(&item.rel_path);
+                let mut params = vec![
+                    ("image_url", image_url.as_str()),
+                    ("is_carousel_item", "true"),
+                ];
+                if !item.alt_text.is_empty() {
+                    params.push(("alt_text", &item.alt_text));
+                }
+
+                children.push(self.create_container(&params)?);
+            }
+
+            self.create_container(&[
+                ("media_type", "CAROUSEL"),
+                ("children", &children.join(",")),
+                ("caption", caption),
+            ])?
+        };
+
+        self.wait_until_finished(&container_id)?;
+
+        let media_id = self.publish_container(&container_id)?;
+        let url = self.permalink(&media_id)?;
+
+        Ok(Published { id: media_id, url })
+    }
+
+    fn image_url(&self, path: &Path) -> String {
+        let mut url = self.media_url.clone();
+        for component in path.components() {
+            url.push('/');
+            url.push_str(&encode(&component.as_os_str().to_string_lossy()));
+        }
+
+        url
+    }
+
+    fn create_container(&self, params: &[(&str, &str)]) -> Result<String> {
+        let mut fields = vec![("access_token", self.token.as_str())];
+        fields.extend_from_slice(params);
+
+        // A container which is never published is harmless, hence
+        // repeating is safe.
+        let endpoint = format!("{}/media", self.user_id);
+        let json = self.retry.run(Error::is_transient, || {
+            self.client.post_form(&endpoint, &fields)
+        })?;
+
+        field(&json, "id")
+    }
+
+    fn wait_until_finished(&self, container_id: &str) -> Result<()> {
+        let query = [
+            ("access_token", self.token.as_str()),
+            ("fields", "status_code,status"),
+        ];
+
+        for _ in 0..self.max_polls {
+            let json = self.retry.run(Error::is_transient, || {
+                self.client.get(container_id, &query)
+            })?;
+
+            match field(&json, "status_code")?.as_str() {
+                "FINISHED" | "PUBLISHED" => return Ok(()),
+                "IN_PROGRESS" => thread::sleep(self.poll_interval),
+                status => {
+                    let details = json["status"].as_str().unwrap_or_default();
+                    return Err(Error::InvalidResponse(format!(
+                        "container {container_id} has status {status} {details}"
+                    )));
+                }
+            }
+        }
+
+        Err(Error::Transport(format!(
+            "container {container_id} was not processed in time"
+        )))
+    }
+
+    fn publish_container(&self, container_id: &str) -> Result<String> {
+        let fields = [
+            ("access_token", self.token.as_str()),
+            ("creation_id", container_id),
+        ];
+
+        // Publishing is not repeated, see Facebook::create_post
+        let endpoint = format!("{}/media_publish", self.user_id);
+        let json = self.retry.run(is_rate_limited, || {
+            self.client.post_form(&endpoint, &fields)
+        })?;
+
+        field(&json, "id")
+    }
+
+    fn permalink(&self, media_id: &str) -> Result<String> {
+        let query = [
+            ("access_token", self.token.as_str()),
+            ("fields", "permalink"),
+        ];
+        let json = self
+            .retry
+            .run(Error::is_transient, || self.client.get(media_id, &query))?;
+
+        field(&json, "permalink")
+    }
+}
+
+fn is_rate_limited(err: &Error) -> bool {
+    matches!(err, Error::Api { status: 429, .. })
+}
+
+/// Percent-encodes a path segment.
+fn encode(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                result.push(byte as char)
+            }
+            _ => result.push_str(&format!("%{byte:02X}")),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::MockServer;
+    use std::path::PathBuf;
+
+    fn instagram(url: &str) -> Instagram {
+        let config = Config {
+            user_id: "1789".to_owned(),
+            access_token: "token".to_owned(),
+            media_url: "https://example.com/photos/".to_owned(),
+            graph_url: url.to_owned(),
+        };
+
+        let retry = RetryPolicy {
+            attempts: 2,
+            delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        };
+
+        Instagram::new(&config)
+            .with_retry(retry)
+            .with_polling(Duration::ZERO, 3)
+    }
+
+    fn files(count: usize) -> Vec<Media> {
+        (0..count)
+            .map(|i| Media {
+                path: PathBuf::from(format!("/photos/2025/lis rudy {i}.jpg")),
+                rel_path: PathBuf::from(format!("2025/lis rudy {i}.jpg")),
+                alt_text: format!("Red fox {i}"),
+                size: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(3, "#fox #vulpes").is_empty());
+        assert_eq!(validate(0, "").len(), 1);
+        assert_eq!(validate(11, "").len(), 1);
+
+        let tags: Vec<String> = (0..31).map(|i| format!("#tag{i}")).collect();
+        assert!(validate(1, &tags[..30].join(" ")).is_empty());
+        assert_eq!(
+            validate(1, &tags.join(" ")),
+            vec!["caption has 31 hashtags, Instagram accepts at most 30"]
+        );
+
+        // characters, not bytes
+        assert!(validate(1, &"ż".repeat(2200)).is_empty());
+        assert_eq!(validate(1, &"ż".repeat(2201)).len(), 1);
+    }
+
+    #[test]
+    fn test_publish_carousel() {
+        let server = MockServer::start(vec![
+            (200, r#"{"id": "c1"}"#),
+            (200, r#"{"id": "c2"}"#),
+            (200, r#"{"id": "carousel"}"#),
+            (200, r#"{"status_code": "IN_PROGRESS", "id": "carousel"}"#),
+            (200, r#"{"status_code": "FINISHED", "id": "carousel"}"#),
+            (200, r#"{"id": "media42"}"#),
+            (
+                200,
+                r#"{"permalink": "https://www.instagram.com/p/ABC/", "id": "media42"}"#,
+            ),
+        ]);
+
+        let published = instagram(&server.url)
+            .publish(&files(2), "🇵🇱 Lis\n\n#fox")
+            .unwrap();
+        assert_eq!(published.id, "media42");
+        assert_eq!(published.url, "https://www.instagram.com/p/ABC/");
+
+        let requests = server.finish();
+        assert_eq!(requests.len(), 7);
+
+        assert_eq!(requests[0].path, "/1789/media");
+        assert_eq!(
+            requests[0].form_value("image_url").unwrap(),
+            "https://example.com/photos/2025/lis%20rudy%200.jpg"
+        );
+        assert_eq!(requests[0].form_value("is_carousel_item").unwrap(), "true");
+        assert_eq!(requests[0].form_value("alt_text").unwrap(), "Red fox 0");
+        assert_eq!(requests[1].form_value("alt_text").unwrap(), "Red fox 1");
+        assert!(requests[0].form_value("caption").is_none());
+
+        let carousel = &requests[2];
+        assert_eq!(carousel.form_value("media_type").unwrap(), "CAROUSEL");
+        assert_eq!(carousel.form_value("children").unwrap(), "c1,c2");
+        assert_eq!(carousel.form_value("caption").unwrap(), "🇵🇱 Lis\n\n#fox");
+
+        assert!(requests[3].path.starts_with("/carousel?"));
+        assert_eq!(requests[5].path, "/1789/media_publish");
+        assert_eq!(requests[5].form_value("creation_id").unwrap(), "carousel");
+        assert!(requests[6].path.starts_with("/media42?"));
+    }
+
+    #[test]
+    fn test_publish_single() {
+        let server = MockServer::start(vec![
+            (200, r#"{"id": "c1"}"#),
+            (200, r#"{"status_code": "FINISHED"}"#),
+            (200, r#"{"id": "media42"}"#),
+            (200, r#"{"permalink": "https://www.instagram.com/p/ABC/"}"#),
+        ]);
+
+        let published = instagram(&server.url)
+            .publish(&files(1), "caption")
+            .unwrap();
+        assert_eq!(published.url, "https://www.instagram.com/p/ABC/");
+
+        let requests = server.finish();
+        assert_eq!(requests[0].form_value("caption").unwrap(), "caption");
+        assert!(requests[0].form_value("media_type").is_none());
+    }
+
+    #[test]
+    fn test_container_error() {
+        let server = MockServer::start(vec![
+            (200, r#"{"id": "c1"}"#),
+            (
+                200,
+                r#"{"status_code": "ERROR", "status": "Error: Media download has failed."}"#,
+            ),
+        ]);
+
+        let err = instagram(&server.url)
+            .publish(&files(1), "caption")
+            .unwrap_err();
+        assert!(err.to_string().contains("Media download has failed"));
+        assert_eq!(server.finish().len(), 2);
+    }
+
+    #[test]
+    fn test_limits_checked_before_upload() {
+        let err = instagram("http://127.0.0.1:1").publish(&files(11), "caption");
+        assert!(matches!(err, Err(Error::Invalid(_))));
+    }
+}
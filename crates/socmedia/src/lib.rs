@@ -0,0 +1,37 @@
+pub mod bluesky;
+mod credentials;
+mod error;
+pub mod facebook;
+mod http;
+pub mod instagram;
+pub mod mastodon;
+#[cfg(test)]
+mod mock;
+
+pub use credentials::Credentials;
+pub use credentials::expand_tilde;
+pub use error::Error;
+pub use http::RetryPolicy;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+use std::path::PathBuf;
+
+/// Photo attached to a post.
+#[derive(Debug, Clone)]
+pub struct Media {
+    pub path: PathBuf,
+    /// Path relative to the photos directory
+    pub rel_path: PathBuf,
+    pub alt_text: String,
+    /// Width and height in pixels, if known
+    pub size: Option<(u16, u16)>,
+}
+
+/// Post created on a platform.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Published {
+    /// Identifier assigned by the platform
+    pub id: String,
+    pub url: String,
+}
@@ -0,0 +1,441 @@
+//! Publishing on Mastodon or any server implementing its API, like Pixelfed.
+
+use crate::Error;
+use crate::Media;
+use crate::Published;
+use crate::Result;
+use crate::RetryPolicy;
+use crate::http::Client;
+use crate::http::Multipart;
+use crate::http::field;
+use serde::Deserialize;
+use serde::Serialize;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::thread;
+use std::time::Duration;
+
+pub const MAX_MEDIA: usize = 4;
+pub const MAX_CHARS: usize = 500;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    /// URL of the server, like `https://mastodon.social`
+    pub instance_url: String,
+    pub access_token: String,
+    #[serde(default)]
+    pub visibility: Visibility,
+    /// Default content warning
+    #[serde(default)]
+    pub content_warning: String,
+    /// Servers might have different limits than the vanilla Mastodon
+    #[serde(default = "default_max_chars")]
+    pub max_chars: usize,
+    #[serde(default = "default_max_media")]
+    pub max_media: usize,
+}
+
+fn default_max_chars() -> usize {
+    MAX_CHARS
+}
+
+fn default_max_media() -> usize {
+    MAX_MEDIA
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    #[default]
+    Public,
+    Unlisted,
+    /// Followers only
+    Private,
+    /// Mentioned users only
+    Direct,
+}
+
+impl Visibility {
+    pub const ALL: [Visibility; 4] = [Self::Public, Self::Unlisted, Self::Private, Self::Direct];
+
+    const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Public => "public",
+            Self::Unlisted => "unlisted",
+            Self::Private => "private",
+            Self::Direct => "direct",
+        }
+    }
+}
+
+impl Display for Visibility {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Private => f.write_str("followers only"),
+            _ => f.write_str(self.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct StatusOptions {
+    /// Content warning (spoiler text); empty means none
+    pub content_warning: String,
+    pub visibility: Visibility,
+}
+
+impl Config {
+    /// Checks the server limits; returns the list of problems.
+    pub fn validate(&self, files: usize, caption: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        if files == 0 {
+            errors.push("post has no photos".to_owned());
+        }
+
+        if files > self.max_media {
+            errors.push(format!(
+                "post has {files} photos, the server accepts at most {}",
+                self.max_media
+            ));
+        }
+
+        let chars = format_caption(caption).chars().count();
+        if chars > self.max_chars {
+            errors.push(format!(
+                "status has {chars} characters, the server accepts at most {}",
+                self.max_chars
+            ));
+        }
+
+        errors
+    }
+
+    pub fn default_options(&self) -> StatusOptions {
+        StatusOptions {
+            content_warning: self.content_warning.clone(),
+            visibility: self.visibility,
+        }
+    }
+}
+
+/// Rewrites hashtags to the form Mastodon recognises: only letters, digits
+/// and underscores; multi-word tags are written in CamelCase, which is
+/// friendly for screen readers.
+pub fn format_caption(caption: &str) -> String {
+    caption
+        .split('\n')
+        .map(|line| {
+            line.split(' ')
+                .map(|word| match word.strip_prefix('#') {
+                    Some(tag) if !tag.is_empty() => format!("#{}", hashtag(tag)),
+                    _ => word.to_owned(),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn hashtag(tag: &str) -> String {
+    let words: Vec<&str> = tag
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    if words.len() == 1 {
+        return words[0].to_owned();
+    }
+
+    let mut result = String::new();
+    for word in words {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            result.extend(first.to_uppercase());
+            result.push_str(chars.as_str());
+        }
+    }
+
+    result
+}
+
+pub struct Mastodon {
+    config: Config,
+    client: Client,
+    retry: RetryPolicy,
+    poll_interval: Duration,
+    max_polls: usize,
+}
+
+impl Mastodon {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            config: config.clone(),
+            client: Client::new(&config.instance_url).with_bearer(&config.access_token),
+            retry: RetryPolicy::default(),
+            poll_interval: Duration::from_secs(2),
+            max_polls: 30,
+        }
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_polling(mut self, interval: Duration, max_polls: usize) -> Self {
+        self.poll_interval = interval;
+        self.max_polls = max_polls;
+        self
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Uploads photos and creates a status.
+    pub fn publish(
+        &self,
+        media: &[Media],
+        caption: &str,
+        options: &StatusOptions,
+    ) -> Result<Published> {
+        let errors = self.config.validate(media.len(), caption);
+        if !errors.is_empty() {
+            return Err(Error::Invalid(errors.join("; ")));
+        }
+
+        let mut media_ids = Vec::with_capacity(media.len());
+        for item in media {
+            media_ids.push(self.upload(item)?);
+        }
+
+        self.create_status(&media_ids, &format_caption(caption), options)
+    }
+
+    fn upload(&self, media: &Media) -> Result<String> {
+        let json = self.retry.run(Error::is_transient, || {
+            let form = Multipart::default()
+                .file("file", &media.path)?
+                .text("description", &media.alt_text);
+
+            self.client.post_multipart("api/v2/media", form)
+        })?;
+
+        let id = field(&json, "id")?;
+
+        // Large files are processed asynchronously, a status cannot refer
+        // to them until they are ready
+        if json["url"].is_null() {
+            self.wait_until_processed(&id)?;
+        }
+
+        Ok(id)
+    }
+
+    fn wait_until_processed(&self, id: &str) -> Result<()> {
+        let path = format!("api/v1/media/{id}");
+        for _ in 0..self.max_polls {
+            thread::sleep(self.poll_interval);
+
+            let json = self
+                .retry
+                .run(Error::is_transient, || self.client.get(&path, &[]))?;
+            if !json["url"].is_null() {
+                return Ok(());
+            }
+        }
+
+        Err(Error::Transport(format!(
+            "media {id} was not processed in time"
+        )))
+    }
+
+    fn create_status(
+        &self,
+        media_ids: &[String],
+        status: &str,
+        options: &StatusOptions,
+    ) -> Result<Published> {
+        let mut fields = vec![
+            ("status", status),
+            ("visibility", options.visibility.as_str()),
+        ];
+        if !options.content_warning.is_empty() {
+            fields.push(("spoiler_text", &options.content_warning));
+            fields.push(("sensitive", "true"));
+        }
+        for id in media_ids {
+            fields.push(("media_ids[]", id));
+        }
+
+        // The server ignores repeated requests having the same key, thus
+        // retrying does not create duplicated statuses
+        let key = format!("publish-photos-{}", media_ids.join("-"));
+        let headers = [("Idempotency-Key", key.as_str())];
+
+        let json = self.retry.run(Error::is_transient, || {
+            self.client
+                .post_form_with("api/v1/statuses", &headers, &fields)
+        })?;
+
+        Ok(Published {
+            id: field(&json, "id")?,
+            url: field(&json, "url")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::MockServer;
+    use std::path::PathBuf;
+
+    fn config(url: &str) -> Config {
+        Config {
+            instance_url: url.to_owned(),
+            access_token: "token".to_owned(),
+            visibility: Visibility::Public,
+            content_warning: String::new(),
+            max_chars: MAX_CHARS,
+            max_media: MAX_MEDIA,
+        }
+    }
+
+    fn mastodon(url: &str) -> Mastodon {
+        let retry = RetryPolicy {
+            attempts: 2,
+            delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        };
+
+        Mastodon::new(&config(url))
+            .with_retry(retry)
+            .with_polling(Duration::ZERO, 3)
+    }
+
+    fn media(dir: &str, count: usize) -> Vec<Media> {
+        let dir = std::env::temp_dir().join(format!("socmedia-{dir}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        (0..count)
+            .map(|i| {
+                let path = dir.join(format!("photo{i}.jpg"));
+                std::fs::write(&path, format!("jpeg data {i}")).unwrap();
+                Media {
+                    rel_path: PathBuf::from(format!("photo{i}.jpg")),
+                    path,
+                    alt_text: format!("Red fox {i}"),
+                    size: None,
+                }
+            })
+            .collect()
+    }
+
+    fn cleanup(media: &[Media]) {
+        let dir: PathBuf = media[0].path.parent().unwrap().to_path_buf();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_format_caption() {
+        assert_eq!(
+            format_caption("🇵🇱 Lis #nie-tag\n\n#red-fox #lis #żuraw_zwyczajny #big cat #"),
+            "🇵🇱 Lis #NieTag\n\n#RedFox #lis #żuraw_zwyczajny #big cat #"
+        );
+        assert_eq!(format_caption("#dzięcioł.duży"), "#DzięciołDuży");
+    }
+
+    #[test]
+    fn test_validate() {
+        let config = config("http://localhost");
+        assert!(config.validate(4, "text").is_empty());
+        assert_eq!(config.validate(5, "text").len(), 1);
+        assert_eq!(config.validate(1, &"ą".repeat(501)).len(), 1);
+    }
+
+    #[test]
+    fn test_publish() {
+        let server = MockServer::start(vec![
+            (200, r#"{"id": "m1", "url": "https://files/m1.jpg"}"#),
+            (202, r#"{"id": "m2", "url": null}"#),
+            (206, r#"{"id": "m2", "url": null}"#),
+            (200, r#"{"id": "m2", "url": "https://files/m2.jpg"}"#),
+            (
+                200,
+                r#"{"id": "1099", "url": "https://mastodon.example/@photos/1099"}"#,
+            ),
+        ]);
+
+        let media = media("mastodon", 2);
+        let options = StatusOptions {
+            content_warning: "spiders".to_owned(),
+            visibility: Visibility::Unlisted,
+        };
+        let published = mastodon(&server.url)
+            .publish(&media, "Pająk #wolf-spider", &options)
+            .unwrap();
+        cleanup(&media);
+
+        assert_eq!(published.id, "1099");
+        assert_eq!(published.url, "https://mastodon.example/@photos/1099");
+
+        let requests = server.finish();
+        assert_eq!(requests.len(), 5);
+
+        let upload = &requests[0];
+        assert_eq!(upload.path, "/api/v2/media");
+        assert_eq!(upload.header("authorization"), Some("Bearer token"));
+        let body = upload.body_str();
+        assert!(body.contains("name=\"description\"\r\n\r\nRed fox 0\r\n"));
+        assert!(body.contains("filename=\"photo0.jpg\""));
+
+        assert_eq!(requests[2].method, "GET");
+        assert_eq!(requests[2].path, "/api/v1/media/m2");
+
+        let status = &requests[4];
+        assert_eq!(status.path, "/api/v1/statuses");
+        assert_eq!(
+            status.header("idempotency-key"),
+            Some("publish-photos-m1-m2")
+        );
+        assert_eq!(status.form_value("status").unwrap(), "Pająk #WolfSpider");
+        assert_eq!(status.form_value("visibility").unwrap(), "unlisted");
+        assert_eq!(status.form_value("spoiler_text").unwrap(), "spiders");
+        let ids: Vec<String> = status
+            .form()
+            .into_iter()
+            .filter(|(key, _)| key == "media_ids[]")
+            .map(|(_, value)| value)
+            .collect();
+        assert_eq!(ids, vec!["m1", "m2"]);
+    }
+
+    #[test]
+    fn test_status_retried() {
+        let server = MockServer::start(vec![
+            (200, r#"{"id": "m1", "url": "https://files/m1.jpg"}"#),
+            (503, "Service Unavailable"),
+            (
+                200,
+                r#"{"id": "1", "url": "https://mastodon.example/@photos/1"}"#,
+            ),
+        ]);
+
+        let media = media("mastodon-retry", 1);
+        let published = mastodon(&server.url)
+            .publish(&media, "text", &StatusOptions::default())
+            .unwrap();
+        cleanup(&media);
+
+        assert_eq!(published.url, "https://mastodon.example/@photos/1");
+
+        let requests = server.finish();
+        assert_eq!(
+            requests[1].header("idempotency-key"),
+            requests[2].header("idempotency-key")
+        );
+        assert!(requests[2].form_value("spoiler_text").is_none());
+        assert_eq!(requests[2].form_value("visibility").unwrap(), "public");
+    }
+}